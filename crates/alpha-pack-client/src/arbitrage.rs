@@ -0,0 +1,216 @@
+//! Instruction builders for `arbitrage_executor`.
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use arbitrage_executor::{accounts, instruction, ExchangeType, TradingPair};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: arbitrage_executor::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Initialize the arbitrage executor global state (protocol admin only)
+pub fn initialize_arbitrage_state(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeArbitrageState {
+            global_state: pda::arbitrage_state().0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeArbitrageState {},
+    )
+}
+
+/// Create the arbitrage bot for `pack`
+pub fn initialize_arbitrage_bot(
+    pack_leader: &Pubkey,
+    pack: &Pubkey,
+    max_trade_amount: u64,
+    min_profit_threshold: u64,
+    max_slippage: u16,
+    enabled_pairs: Vec<TradingPair>,
+) -> Instruction {
+    build(
+        accounts::InitializeArbitrageBot {
+            arbitrage_bot: pda::arbitrage_bot(pack).0,
+            pack: *pack,
+            core_state: pda::global_state().0,
+            pack_leader: *pack_leader,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeArbitrageBot {
+            max_trade_amount,
+            min_profit_threshold,
+            max_slippage,
+            enabled_pairs,
+        },
+    )
+}
+
+/// Enable or disable a pack's bot
+pub fn set_bot_status(authority: &Pubkey, pack: &Pubkey, is_active: bool) -> Instruction {
+    build(
+        accounts::SetBotStatus {
+            arbitrage_bot: pda::arbitrage_bot(pack).0,
+            pack: *pack,
+            authority: *authority,
+        },
+        instruction::SetBotStatus { is_active },
+    )
+}
+
+/// Publish an opportunity; `opportunity_index` must equal `GlobalArbitrageState.total_opportunities`
+pub fn create_opportunity(
+    creator: &Pubkey,
+    opportunity_index: u64,
+    token_a: Pubkey,
+    token_b: Pubkey,
+    exchange_a: ExchangeType,
+    exchange_b: ExchangeType,
+    price_a: u64,
+    price_b: u64,
+    liquidity_a: u64,
+    liquidity_b: u64,
+    gas_cost: u64,
+) -> Instruction {
+    build(
+        accounts::CreateOpportunity {
+            opportunity: pda::opportunity(opportunity_index).0,
+            global_state: pda::arbitrage_state().0,
+            core_state: pda::global_state().0,
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        instruction::CreateOpportunity {
+            opportunity_index,
+            token_a,
+            token_b,
+            exchange_a,
+            exchange_b,
+            price_a,
+            price_b,
+            liquidity_a,
+            liquidity_b,
+            gas_cost,
+        },
+    )
+}
+
+/// Execute an opportunity with `pack`'s bot; `bot_trade_index` must equal `ArbitrageBot.total_trades`
+pub fn execute_arbitrage(
+    executor: &Pubkey,
+    opportunity: &Pubkey,
+    pack: &Pubkey,
+    bot_trade_index: u64,
+    amount: u64,
+) -> Instruction {
+    let arbitrage_bot = pda::arbitrage_bot(pack).0;
+    build(
+        accounts::ExecuteArbitrage {
+            opportunity: *opportunity,
+            arbitrage_bot,
+            trade_record: pda::arbitrage_trade(&arbitrage_bot, bot_trade_index).0,
+            core_state: pda::global_state().0,
+            executor: *executor,
+            system_program: system_program::ID,
+        },
+        instruction::ExecuteArbitrage { amount },
+    )
+}
+
+/// Update a bot's limits and enabled pairs
+pub fn update_bot_settings(
+    authority: &Pubkey,
+    pack: &Pubkey,
+    max_trade_amount: Option<u64>,
+    min_profit_threshold: Option<u64>,
+    max_slippage: Option<u16>,
+    enabled_pairs: Option<Vec<TradingPair>>,
+) -> Instruction {
+    build(
+        accounts::UpdateBotSettings {
+            arbitrage_bot: pda::arbitrage_bot(pack).0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::UpdateBotSettings {
+            max_trade_amount,
+            min_profit_threshold,
+            max_slippage,
+            enabled_pairs,
+        },
+    )
+}
+
+/// Mark an opportunity as expired
+pub fn close_opportunity(authority: &Pubkey, opportunity: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseOpportunity {
+            opportunity: *opportunity,
+            authority: *authority,
+        },
+        instruction::CloseOpportunity {},
+    )
+}
+
+/// Halt all arbitrage bots (admin only)
+pub fn emergency_stop(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::EmergencyStop {
+            global_state: pda::arbitrage_state().0,
+            authority: *authority,
+        },
+        instruction::EmergencyStop {},
+    )
+}
+
+/// Propose `new_authority` as arbitrage admin; `None` cancels the proposal
+pub fn propose_admin_authority(authority: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::ProposeAdminAuthority {
+            global_state: pda::arbitrage_state().0,
+            authority: *authority,
+        },
+        instruction::ProposeAdminAuthority { new_authority },
+    )
+}
+
+/// Accept a pending arbitrage admin transfer
+pub fn accept_admin_authority(new_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdminAuthority {
+            global_state: pda::arbitrage_state().0,
+            new_authority: *new_authority,
+        },
+        instruction::AcceptAdminAuthority {},
+    )
+}
+
+/// Require `threshold` of `approvers` to sign arbitrage admin actions
+pub fn configure_admin_approvals(authority: &Pubkey, approvers: Vec<Pubkey>, threshold: u8) -> Instruction {
+    build(
+        accounts::ConfigureAdminApprovals {
+            global_state: pda::arbitrage_state().0,
+            authority: *authority,
+        },
+        instruction::ConfigureAdminApprovals { approvers, threshold },
+    )
+}
+
+/// Upgrade the v1 arbitrage global state to the current layout
+pub fn migrate_arbitrage_state(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateArbitrageState {
+            global_state: pda::arbitrage_state().0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateArbitrageState {},
+    )
+}
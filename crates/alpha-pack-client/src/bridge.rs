@@ -0,0 +1,220 @@
+//! Instruction builders for `cross_chain_bridge`.
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token;
+use cross_chain_bridge::{accounts, instruction, ChainInfo, SourceChain, TargetChain};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: cross_chain_bridge::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Initialize the bridge configuration
+pub fn initialize_bridge(
+    authority: &Pubkey,
+    supported_chains: Vec<ChainInfo>,
+    bridge_fee_bps: u16,
+    min_transfer_amount: u64,
+    max_transfer_amount: u64,
+) -> Instruction {
+    build(
+        accounts::InitializeBridge {
+            bridge: pda::bridge().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeBridge {
+            supported_chains,
+            bridge_fee_bps,
+            min_transfer_amount,
+            max_transfer_amount,
+        },
+    )
+}
+
+/// Lock tokens for an outgoing transfer; `transfer_index` must equal `CrossChainBridge.total_transfers`
+pub fn initiate_transfer(
+    initiator: &Pubkey,
+    user_token_account: &Pubkey,
+    bridge_vault: &Pubkey,
+    transfer_index: u64,
+    target_chain: TargetChain,
+    target_address: String,
+    amount: u64,
+    token_symbol: String,
+) -> Instruction {
+    build(
+        accounts::InitiateTransfer {
+            transfer: pda::transfer(transfer_index).0,
+            bridge: pda::bridge().0,
+            user: pda::user(initiator).0,
+            user_token_account: *user_token_account,
+            bridge_vault: *bridge_vault,
+            core_state: pda::global_state().0,
+            initiator: *initiator,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitiateTransfer {
+            transfer_index,
+            target_chain,
+            target_address,
+            amount,
+            token_symbol,
+        },
+    )
+}
+
+/// Settle an outgoing transfer, refunding `user_token_account` on failure
+pub fn complete_transfer(
+    relayer: &Pubkey,
+    transfer: &Pubkey,
+    user_token_account: &Pubkey,
+    bridge_vault: &Pubkey,
+    tx_hash: String,
+    success: bool,
+) -> Instruction {
+    build(
+        accounts::CompleteTransfer {
+            transfer: *transfer,
+            bridge: pda::bridge().0,
+            trade_record: pda::bridge_trade(transfer).0,
+            user_token_account: *user_token_account,
+            bridge_vault: *bridge_vault,
+            core_state: pda::global_state().0,
+            relayer: *relayer,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CompleteTransfer { tx_hash, success },
+    )
+}
+
+/// Release tokens for a transfer arriving from another chain
+pub fn process_incoming_transfer(
+    relayer: &Pubkey,
+    recipient_token_account: &Pubkey,
+    bridge_vault: &Pubkey,
+    source_chain: SourceChain,
+    source_tx_hash: String,
+    recipient: Pubkey,
+    amount: u64,
+    token_symbol: String,
+    nonce: u64,
+) -> Instruction {
+    build(
+        accounts::ProcessIncomingTransfer {
+            incoming_transfer: pda::incoming_transfer(nonce).0,
+            bridge: pda::bridge().0,
+            recipient_token_account: *recipient_token_account,
+            bridge_vault: *bridge_vault,
+            core_state: pda::global_state().0,
+            relayer: *relayer,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ProcessIncomingTransfer {
+            source_chain,
+            source_tx_hash,
+            recipient,
+            amount,
+            token_symbol,
+            nonce,
+        },
+    )
+}
+
+/// Add a chain to the bridge (bridge authority only)
+pub fn add_supported_chain(authority: &Pubkey, chain_info: ChainInfo) -> Instruction {
+    build(
+        accounts::AddSupportedChain {
+            bridge: pda::bridge().0,
+            authority: *authority,
+        },
+        instruction::AddSupportedChain { chain_info },
+    )
+}
+
+/// Update fee and transfer limits (bridge authority only)
+pub fn update_bridge_settings(
+    authority: &Pubkey,
+    bridge_fee_bps: Option<u16>,
+    min_transfer_amount: Option<u64>,
+    max_transfer_amount: Option<u64>,
+    is_active: Option<bool>,
+) -> Instruction {
+    build(
+        accounts::UpdateBridgeSettings {
+            bridge: pda::bridge().0,
+            authority: *authority,
+        },
+        instruction::UpdateBridgeSettings {
+            bridge_fee_bps,
+            min_transfer_amount,
+            max_transfer_amount,
+            is_active,
+        },
+    )
+}
+
+/// Deactivate the bridge (bridge authority only)
+pub fn emergency_pause(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::EmergencyPause {
+            bridge: pda::bridge().0,
+            authority: *authority,
+        },
+        instruction::EmergencyPause {},
+    )
+}
+
+/// Propose `new_authority` as bridge authority; `None` cancels the proposal
+pub fn propose_admin_authority(authority: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::ProposeAdminAuthority {
+            bridge: pda::bridge().0,
+            authority: *authority,
+        },
+        instruction::ProposeAdminAuthority { new_authority },
+    )
+}
+
+/// Accept a pending bridge authority transfer
+pub fn accept_admin_authority(new_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdminAuthority {
+            bridge: pda::bridge().0,
+            new_authority: *new_authority,
+        },
+        instruction::AcceptAdminAuthority {},
+    )
+}
+
+/// Require `threshold` of `approvers` to sign bridge admin actions
+pub fn configure_admin_approvals(authority: &Pubkey, approvers: Vec<Pubkey>, threshold: u8) -> Instruction {
+    build(
+        accounts::ConfigureAdminApprovals {
+            bridge: pda::bridge().0,
+            authority: *authority,
+        },
+        instruction::ConfigureAdminApprovals { approvers, threshold },
+    )
+}
+
+/// Upgrade the v1 bridge account to the current layout
+pub fn migrate_bridge(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateBridge {
+            bridge: pda::bridge().0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateBridge {},
+    )
+}
@@ -0,0 +1,187 @@
+//! Instruction builders for `competition_engine`.
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::token;
+use competition_engine::{accounts, instruction, CompetitionType, TournamentType};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: competition_engine::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Initialize the competition engine global state (protocol admin only)
+pub fn initialize_competition_state(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeCompetitionState {
+            competition_state: pda::competition_state().0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeCompetitionState {},
+    )
+}
+
+/// Create a competition; `competition_index` must equal `CompetitionState.total_competitions`
+pub fn create_competition(
+    authority: &Pubkey,
+    competition_index: u64,
+    name: String,
+    description: String,
+    competition_type: CompetitionType,
+    entry_fee: u64,
+    prize_pool: u64,
+    max_participants: u32,
+    duration_hours: u32,
+) -> Instruction {
+    build(
+        accounts::CreateCompetition {
+            competition: pda::competition(competition_index).0,
+            competition_state: pda::competition_state().0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateCompetition {
+            competition_index,
+            name,
+            description,
+            competition_type,
+            entry_fee,
+            prize_pool,
+            max_participants,
+            duration_hours,
+        },
+    )
+}
+
+/// Register `pack` for a competition, paying the entry fee from `pack_treasury`
+pub fn register_for_competition(
+    pack_leader: &Pubkey,
+    competition: &Pubkey,
+    pack: &Pubkey,
+    pack_treasury: &Pubkey,
+    competition_treasury: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::RegisterForCompetition {
+            competition: *competition,
+            pack: *pack,
+            pack_treasury: *pack_treasury,
+            competition_treasury: *competition_treasury,
+            core_state: pda::global_state().0,
+            pack_leader: *pack_leader,
+            token_program: token::ID,
+        },
+        instruction::RegisterForCompetition {},
+    )
+}
+
+/// Start a competition (competition authority only)
+pub fn start_competition(authority: &Pubkey, competition: &Pubkey) -> Instruction {
+    build(
+        accounts::StartCompetition {
+            competition: *competition,
+            core_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::StartCompetition {},
+    )
+}
+
+/// Push a pack score update
+pub fn update_pack_score(
+    score_oracle: &Pubkey,
+    competition: &Pubkey,
+    pack: Pubkey,
+    score_delta: i64,
+    trade_volume: u64,
+    profit_loss: i64,
+    social_score_delta: u64,
+) -> Instruction {
+    build(
+        accounts::UpdatePackScore {
+            competition: *competition,
+            core_state: pda::global_state().0,
+            score_oracle: *score_oracle,
+        },
+        instruction::UpdatePackScore {
+            pack,
+            score_delta,
+            trade_volume,
+            profit_loss,
+            social_score_delta,
+        },
+    )
+}
+
+/// End a competition (competition authority only)
+pub fn end_competition(authority: &Pubkey, competition: &Pubkey) -> Instruction {
+    build(
+        accounts::EndCompetition {
+            competition: *competition,
+            core_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::EndCompetition {},
+    )
+}
+
+/// Create a tournament; `tournament_index` must equal `CompetitionState.total_tournaments`
+pub fn create_tournament(
+    authority: &Pubkey,
+    tournament_index: u64,
+    name: String,
+    description: String,
+    tournament_type: TournamentType,
+    rounds: u8,
+    entry_fee: u64,
+) -> Instruction {
+    build(
+        accounts::CreateTournament {
+            tournament: pda::tournament(tournament_index).0,
+            competition_state: pda::competition_state().0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateTournament {
+            tournament_index,
+            name,
+            description,
+            tournament_type,
+            rounds,
+            entry_fee,
+        },
+    )
+}
+
+/// Advance a tournament to its next round
+pub fn advance_tournament_round(authority: &Pubkey, tournament: &Pubkey) -> Instruction {
+    build(
+        accounts::AdvanceTournamentRound {
+            tournament: *tournament,
+            core_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::AdvanceTournamentRound {},
+    )
+}
+
+/// Upgrade a v1 Competition account to the current layout
+pub fn migrate_competition(payer: &Pubkey, competition: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateCompetition {
+            competition: *competition,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateCompetition {},
+    )
+}
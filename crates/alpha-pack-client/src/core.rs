@@ -0,0 +1,921 @@
+//! Instruction builders for `alpha_pack_core`.
+
+use alpha_pack_core::{
+    accounts, instruction, LeaderboardScope, RankingMetric, RecruitmentMode, RiskLevel, Role, StrategyFocus, TradeType,
+};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::pda;
+
+/// Signer and session key for an instruction sent by `authority` itself or by its `delegate`
+pub fn signer_and_session(authority: &Pubkey, delegate: Option<Pubkey>) -> (Pubkey, Option<Pubkey>) {
+    match delegate {
+        Some(delegate) => (delegate, Some(pda::session_key(&pda::user(authority).0, &delegate).0)),
+        None => (*authority, None),
+    }
+}
+
+/// Current-epoch stats accounts for `authority`'s user and, when it is in one, its pack
+pub fn epoch_stats(authority: &Pubkey, pack: Option<Pubkey>, epoch: u64) -> (Pubkey, Option<Pubkey>) {
+    (
+        pda::user_epoch_stats(&pda::user(authority).0, epoch).0,
+        pack.map(|pack| pda::pack_epoch_stats(&pack, epoch).0),
+    )
+}
+
+/// Trade account or TradeLog for a recorded trade; a logged trade gets no account of its own
+pub fn trade_storage(authority: &Pubkey, trade_index: u64, use_trade_log: bool) -> (Option<Pubkey>, Option<Pubkey>) {
+    if use_trade_log {
+        (None, Some(pda::trade_log(&pda::user(authority).0).0))
+    } else {
+        (Some(pda::trade(authority, trade_index).0), None)
+    }
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Initialize the protocol global state
+pub fn initialize(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::Initialize {
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::Initialize {},
+    )
+}
+
+/// Create the user profile owned by `authority`
+pub fn create_user(authority: &Pubkey, username: String, wallet_address: Pubkey) -> Instruction {
+    build(
+        accounts::CreateUser {
+            user: pda::user(authority).0,
+            username_record: pda::username(&username).0,
+            user_counters: pda::user_counters(authority).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateUser { username, wallet_address },
+    )
+}
+
+/// Move the user from `old_username` to `new_username` in the registry
+pub fn rename_user(authority: &Pubkey, old_username: &str, new_username: String) -> Instruction {
+    build(
+        accounts::RenameUser {
+            user: pda::user(authority).0,
+            old_username_record: pda::username(old_username).0,
+            new_username_record: pda::username(&new_username).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::RenameUser { new_username },
+    )
+}
+
+/// Update the payout wallet and/or metadata URI; `None` leaves a field unchanged
+pub fn update_user_profile(
+    authority: &Pubkey,
+    wallet_address: Option<Pubkey>,
+    metadata_uri: Option<String>,
+) -> Instruction {
+    build(
+        accounts::UpdateUserProfile {
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::UpdateUserProfile {
+            wallet_address,
+            metadata_uri,
+        },
+    )
+}
+
+/// Nominate `new_authority` for the user; `None` cancels a pending rotation
+pub fn rotate_authority(authority: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::RotateAuthority {
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::RotateAuthority { new_authority },
+    )
+}
+
+/// Accept a rotation from `old_authority`; the user must not be in a pack, hold session keys or
+/// have trades, a trade log or a social score
+pub fn accept_authority(new_authority: &Pubkey, old_authority: &Pubkey, username: &str) -> Instruction {
+    let old_user = pda::user(old_authority).0;
+    build(
+        accounts::AcceptAuthority {
+            old_user,
+            trade_log: pda::trade_log(&old_user).0,
+            new_user: pda::user(new_authority).0,
+            username_record: pda::username(username).0,
+            global_leaderboard: pda::leaderboard(&LeaderboardScope::Global).0,
+            global_state: pda::global_state().0,
+            new_authority: *new_authority,
+            system_program: system_program::ID,
+        },
+        instruction::AcceptAuthority {},
+    )
+}
+
+/// Close the user and release `username`; the user must not be in a pack or hold session keys
+pub fn close_user(authority: &Pubkey, username: &str) -> Instruction {
+    build(
+        accounts::CloseUser {
+            user: pda::user(authority).0,
+            user_counters: pda::user_counters(authority).0,
+            username_record: pda::username(username).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CloseUser {},
+    )
+}
+
+/// Authorise `delegate` for the instructions in `allowed_instructions` (see `SessionKey` bits)
+pub fn create_session_key(
+    authority: &Pubkey,
+    delegate: Pubkey,
+    allowed_instructions: u8,
+    duration_seconds: i64,
+    spend_cap: u64,
+) -> Instruction {
+    let user = pda::user(authority).0;
+    build(
+        accounts::CreateSessionKey {
+            session_key: pda::session_key(&user, &delegate).0,
+            user,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateSessionKey {
+            delegate,
+            allowed_instructions,
+            duration_seconds,
+            spend_cap,
+        },
+    )
+}
+
+/// Revoke `delegate`'s session key
+pub fn revoke_session_key(authority: &Pubkey, delegate: &Pubkey) -> Instruction {
+    let user = pda::user(authority).0;
+    build(
+        accounts::RevokeSessionKey {
+            session_key: pda::session_key(&user, delegate).0,
+            user,
+            authority: *authority,
+        },
+        instruction::RevokeSessionKey {},
+    )
+}
+
+/// Create a pack; `pack_index` must equal the creator's `User.packs_created`
+pub fn create_pack(
+    authority: &Pubkey,
+    pack_index: u64,
+    name: String,
+    description: String,
+    max_members: u8,
+    strategy_focus: StrategyFocus,
+    risk_level: RiskLevel,
+) -> Instruction {
+    build(
+        accounts::CreatePack {
+            pack: pda::pack(authority, pack_index).0,
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreatePack {
+            pack_index,
+            name,
+            description,
+            max_members,
+            strategy_focus,
+            risk_level,
+        },
+    )
+}
+
+/// Join an existing pack
+pub fn join_pack(authority: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::JoinPack {
+            pack: *pack,
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::JoinPack {},
+    )
+}
+
+/// Set how new members may join the pack (leader only)
+pub fn set_recruitment_policy(
+    leader: &Pubkey,
+    pack: &Pubkey,
+    recruitment_mode: RecruitmentMode,
+    min_social_score: u64,
+    min_win_rate: u8,
+) -> Instruction {
+    build(
+        accounts::SetRecruitmentPolicy {
+            pack: *pack,
+            global_state: pda::global_state().0,
+            leader: *leader,
+        },
+        instruction::SetRecruitmentPolicy {
+            recruitment_mode,
+            min_social_score,
+            min_win_rate,
+        },
+    )
+}
+
+/// Invite `invitee` to the pack for `duration_seconds` (leader only)
+pub fn invite_member(leader: &Pubkey, pack: &Pubkey, invitee: &Pubkey, duration_seconds: i64) -> Instruction {
+    build(
+        accounts::InviteMember {
+            invite: pda::invite(pack, invitee).0,
+            pack: *pack,
+            invitee: pda::user(invitee).0,
+            global_state: pda::global_state().0,
+            leader: *leader,
+            system_program: system_program::ID,
+        },
+        instruction::InviteMember { duration_seconds },
+    )
+}
+
+/// Join a pack through a pending invite; the invite rent goes back to `inviter`
+pub fn accept_invite(authority: &Pubkey, pack: &Pubkey, inviter: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptInvite {
+            invite: pda::invite(pack, authority).0,
+            pack: *pack,
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            inviter: *inviter,
+            authority: *authority,
+        },
+        instruction::AcceptInvite {},
+    )
+}
+
+/// Apply to a pack that recruits by application
+pub fn apply_to_pack(authority: &Pubkey, pack: &Pubkey, duration_seconds: i64) -> Instruction {
+    build(
+        accounts::ApplyToPack {
+            application: pda::application(pack, authority).0,
+            pack: *pack,
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::ApplyToPack { duration_seconds },
+    )
+}
+
+/// Accept `applicant` into the pack (leader only)
+pub fn approve_application(leader: &Pubkey, pack: &Pubkey, applicant: &Pubkey) -> Instruction {
+    build(
+        accounts::ApproveApplication {
+            application: pda::application(pack, applicant).0,
+            pack: *pack,
+            applicant_user: pda::user(applicant).0,
+            global_state: pda::global_state().0,
+            applicant: *applicant,
+            leader: *leader,
+        },
+        instruction::ApproveApplication {},
+    )
+}
+
+/// Decline `applicant`'s application (leader only)
+pub fn reject_application(leader: &Pubkey, pack: &Pubkey, applicant: &Pubkey) -> Instruction {
+    build(
+        accounts::RejectApplication {
+            application: pda::application(pack, applicant).0,
+            pack: *pack,
+            global_state: pda::global_state().0,
+            applicant: *applicant,
+            leader: *leader,
+        },
+        instruction::RejectApplication {},
+    )
+}
+
+/// Withdraw the invite of `invitee` to `pack`; signed by the inviter, the pack leader or the invitee
+pub fn cancel_invite(authority: &Pubkey, pack: &Pubkey, invitee: &Pubkey, inviter: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelInvite {
+            invite: pda::invite(pack, invitee).0,
+            pack: *pack,
+            global_state: pda::global_state().0,
+            inviter: *inviter,
+            authority: *authority,
+        },
+        instruction::CancelInvite {},
+    )
+}
+
+/// Close an expired invite; anyone may sign and the rent goes back to `inviter`
+pub fn close_expired_invite(closer: &Pubkey, pack: &Pubkey, invitee: &Pubkey, inviter: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseExpiredInvite {
+            invite: pda::invite(pack, invitee).0,
+            global_state: pda::global_state().0,
+            inviter: *inviter,
+            closer: *closer,
+        },
+        instruction::CloseExpiredInvite {},
+    )
+}
+
+/// Withdraw the applicant's pending application to `pack`
+pub fn withdraw_application(applicant: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::WithdrawApplication {
+            application: pda::application(pack, applicant).0,
+            global_state: pda::global_state().0,
+            applicant: *applicant,
+        },
+        instruction::WithdrawApplication {},
+    )
+}
+
+/// Close an expired application; anyone may sign and the rent goes back to `applicant`
+pub fn close_expired_application(closer: &Pubkey, pack: &Pubkey, applicant: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseExpiredApplication {
+            application: pda::application(pack, applicant).0,
+            global_state: pda::global_state().0,
+            applicant: *applicant,
+            closer: *closer,
+        },
+        instruction::CloseExpiredApplication {},
+    )
+}
+
+/// Leave the user's current pack
+pub fn leave_pack(authority: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::LeavePack {
+            pack: *pack,
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::LeavePack {},
+    )
+}
+
+/// Remove `member` from the pack (leader only)
+pub fn remove_member(leader: &Pubkey, pack: &Pubkey, member: &Pubkey) -> Instruction {
+    build(
+        accounts::RemoveMember {
+            pack: *pack,
+            member: pda::user(member).0,
+            global_state: pda::global_state().0,
+            leader: *leader,
+        },
+        instruction::RemoveMember {},
+    )
+}
+
+/// Hand pack leadership to `new_leader` (leader only)
+pub fn transfer_leadership(leader: &Pubkey, pack: &Pubkey, new_leader: &Pubkey) -> Instruction {
+    build(
+        accounts::TransferLeadership {
+            pack: *pack,
+            new_leader: pda::user(new_leader).0,
+            global_state: pda::global_state().0,
+            leader: *leader,
+        },
+        instruction::TransferLeadership {},
+    )
+}
+
+/// Close a pack whose only member is its leader, refunding rent to the leader
+pub fn disband_pack(leader: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::DisbandPack {
+            pack: *pack,
+            leader_user: pda::user(leader).0,
+            global_state: pda::global_state().0,
+            leader: *leader,
+        },
+        instruction::DisbandPack {},
+    )
+}
+
+/// Self-reported trade; `trade_index` must equal `User.total_trades`. With `delegate` set the
+/// delegate signs and pays through its session key. With `use_trade_log` the trade is appended
+/// to the user's TradeLog instead of getting its own Trade account.
+pub fn record_trade(
+    authority: &Pubkey,
+    delegate: Option<Pubkey>,
+    pack: Option<Pubkey>,
+    use_trade_log: bool,
+    trade_index: u64,
+    epoch: u64,
+    trade_type: TradeType,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+    from_amount: u64,
+    to_amount: u64,
+    fee: u64,
+    signature: String,
+) -> Instruction {
+    let (signer, session_key) = signer_and_session(authority, delegate);
+    let (trade, trade_log) = trade_storage(authority, trade_index, use_trade_log);
+    let (user_epoch_stats, pack_epoch_stats) = epoch_stats(authority, pack, epoch);
+    build(
+        accounts::RecordTrade {
+            trade,
+            trade_log,
+            user: pda::user(authority).0,
+            pack,
+            session_key,
+            user_epoch_stats,
+            pack_epoch_stats,
+            global_state: pda::global_state().0,
+            from_mint: *from_mint,
+            to_mint: *to_mint,
+            from_price: pda::price_feed(from_mint).0,
+            to_price: pda::price_feed(to_mint).0,
+            authority: signer,
+            system_program: system_program::ID,
+        },
+        instruction::RecordTrade {
+            trade_index,
+            epoch,
+            trade_type,
+            from_amount,
+            to_amount,
+            fee,
+            signature,
+        },
+    )
+}
+
+/// Trade co-signed by the registered trade attestor
+pub fn record_attested_trade(
+    authority: &Pubkey,
+    delegate: Option<Pubkey>,
+    attestor: &Pubkey,
+    pack: Option<Pubkey>,
+    use_trade_log: bool,
+    trade_index: u64,
+    epoch: u64,
+    trade_type: TradeType,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+    from_amount: u64,
+    to_amount: u64,
+    fee: u64,
+    signature: String,
+) -> Instruction {
+    let (signer, session_key) = signer_and_session(authority, delegate);
+    let (trade, trade_log) = trade_storage(authority, trade_index, use_trade_log);
+    let (user_epoch_stats, pack_epoch_stats) = epoch_stats(authority, pack, epoch);
+    build(
+        accounts::RecordAttestedTrade {
+            trade,
+            trade_log,
+            user: pda::user(authority).0,
+            pack,
+            session_key,
+            user_epoch_stats,
+            pack_epoch_stats,
+            global_state: pda::global_state().0,
+            from_mint: *from_mint,
+            to_mint: *to_mint,
+            from_price: pda::price_feed(from_mint).0,
+            to_price: pda::price_feed(to_mint).0,
+            attestor: *attestor,
+            authority: signer,
+            system_program: system_program::ID,
+        },
+        instruction::RecordAttestedTrade {
+            trade_index,
+            epoch,
+            trade_type,
+            from_amount,
+            to_amount,
+            fee,
+            signature,
+        },
+    )
+}
+
+/// Snapshot balances ahead of a swap; must be followed by `record_settled_trade` in the same transaction
+pub fn begin_trade_settlement(
+    authority: &Pubkey,
+    from_token_account: &Pubkey,
+    to_token_account: &Pubkey,
+) -> Instruction {
+    build(
+        accounts::BeginTradeSettlement {
+            settlement: pda::settlement(authority).0,
+            user: pda::user(authority).0,
+            global_state: pda::global_state().0,
+            from_token_account: *from_token_account,
+            to_token_account: *to_token_account,
+            instructions: sysvar::instructions::ID,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::BeginTradeSettlement {},
+    )
+}
+
+/// Close a settlement and record the trade from the observed balance deltas
+pub fn record_settled_trade(
+    authority: &Pubkey,
+    from_token_account: &Pubkey,
+    to_token_account: &Pubkey,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+    pack: Option<Pubkey>,
+    use_trade_log: bool,
+    trade_index: u64,
+    epoch: u64,
+    trade_type: TradeType,
+    signature: String,
+) -> Instruction {
+    let (trade, trade_log) = trade_storage(authority, trade_index, use_trade_log);
+    let (user_epoch_stats, pack_epoch_stats) = epoch_stats(authority, pack, epoch);
+    build(
+        accounts::RecordSettledTrade {
+            trade,
+            trade_log,
+            settlement: pda::settlement(authority).0,
+            user: pda::user(authority).0,
+            pack,
+            user_epoch_stats,
+            pack_epoch_stats,
+            global_state: pda::global_state().0,
+            from_token_account: *from_token_account,
+            to_token_account: *to_token_account,
+            from_mint: *from_mint,
+            to_mint: *to_mint,
+            from_price: pda::price_feed(from_mint).0,
+            to_price: pda::price_feed(to_mint).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::RecordSettledTrade {
+            trade_index,
+            epoch,
+            trade_type,
+            signature,
+        },
+    )
+}
+
+/// Create `authority`'s TradeLog ring buffer
+pub fn create_trade_log(authority: &Pubkey) -> Instruction {
+    let user = pda::user(authority).0;
+    build(
+        accounts::CreateTradeLog {
+            trade_log: pda::trade_log(&user).0,
+            user,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateTradeLog {},
+    )
+}
+
+/// Close one of `authority`'s Trade accounts, refunding its rent
+pub fn close_trade(authority: &Pubkey, trade_index: u64) -> Instruction {
+    build(
+        accounts::CloseTrade {
+            trade: pda::trade(authority, trade_index).0,
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::CloseTrade { trade_index },
+    )
+}
+
+/// Configure verified-only recording and the settlement allow-list (admin only)
+pub fn configure_trade_verification(
+    authority: &Pubkey,
+    verified_trades_only: bool,
+    settlement_programs: Vec<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::ConfigureTradeVerification {
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::ConfigureTradeVerification {
+            verified_trades_only,
+            settlement_programs,
+        },
+    )
+}
+
+/// Assign or clear a protocol role (admin only)
+pub fn set_role(authority: &Pubkey, role: Role, account: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetRole {
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::SetRole { role, account },
+    )
+}
+
+/// Create the price feed for `mint`; `price` is in quote units per whole token
+pub fn create_price_feed(price_oracle: &Pubkey, mint: &Pubkey, price: u64) -> Instruction {
+    build(
+        accounts::CreatePriceFeed {
+            price_feed: pda::price_feed(mint).0,
+            mint: *mint,
+            global_state: pda::global_state().0,
+            price_oracle: *price_oracle,
+            system_program: system_program::ID,
+        },
+        instruction::CreatePriceFeed { price },
+    )
+}
+
+/// Publish a new price for `mint`
+pub fn update_price_feed(price_oracle: &Pubkey, mint: &Pubkey, price: u64) -> Instruction {
+    build(
+        accounts::UpdatePriceFeed {
+            price_feed: pda::price_feed(mint).0,
+            global_state: pda::global_state().0,
+            price_oracle: *price_oracle,
+        },
+        instruction::UpdatePriceFeed { price },
+    )
+}
+
+/// Reward (positive `score_delta`) or penalise `authority`'s user (score oracle only); the
+/// oracle also pays for the epoch stats accounts if this is their first update of `epoch`
+pub fn update_social_score(
+    score_oracle: &Pubkey,
+    authority: &Pubkey,
+    pack: Option<Pubkey>,
+    epoch: u64,
+    score_delta: i64,
+) -> Instruction {
+    let (user_epoch_stats, pack_epoch_stats) = epoch_stats(authority, pack, epoch);
+    build(
+        accounts::UpdateSocialScore {
+            user: pda::user(authority).0,
+            pack,
+            user_epoch_stats,
+            pack_epoch_stats,
+            global_state: pda::global_state().0,
+            scorer: *score_oracle,
+            payer: *score_oracle,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateSocialScore { epoch, score_delta },
+    )
+}
+
+/// Change the stats epoch length in seconds (admin only)
+pub fn set_epoch_length(authority: &Pubkey, epoch_length: i64) -> Instruction {
+    build(
+        accounts::SetEpochLength {
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::SetEpochLength { epoch_length },
+    )
+}
+
+/// Create the leaderboard for `scope`; pass `pack` when a pack leader creates its member ranking
+pub fn create_leaderboard(
+    authority: &Pubkey,
+    scope: LeaderboardScope,
+    metric: RankingMetric,
+    pack: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::CreateLeaderboard {
+            leaderboard: pda::leaderboard(&scope).0,
+            pack,
+            global_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateLeaderboard { scope, metric },
+    )
+}
+
+/// Re-rank the leaderboard for `scope` by `metric`, clearing its entries
+pub fn set_leaderboard_metric(
+    authority: &Pubkey,
+    scope: LeaderboardScope,
+    metric: RankingMetric,
+    pack: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::SetLeaderboardMetric {
+            leaderboard: pda::leaderboard(&scope).0,
+            pack,
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::SetLeaderboardMetric { metric },
+    )
+}
+
+/// Re-score up to `Leaderboard::MAX_BATCH` accounts: User PDAs, or Pack addresses for `LeaderboardScope::Packs`
+pub fn crank_leaderboard(cranker: &Pubkey, scope: LeaderboardScope, subjects: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::CrankLeaderboard {
+            leaderboard: pda::leaderboard(&scope).0,
+            global_state: pda::global_state().0,
+            cranker: *cranker,
+        },
+        instruction::CrankLeaderboard {},
+    );
+    ix.accounts.extend(subjects.iter().map(|subject| AccountMeta::new(*subject, false)));
+    ix
+}
+
+/// Drop up to `Leaderboard::MAX_BATCH` entries whose accounts were closed, whose pack disbanded, or
+/// who left the pack a pack leaderboard ranks
+pub fn evict_leaderboard_entries(cranker: &Pubkey, scope: LeaderboardScope, entries: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::EvictLeaderboardEntries {
+            leaderboard: pda::leaderboard(&scope).0,
+            global_state: pda::global_state().0,
+            cranker: *cranker,
+        },
+        instruction::EvictLeaderboardEntries {},
+    );
+    ix.accounts.extend(entries.iter().map(|entry| AccountMeta::new_readonly(*entry, false)));
+    ix
+}
+
+/// Upgrade a v1 GlobalState account to the current layout
+pub fn migrate_global_state(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateGlobalState {
+            global_state: pda::global_state().0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateGlobalState {},
+    )
+}
+
+/// Upgrade `authority`'s v1 User account to the current layout
+pub fn migrate_user(payer: &Pubkey, authority: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateUser {
+            user: pda::user(authority).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateUser {},
+    )
+}
+
+/// Upgrade a v1 Pack account to the current layout
+pub fn migrate_pack(payer: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::MigratePack {
+            pack: *pack,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigratePack {},
+    )
+}
+
+/// Mirror a treasury value onto the pack; normally sent by pack_manager via CPI
+pub fn sync_treasury_value(treasury: &Pubkey, pack: &Pubkey, treasury_value: u64) -> Instruction {
+    build(
+        accounts::SyncTreasuryValue {
+            pack: *pack,
+            global_state: pda::global_state().0,
+            treasury: *treasury,
+        },
+        instruction::SyncTreasuryValue { treasury_value },
+    )
+}
+
+/// Set a pack's strategy; normally sent by pack_manager via CPI, signed by the pack's governance PDA
+pub fn set_pack_strategy(
+    governance: &Pubkey,
+    pack: &Pubkey,
+    strategy_focus: StrategyFocus,
+    risk_level: RiskLevel,
+) -> Instruction {
+    build(
+        accounts::SetPackStrategy {
+            pack: *pack,
+            global_state: pda::global_state().0,
+            governance: *governance,
+        },
+        instruction::SetPackStrategy {
+            strategy_focus,
+            risk_level,
+        },
+    )
+}
+
+/// Remove `member_authority`'s user from a pack by vote; normally sent by pack_manager via CPI
+pub fn governance_remove_member(governance: &Pubkey, pack: &Pubkey, member_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::GovernanceRemoveMember {
+            pack: *pack,
+            member: pda::user(member_authority).0,
+            global_state: pda::global_state().0,
+            governance: *governance,
+        },
+        instruction::GovernanceRemoveMember {},
+    )
+}
+
+/// Hand pack leadership to `new_leader_authority` by vote; normally sent by pack_manager via CPI
+pub fn governance_transfer_leadership(governance: &Pubkey, pack: &Pubkey, new_leader_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::GovernanceTransferLeadership {
+            pack: *pack,
+            new_leader: pda::user(new_leader_authority).0,
+            global_state: pda::global_state().0,
+            governance: *governance,
+        },
+        instruction::GovernanceTransferLeadership {},
+    )
+}
+
+/// Pause or unpause the whole protocol (admin only)
+pub fn set_pause_state(authority: &Pubkey, paused: bool) -> Instruction {
+    build(
+        accounts::SetPauseState {
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::SetPauseState { paused },
+    )
+}
+
+/// Propose `new_authority` as protocol admin; `None` cancels the proposal
+pub fn propose_admin_authority(authority: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::ProposeAdminAuthority {
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::ProposeAdminAuthority { new_authority },
+    )
+}
+
+/// Accept a pending protocol admin transfer
+pub fn accept_admin_authority(new_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdminAuthority {
+            global_state: pda::global_state().0,
+            new_authority: *new_authority,
+        },
+        instruction::AcceptAdminAuthority {},
+    )
+}
+
+/// Require `threshold` of `approvers` to sign sensitive admin actions
+pub fn configure_admin_approvals(authority: &Pubkey, approvers: Vec<Pubkey>, threshold: u8) -> Instruction {
+    build(
+        accounts::ConfigureAdminApprovals {
+            global_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::ConfigureAdminApprovals { approvers, threshold },
+    )
+}
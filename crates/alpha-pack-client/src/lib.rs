@@ -0,0 +1,90 @@
+//! Typed client for the Alpha Pack on-chain programs.
+//!
+//! `pda` derives every program address, the per-program modules build instructions with
+//! the same account ordering the programs expect, and the fetch helpers below decode
+//! accounts through their Anchor discriminators.
+
+// Builders take one argument per instruction field, and ClientError carries the RPC client's
+// large error type
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
+pub mod arbitrage;
+pub mod bridge;
+pub mod competition;
+pub mod core;
+pub mod pack_manager;
+pub mod pda;
+pub mod social;
+
+use alpha_pack_core::{GlobalState, Leaderboard, LeaderboardScope, Pack, User};
+use anchor_lang::AccountDeserialize;
+use arbitrage_executor::ArbitrageBot;
+use cross_chain_bridge::CrossChainBridge;
+use ::pack_manager::PackTreasury;
+use social_rewards::SocialRewardsSystem;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("rpc error: {0}")]
+    Rpc(#[from] solana_client::client_error::ClientError),
+    #[error("failed to decode account: {0}")]
+    Decode(#[from] anchor_lang::error::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+/// Decode raw account data, checking the 8-byte discriminator
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    let mut data = data;
+    Ok(T::try_deserialize(&mut data)?)
+}
+
+/// Append admin co-signers to an instruction gated by M-of-N approval; each must sign the transaction
+pub fn with_approvers(mut ix: Instruction, approvers: &[Pubkey]) -> Instruction {
+    ix.accounts.extend(approvers.iter().map(|approver| AccountMeta::new_readonly(*approver, true)));
+    ix
+}
+
+/// Fetch and decode the account at `address`
+pub fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let data = rpc.get_account_data(address)?;
+    decode(&data)
+}
+
+pub fn fetch_global_state(rpc: &RpcClient) -> Result<GlobalState> {
+    fetch(rpc, &pda::global_state().0)
+}
+
+pub fn fetch_user(rpc: &RpcClient, authority: &Pubkey) -> Result<User> {
+    fetch(rpc, &pda::user(authority).0)
+}
+
+pub fn fetch_pack(rpc: &RpcClient, authority: &Pubkey, pack_index: u64) -> Result<Pack> {
+    fetch(rpc, &pda::pack(authority, pack_index).0)
+}
+
+pub fn fetch_leaderboard(rpc: &RpcClient, scope: &LeaderboardScope) -> Result<Leaderboard> {
+    fetch(rpc, &pda::leaderboard(scope).0)
+}
+
+pub fn fetch_treasury(rpc: &RpcClient, pack: &Pubkey) -> Result<PackTreasury> {
+    fetch(rpc, &pda::treasury(pack).0)
+}
+
+pub fn fetch_arbitrage_bot(rpc: &RpcClient, pack: &Pubkey) -> Result<ArbitrageBot> {
+    fetch(rpc, &pda::arbitrage_bot(pack).0)
+}
+
+pub fn fetch_bridge(rpc: &RpcClient) -> Result<CrossChainBridge> {
+    fetch(rpc, &pda::bridge().0)
+}
+
+pub fn fetch_rewards_system(rpc: &RpcClient) -> Result<SocialRewardsSystem> {
+    fetch(rpc, &pda::rewards_system().0)
+}
@@ -0,0 +1,329 @@
+//! Instruction builders for `pack_manager`.
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use pack_manager::{accounts, instruction, ProposalAction, TokenPrice, VoteChoice, VotingMode};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: pack_manager::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Initialize the pack manager global state (protocol admin only)
+pub fn initialize_manager_state(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeManagerState {
+            manager_state: pda::manager_state().0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeManagerState {},
+    )
+}
+
+/// Create the treasury for `pack`
+pub fn initialize_treasury(pack_leader: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeTreasury {
+            treasury: pda::treasury(pack).0,
+            pack: *pack,
+            core_state: pda::global_state().0,
+            pack_leader: *pack_leader,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeTreasury {},
+    )
+}
+
+/// Track `mint` in the treasury and create its associated token account
+pub fn add_treasury_token(pack_leader: &Pubkey, pack: &Pubkey, mint: &Pubkey, allocation_percentage: u8) -> Instruction {
+    let treasury = pda::treasury(pack).0;
+    build(
+        accounts::AddTreasuryToken {
+            treasury,
+            pack: *pack,
+            mint: *mint,
+            token_account: get_associated_token_address(&treasury, mint),
+            core_state: pda::global_state().0,
+            pack_leader: *pack_leader,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::AddTreasuryToken { allocation_percentage },
+    )
+}
+
+/// Deposit `amount` of `mint` from the depositor's associated token account in exchange for
+/// treasury shares; creates the depositor's MemberShare account on first deposit
+pub fn deposit_to_treasury(depositor: &Pubkey, pack: &Pubkey, mint: &Pubkey, amount: u64) -> Instruction {
+    let treasury = pda::treasury(pack).0;
+    build(
+        accounts::DepositToTreasury {
+            treasury,
+            pack: *pack,
+            member_share: pda::member_share(&treasury, depositor).0,
+            mint: *mint,
+            user_token_account: get_associated_token_address(depositor, mint),
+            treasury_token_account: get_associated_token_address(&treasury, mint),
+            core_state: pda::global_state().0,
+            depositor: *depositor,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::DepositToTreasury { amount },
+    )
+}
+
+/// Redeem `shares` for a pro-rata cut of the treasury, paid to the owner's associated token
+/// accounts. `mints` must list every treasury token in `PackTreasury.tokens` order.
+pub fn withdraw_from_treasury(owner: &Pubkey, pack: &Pubkey, mints: &[Pubkey], shares: u64) -> Instruction {
+    let treasury = pda::treasury(pack).0;
+    let mut ix = build(
+        accounts::WithdrawFromTreasury {
+            treasury,
+            member_share: pda::member_share(&treasury, owner).0,
+            core_state: pda::global_state().0,
+            owner: *owner,
+            token_program: token::ID,
+        },
+        instruction::WithdrawFromTreasury { shares },
+    );
+    for mint in mints {
+        ix.accounts.push(AccountMeta::new(get_associated_token_address(&treasury, mint), false));
+        ix.accounts.push(AccountMeta::new(get_associated_token_address(owner, mint), false));
+    }
+    ix
+}
+
+/// Recompute treasury allocation targets
+pub fn rebalance_treasury(authority: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::RebalanceTreasury {
+            treasury: pda::treasury(pack).0,
+            pack: *pack,
+            core_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::RebalanceTreasury {},
+    )
+}
+
+/// Push oracle prices for treasury tokens and sync the pack's treasury value; `price_oracle` must be
+/// the core `Role::PriceOracle`
+pub fn update_token_prices(price_oracle: &Pubkey, pack: &Pubkey, prices: Vec<TokenPrice>) -> Instruction {
+    build(
+        accounts::UpdateTokenPrices {
+            treasury: pda::treasury(pack).0,
+            pack: *pack,
+            core_state: pda::global_state().0,
+            price_oracle: *price_oracle,
+            core_program: alpha_pack_core::ID,
+        },
+        instruction::UpdateTokenPrices { prices },
+    )
+}
+
+/// Accounts `create_proposal` reads the members' voting weights from, given the pack's voting
+/// mode and its members in order
+pub fn voter_accounts(pack: &Pubkey, voting_mode: VotingMode, members: &[Pubkey]) -> Vec<Pubkey> {
+    let treasury = pda::treasury(pack).0;
+    members
+        .iter()
+        .filter_map(|member| match voting_mode {
+            VotingMode::Equal => None,
+            VotingMode::DepositWeighted | VotingMode::Quadratic => Some(pda::member_share(&treasury, member).0),
+            VotingMode::ReputationWeighted => Some(pda::user(member).0),
+        })
+        .collect()
+}
+
+/// Create a proposal to carry out `action`; `proposal_index` must equal `ManagerState.total_proposals`
+/// and `voter_accounts` comes from [`voter_accounts`]
+pub fn create_proposal(
+    proposer: &Pubkey,
+    pack: &Pubkey,
+    proposal_index: u64,
+    title: String,
+    description: String,
+    action: &ProposalAction,
+    voter_accounts: &[Pubkey],
+) -> Instruction {
+    let mut ix = build(
+        accounts::CreateProposal {
+            proposal: pda::proposal(pack, proposal_index).0,
+            pack: *pack,
+            manager_state: pda::manager_state().0,
+            governance_config: pda::governance_config(pack).0,
+            core_state: pda::global_state().0,
+            proposer: *proposer,
+            system_program: system_program::ID,
+        },
+        instruction::CreateProposal {
+            proposal_index,
+            title,
+            description,
+            proposal_type: action.proposal_type(),
+            execution_data: action.execution_data(),
+        },
+    );
+    for account in voter_accounts {
+        ix.accounts.push(AccountMeta::new_readonly(*account, false));
+    }
+    ix
+}
+
+/// Vote on a proposal of `pack` as a member of its snapshot, signed by the voter or its `delegate`
+pub fn vote_on_proposal(
+    authority: &Pubkey,
+    delegate: Option<Pubkey>,
+    pack: &Pubkey,
+    proposal: &Pubkey,
+    choice: VoteChoice,
+) -> Instruction {
+    let (signer, session_key) = crate::core::signer_and_session(authority, delegate);
+    build(
+        accounts::VoteOnProposal {
+            proposal: *proposal,
+            voter: pda::user(authority).0,
+            vote_receipt: pda::vote_receipt(proposal, authority).0,
+            member_share: Some(pda::member_share(&pda::treasury(pack).0, authority).0),
+            session_key,
+            core_state: pda::global_state().0,
+            authority: signer,
+            system_program: system_program::ID,
+        },
+        instruction::VoteOnProposal { choice },
+    )
+}
+
+/// Create `pack`'s governance config with the default voting rules; later changes go through
+/// GovernanceChange proposals
+pub fn initialize_governance_config(payer: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::InitializeGovernanceConfig {
+            governance_config: pda::governance_config(pack).0,
+            pack: *pack,
+            core_state: pda::global_state().0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeGovernanceConfig {},
+    )
+}
+
+/// Execute a passed proposal whose timelock has elapsed; `action` is the proposal's decoded
+/// execution data and selects the accounts to pass
+pub fn execute_proposal(executor: &Pubkey, pack: &Pubkey, proposal: &Pubkey, action: &ProposalAction) -> Instruction {
+    let treasury = pda::treasury(pack).0;
+    let (withdrawal, target_user) = match action {
+        ProposalAction::TreasuryWithdrawal(withdrawal) => (Some(withdrawal), None),
+        ProposalAction::StrategyChange(_) | ProposalAction::GovernanceChange(_) => (None, None),
+        ProposalAction::MemberRemoval(removal) => (None, Some(pda::user(&removal.member).0)),
+        ProposalAction::LeadershipChange(change) => (None, Some(pda::user(&change.new_leader).0)),
+    };
+    let governance_config =
+        matches!(action, ProposalAction::GovernanceChange(_)).then(|| pda::governance_config(pack).0);
+    build(
+        accounts::ExecuteProposal {
+            proposal: *proposal,
+            pack: *pack,
+            governance: pda::governance(pack).0,
+            treasury: withdrawal.map(|_| treasury),
+            treasury_token_account: withdrawal.map(|w| get_associated_token_address(&treasury, &w.mint)),
+            recipient_token_account: withdrawal.map(|w| get_associated_token_address(&w.recipient, &w.mint)),
+            token_program: withdrawal.map(|_| token::ID),
+            target_user,
+            governance_config,
+            core_state: pda::global_state().0,
+            core_program: alpha_pack_core::ID,
+            executor: *executor,
+        },
+        instruction::ExecuteProposal {},
+    )
+}
+
+/// Cancel an active proposal nobody has voted on, signed by its proposer or the pack leader
+pub fn cancel_proposal(authority: &Pubkey, pack: &Pubkey, proposal: &Pubkey) -> Instruction {
+    build(
+        accounts::CancelProposal {
+            proposal: *proposal,
+            pack: *pack,
+            core_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::CancelProposal {},
+    )
+}
+
+/// Resolve a proposal whose voting window has closed
+pub fn finalize_proposal(proposal: &Pubkey) -> Instruction {
+    build(
+        accounts::FinalizeProposal {
+            proposal: *proposal,
+            core_state: pda::global_state().0,
+        },
+        instruction::FinalizeProposal {},
+    )
+}
+
+/// Close a settled proposal, or a passed one whose execution window lapsed, returning its rent to the proposer
+pub fn close_proposal(proposer: &Pubkey, proposal: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseProposal {
+            proposal: *proposal,
+            core_state: pda::global_state().0,
+            proposer: *proposer,
+        },
+        instruction::CloseProposal {},
+    )
+}
+
+/// Upgrade `pack`'s v1 PackTreasury account to the current layout
+pub fn migrate_treasury(payer: &Pubkey, pack: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateTreasury {
+            treasury: pda::treasury(pack).0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateTreasury {},
+    )
+}
+
+/// Upgrade a v1 Proposal account of `pack` to the current layout
+pub fn migrate_proposal(payer: &Pubkey, pack: &Pubkey, proposal: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateProposal {
+            proposal: *proposal,
+            pack: *pack,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateProposal {},
+    )
+}
+
+/// Close `voter`'s vote receipt once its proposal is closed or closable, returning the rent
+pub fn close_vote_receipt(voter: &Pubkey, proposal: &Pubkey) -> Instruction {
+    build(
+        accounts::CloseVoteReceipt {
+            vote_receipt: pda::vote_receipt(proposal, voter).0,
+            proposal: *proposal,
+            core_state: pda::global_state().0,
+            voter: *voter,
+        },
+        instruction::CloseVoteReceipt {},
+    )
+}
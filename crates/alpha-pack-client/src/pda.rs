@@ -0,0 +1,228 @@
+//! Program derived addresses for every seed scheme used by the Alpha Pack programs.
+//!
+//! Each helper mirrors the `seeds = [...]` constraint of the matching Anchor account and
+//! returns the address together with its canonical bump.
+
+use solana_sdk::pubkey::Pubkey;
+
+// alpha_pack_core
+
+/// Core protocol state, `[b"global_state"]`
+pub fn global_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"global_state"], &alpha_pack_core::ID)
+}
+
+/// User profile, `[b"user", authority]`
+pub fn user(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user", authority.as_ref()], &alpha_pack_core::ID)
+}
+
+/// Registry entry for a handle, `[b"username", hash(lowercase(username))]`
+pub fn username(username: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"username", alpha_pack_core::username_seed(username).as_ref()],
+        &alpha_pack_core::ID,
+    )
+}
+
+/// Counters kept when the user of `authority` is closed, `[b"user_counters", authority]`
+pub fn user_counters(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user_counters", authority.as_ref()], &alpha_pack_core::ID)
+}
+
+/// Session key letting `delegate` act for `user`, `[b"session", user, delegate]`
+pub fn session_key(user: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"session", user.as_ref(), delegate.as_ref()], &alpha_pack_core::ID)
+}
+
+/// Pack created by `authority`, `[b"pack", authority, pack_index]`
+pub fn pack(authority: &Pubkey, pack_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"pack", authority.as_ref(), &pack_index.to_le_bytes()],
+        &alpha_pack_core::ID,
+    )
+}
+
+/// Pending invite to `pack` for `invitee`, `[b"invite", pack, invitee]`
+pub fn invite(pack: &Pubkey, invitee: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"invite", pack.as_ref(), invitee.as_ref()], &alpha_pack_core::ID)
+}
+
+/// Pending application to `pack` by `applicant`, `[b"application", pack, applicant]`
+pub fn application(pack: &Pubkey, applicant: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"application", pack.as_ref(), applicant.as_ref()], &alpha_pack_core::ID)
+}
+
+/// Trade record, `[b"trade", authority, trade_index]`
+pub fn trade(authority: &Pubkey, trade_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"trade", authority.as_ref(), &trade_index.to_le_bytes()],
+        &alpha_pack_core::ID,
+    )
+}
+
+/// Trade ring buffer for `user` (the User PDA), `[b"trade_log", user]`
+pub fn trade_log(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"trade_log", user.as_ref()], &alpha_pack_core::ID)
+}
+
+/// Quote price feed for `mint`, `[b"price_feed", mint]`
+pub fn price_feed(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"price_feed", mint.as_ref()], &alpha_pack_core::ID)
+}
+
+/// In-flight balance settlement, `[b"settlement", authority]`
+pub fn settlement(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"settlement", authority.as_ref()], &alpha_pack_core::ID)
+}
+
+/// Stats for `user` (the User PDA) in one epoch, `[b"user_epoch", user, epoch]`
+pub fn user_epoch_stats(user: &Pubkey, epoch: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"user_epoch", user.as_ref(), &epoch.to_le_bytes()],
+        &alpha_pack_core::ID,
+    )
+}
+
+/// Stats for `pack` in one epoch, `[b"pack_epoch", pack, epoch]`
+pub fn pack_epoch_stats(pack: &Pubkey, epoch: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"pack_epoch", pack.as_ref(), &epoch.to_le_bytes()],
+        &alpha_pack_core::ID,
+    )
+}
+
+/// Ranking for `scope`, `[b"leaderboard", scope.seed()]`
+pub fn leaderboard(scope: &alpha_pack_core::LeaderboardScope) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"leaderboard", scope.seed().as_ref()], &alpha_pack_core::ID)
+}
+
+// pack_manager
+
+/// Pack manager state, `[b"manager_state"]`
+pub fn manager_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"manager_state"], &pack_manager::ID)
+}
+
+/// Pack treasury, `[b"treasury", pack]`
+pub fn treasury(pack: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury", pack.as_ref()], &pack_manager::ID)
+}
+
+/// A member's treasury shares, `[b"member_share", treasury, owner]`
+pub fn member_share(treasury: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"member_share", treasury.as_ref(), owner.as_ref()], &pack_manager::ID)
+}
+
+/// Signer for a pack's governance actions in alpha_pack_core, `[b"governance", pack]`
+pub fn governance(pack: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"governance", pack.as_ref()], &pack_manager::ID)
+}
+
+/// A pack's voting rules, `[b"governance_config", pack]`
+pub fn governance_config(pack: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"governance_config", pack.as_ref()], &pack_manager::ID)
+}
+
+/// Governance proposal, `[b"proposal", pack, proposal_index]`
+pub fn proposal(pack: &Pubkey, proposal_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"proposal", pack.as_ref(), &proposal_index.to_le_bytes()],
+        &pack_manager::ID,
+    )
+}
+
+/// A member's vote on a proposal, `[b"vote", proposal, voter]`
+pub fn vote_receipt(proposal: &Pubkey, voter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vote", proposal.as_ref(), voter.as_ref()], &pack_manager::ID)
+}
+
+// competition_engine
+
+/// Competition engine state, `[b"competition_state"]`
+pub fn competition_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"competition_state"], &competition_engine::ID)
+}
+
+/// Competition, `[b"competition", competition_index]`
+pub fn competition(competition_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"competition", &competition_index.to_le_bytes()],
+        &competition_engine::ID,
+    )
+}
+
+/// Tournament, `[b"tournament", tournament_index]`
+pub fn tournament(tournament_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"tournament", &tournament_index.to_le_bytes()],
+        &competition_engine::ID,
+    )
+}
+
+// arbitrage_executor
+
+/// Arbitrage executor state, `[b"global_state"]`
+pub fn arbitrage_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"global_state"], &arbitrage_executor::ID)
+}
+
+/// Pack arbitrage bot, `[b"arbitrage_bot", pack]`
+pub fn arbitrage_bot(pack: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"arbitrage_bot", pack.as_ref()], &arbitrage_executor::ID)
+}
+
+/// Arbitrage opportunity, `[b"opportunity", opportunity_index]`
+pub fn opportunity(opportunity_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"opportunity", &opportunity_index.to_le_bytes()],
+        &arbitrage_executor::ID,
+    )
+}
+
+/// Trade recorded by an arbitrage bot, `[b"trade", arbitrage_bot, bot_trade_index]`
+pub fn arbitrage_trade(arbitrage_bot: &Pubkey, bot_trade_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"trade", arbitrage_bot.as_ref(), &bot_trade_index.to_le_bytes()],
+        &arbitrage_executor::ID,
+    )
+}
+
+// social_rewards
+
+/// Social rewards system, `[b"rewards_system"]`
+pub fn rewards_system() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rewards_system"], &social_rewards::ID)
+}
+
+/// Content post, `[b"content_post", post_index]`
+pub fn content_post(post_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"content_post", &post_index.to_le_bytes()], &social_rewards::ID)
+}
+
+/// Influence leaderboard, `[b"influence_leaderboard"]`
+pub fn influence_leaderboard() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"influence_leaderboard"], &social_rewards::ID)
+}
+
+// cross_chain_bridge
+
+/// Bridge configuration, `[b"bridge"]`
+pub fn bridge() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"bridge"], &cross_chain_bridge::ID)
+}
+
+/// Outgoing transfer, `[b"transfer", transfer_index]`
+pub fn transfer(transfer_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"transfer", &transfer_index.to_le_bytes()], &cross_chain_bridge::ID)
+}
+
+/// Trade recorded when a transfer completes, `[b"trade", transfer]`
+pub fn bridge_trade(transfer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"trade", transfer.as_ref()], &cross_chain_bridge::ID)
+}
+
+/// Incoming transfer, `[b"incoming_transfer", nonce]`
+pub fn incoming_transfer(nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"incoming_transfer", &nonce.to_le_bytes()], &cross_chain_bridge::ID)
+}
@@ -0,0 +1,221 @@
+//! Instruction builders for `social_rewards`.
+
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use social_rewards::{accounts, instruction, ContentType, InfluencerEntry, SocialPlatform};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::pda;
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: social_rewards::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Initialize the rewards system paying out in `reward_mint`
+pub fn initialize_rewards_system(
+    authority: &Pubkey,
+    reward_mint: &Pubkey,
+    daily_reward_pool: u64,
+    viral_bonus_multiplier: u16,
+    engagement_threshold: u64,
+) -> Instruction {
+    build(
+        accounts::InitializeRewardsSystem {
+            rewards_system: pda::rewards_system().0,
+            reward_mint: *reward_mint,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::InitializeRewardsSystem {
+            daily_reward_pool,
+            viral_bonus_multiplier,
+            engagement_threshold,
+        },
+    )
+}
+
+/// Create a content post; `post_index` must equal `SocialRewardsSystem.total_content_pieces`
+pub fn create_content_post(
+    creator: &Pubkey,
+    post_index: u64,
+    platform: SocialPlatform,
+    content_type: ContentType,
+    content_hash: String,
+    metadata_uri: String,
+) -> Instruction {
+    build(
+        accounts::CreateContentPost {
+            content_post: pda::content_post(post_index).0,
+            user: pda::user(creator).0,
+            rewards_system: pda::rewards_system().0,
+            core_state: pda::global_state().0,
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        instruction::CreateContentPost {
+            post_index,
+            platform,
+            content_type,
+            content_hash,
+            metadata_uri,
+        },
+    )
+}
+
+/// Push engagement metrics for a post
+pub fn update_engagement(
+    engagement_oracle: &Pubkey,
+    content_post: &Pubkey,
+    likes: u64,
+    shares: u64,
+    comments: u64,
+    views: u64,
+) -> Instruction {
+    build(
+        accounts::UpdateEngagement {
+            content_post: *content_post,
+            rewards_system: pda::rewards_system().0,
+            core_state: pda::global_state().0,
+            engagement_oracle: *engagement_oracle,
+        },
+        instruction::UpdateEngagement {
+            likes,
+            shares,
+            comments,
+            views,
+        },
+    )
+}
+
+/// Claim rewards for a viral post into the creator's associated token account; `pack` is
+/// required while the creator is in a pack so core can credit its social score, and `epoch`
+/// must be the current stats epoch
+pub fn claim_content_rewards(
+    creator: &Pubkey,
+    content_post: &Pubkey,
+    reward_mint: &Pubkey,
+    pack: Option<Pubkey>,
+    epoch: u64,
+) -> Instruction {
+    let (user_epoch_stats, pack_epoch_stats) = crate::core::epoch_stats(creator, pack, epoch);
+    build(
+        accounts::ClaimContentRewards {
+            content_post: *content_post,
+            rewards_system: pda::rewards_system().0,
+            reward_mint: *reward_mint,
+            creator_token_account: get_associated_token_address(creator, reward_mint),
+            user: pda::user(creator).0,
+            pack,
+            user_epoch_stats,
+            pack_epoch_stats,
+            core_state: pda::global_state().0,
+            creator: *creator,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            core_program: alpha_pack_core::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimContentRewards {},
+    )
+}
+
+/// Mint an NFT for a highly viral post; `nft_mint` must also sign
+pub fn mint_viral_nft(
+    creator: &Pubkey,
+    content_post: &Pubkey,
+    nft_mint: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    build(
+        accounts::MintViralNFT {
+            content_post: *content_post,
+            nft_mint: *nft_mint,
+            creator_nft_account: get_associated_token_address(creator, nft_mint),
+            core_state: pda::global_state().0,
+            creator: *creator,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::MintViralNft { name, symbol, uri },
+    )
+}
+
+/// Replace the influence leaderboard and open a new season
+pub fn update_influence_leaderboard(authority: &Pubkey, top_influencers: Vec<InfluencerEntry>) -> Instruction {
+    build(
+        accounts::UpdateInfluenceLeaderboard {
+            influence_leaderboard: pda::influence_leaderboard().0,
+            rewards_system: pda::rewards_system().0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::UpdateInfluenceLeaderboard { top_influencers },
+    )
+}
+
+/// Record seasonal reward amounts for the current leaderboard
+pub fn distribute_seasonal_rewards(authority: &Pubkey, reward_amounts: Vec<u64>) -> Instruction {
+    build(
+        accounts::DistributeSeasonalRewards {
+            influence_leaderboard: pda::influence_leaderboard().0,
+            rewards_system: pda::rewards_system().0,
+            core_state: pda::global_state().0,
+            authority: *authority,
+        },
+        instruction::DistributeSeasonalRewards { reward_amounts },
+    )
+}
+
+/// Propose `new_authority` as rewards system authority; `None` cancels the proposal
+pub fn propose_admin_authority(authority: &Pubkey, new_authority: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::ProposeAdminAuthority {
+            rewards_system: pda::rewards_system().0,
+            authority: *authority,
+        },
+        instruction::ProposeAdminAuthority { new_authority },
+    )
+}
+
+/// Accept a pending rewards system authority transfer
+pub fn accept_admin_authority(new_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::AcceptAdminAuthority {
+            rewards_system: pda::rewards_system().0,
+            new_authority: *new_authority,
+        },
+        instruction::AcceptAdminAuthority {},
+    )
+}
+
+/// Require `threshold` of `approvers` to sign rewards admin actions
+pub fn configure_admin_approvals(authority: &Pubkey, approvers: Vec<Pubkey>, threshold: u8) -> Instruction {
+    build(
+        accounts::ConfigureAdminApprovals {
+            rewards_system: pda::rewards_system().0,
+            authority: *authority,
+        },
+        instruction::ConfigureAdminApprovals { approvers, threshold },
+    )
+}
+
+/// Upgrade the v1 rewards system account to the current layout
+pub fn migrate_rewards_system(payer: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateRewardsSystem {
+            rewards_system: pda::rewards_system().0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateRewardsSystem {},
+    )
+}
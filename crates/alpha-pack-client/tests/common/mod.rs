@@ -0,0 +1,72 @@
+//! Helpers shared by the program-test suites; each suite uses a different subset of them.
+#![allow(dead_code)]
+
+use alpha_pack_client::decode;
+use anchor_lang::AccountDeserialize;
+use solana_program_test::{BanksClientError, ProgramTestContext};
+use solana_sdk::{
+    clock::Clock,
+    instruction::{Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account::{get_associated_token_address, instruction::create_associated_token_account};
+
+pub async fn send(ctx: &mut ProgramTestContext, ix: Instruction, signers: &[&Keypair]) -> Result<(), BanksClientError> {
+    let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+    ctx.banks_client.process_transaction(tx).await
+}
+
+pub async fn fetch<T: AccountDeserialize>(ctx: &mut ProgramTestContext, address: Pubkey) -> T {
+    let account = ctx.banks_client.get_account(address).await.unwrap().expect("account not found");
+    decode(&account.data).unwrap()
+}
+
+pub async fn token_balance(ctx: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    let account = ctx.banks_client.get_account(address).await.unwrap().expect("token account not found");
+    spl_token::state::Account::unpack(&account.data).unwrap().amount
+}
+
+pub fn assert_error(result: Result<(), BanksClientError>, error: impl Into<u32>) {
+    let err = result.expect_err("instruction should fail").unwrap();
+    assert_eq!(err, TransactionError::InstructionError(0, InstructionError::Custom(error.into())));
+}
+
+pub async fn warp_by(ctx: &mut ProgramTestContext, seconds: i64) {
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += seconds;
+    ctx.set_sysvar(&clock);
+}
+
+// A 6-decimal mint whose authority is the payer
+pub async fn create_mint(ctx: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &mint.pubkey(),
+        rent.minimum_balance(spl_token::state::Mint::LEN),
+        spl_token::state::Mint::LEN as u64,
+        &spl_token::ID,
+    );
+    send(ctx, ix, &[&mint]).await.unwrap();
+    let ix = spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &ctx.payer.pubkey(), None, 6)
+        .unwrap();
+    send(ctx, ix, &[]).await.unwrap();
+    mint.pubkey()
+}
+
+// Creates `owner`'s associated token account for `mint` and mints `amount` into it
+pub async fn fund_tokens(ctx: &mut ProgramTestContext, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+    let payer = ctx.payer.pubkey();
+    send(ctx, create_associated_token_account(&payer, owner, mint, &spl_token::ID), &[]).await.unwrap();
+    let ata = get_associated_token_address(owner, mint);
+    let ix = spl_token::instruction::mint_to(&spl_token::ID, mint, &ata, &payer, &[], amount).unwrap();
+    send(ctx, ix, &[]).await.unwrap();
+}
@@ -0,0 +1,314 @@
+mod common;
+
+use alpha_pack_client::{core, pack_manager, pda};
+use alpha_pack_core::{Pack, RiskLevel, Role, StrategyFocus};
+use ::pack_manager::{
+    EligibleVoter, GovernanceChangeAction, GovernanceConfig, PackManagerError, Proposal, ProposalAction,
+    ProposalStatus, StrategyChangeAction, TokenPrice, VoteChoice, VoteReceipt, VotingMode,
+};
+use common::{assert_error, create_mint, fetch, fund_tokens, send, warp_by};
+use solana_program_test::{tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+
+// The admin leads a pack with `member`; `outsider` has a user but no pack. Proposals start out
+// under the default rules: one vote per member and a 50% quorum.
+async fn setup() -> (ProgramTestContext, Keypair, Keypair, Pubkey) {
+    let mut program_test = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None);
+    program_test.add_program("pack_manager", ::pack_manager::ID, None);
+    let mut ctx = program_test.start_with_context().await;
+    let admin = ctx.payer.pubkey();
+    let member = Keypair::new();
+    let outsider = Keypair::new();
+    for kp in [&member, &outsider] {
+        let ix = system_instruction::transfer(&admin, &kp.pubkey(), 1_000_000_000);
+        send(&mut ctx, ix, &[]).await.unwrap();
+    }
+
+    send(&mut ctx, core::initialize(&admin), &[]).await.unwrap();
+    send(&mut ctx, core::create_user(&admin, "leader".to_string(), admin), &[]).await.unwrap();
+    for (kp, name) in [(&member, "member"), (&outsider, "outsider")] {
+        let ix = core::create_user(&kp.pubkey(), name.to_string(), kp.pubkey());
+        send(&mut ctx, ix, &[kp]).await.unwrap();
+    }
+    let ix = core::create_pack(
+        &admin,
+        0,
+        "Wolves".to_string(),
+        "Governance test pack".to_string(),
+        5,
+        StrategyFocus::Balanced,
+        RiskLevel::Medium,
+    );
+    send(&mut ctx, ix, &[]).await.unwrap();
+    let pack = pda::pack(&admin, 0).0;
+    send(&mut ctx, core::join_pack(&member.pubkey(), &pack), &[&member]).await.unwrap();
+    send(&mut ctx, pack_manager::initialize_manager_state(&admin), &[]).await.unwrap();
+    (ctx, member, outsider, pack)
+}
+
+// Opens proposal `index` as the admin; `voter_accounts` are the members' weight accounts in member order
+async fn propose(
+    ctx: &mut ProgramTestContext,
+    pack: &Pubkey,
+    index: u64,
+    action: &ProposalAction,
+    voter_accounts: &[Pubkey],
+) -> Pubkey {
+    let admin = ctx.payer.pubkey();
+    let ix = pack_manager::create_proposal(
+        &admin,
+        pack,
+        index,
+        format!("Proposal {index}"),
+        "Governance test".to_string(),
+        action,
+        voter_accounts,
+    );
+    send(ctx, ix, &[]).await.unwrap();
+    pda::proposal(pack, index).0
+}
+
+// Passes and executes a GovernanceChange to `voting_mode` as proposal `index`, with the admin
+// voting first and `member` only if the outcome is still open
+async fn change_voting_mode(
+    ctx: &mut ProgramTestContext,
+    member: &Keypair,
+    pack: &Pubkey,
+    index: u64,
+    voting_mode: VotingMode,
+    voter_accounts: &[Pubkey],
+) {
+    let admin = ctx.payer.pubkey();
+    let action = ProposalAction::GovernanceChange(GovernanceChangeAction {
+        quorum_bps: 5_000,
+        threshold_bps: 5_000,
+        voting_mode,
+        voting_period: GovernanceConfig::DEFAULT_VOTING_PERIOD,
+    });
+    let proposal = propose(ctx, pack, index, &action, voter_accounts).await;
+    send(ctx, pack_manager::vote_on_proposal(&admin, None, pack, &proposal, VoteChoice::For), &[]).await.unwrap();
+    if fetch::<Proposal>(ctx, proposal).await.status == ProposalStatus::Active {
+        let ix = pack_manager::vote_on_proposal(&member.pubkey(), None, pack, &proposal, VoteChoice::For);
+        send(ctx, ix, &[member]).await.unwrap();
+    }
+    warp_by(ctx, Proposal::EXECUTION_DELAY).await;
+    send(ctx, pack_manager::execute_proposal(&admin, pack, &proposal, &action), &[]).await.unwrap();
+    let config: GovernanceConfig = fetch(ctx, pda::governance_config(pack).0).await;
+    assert!(config.voting_mode == voting_mode);
+}
+
+#[tokio::test]
+async fn passed_proposals_execute_only_after_the_timelock() {
+    let (mut ctx, member, outsider, pack) = setup().await;
+    let admin = ctx.payer.pubkey();
+
+    let action = ProposalAction::StrategyChange(StrategyChangeAction {
+        strategy_focus: StrategyFocus::Arbitrage,
+        risk_level: RiskLevel::Low,
+    });
+    let voters = pack_manager::voter_accounts(&pack, VotingMode::Equal, &[admin, member.pubkey()]);
+
+    // Only members may put proposals to their pack
+    let ix = pack_manager::create_proposal(
+        &outsider.pubkey(),
+        &pack,
+        0,
+        "Outside proposal".to_string(),
+        "Governance test".to_string(),
+        &action,
+        &voters,
+    );
+    assert_error(send(&mut ctx, ix, &[&outsider]).await, PackManagerError::NotPackMember);
+    let proposal = propose(&mut ctx, &pack, 0, &action, &voters).await;
+
+    // Still open, so there is nothing to execute
+    assert_error(
+        send(&mut ctx, pack_manager::execute_proposal(&admin, &pack, &proposal, &action), &[]).await,
+        PackManagerError::ProposalNotPassed,
+    );
+
+    let ix = pack_manager::vote_on_proposal(&admin, None, &pack, &proposal, VoteChoice::For);
+    send(&mut ctx, ix, &[]).await.unwrap();
+    let ix = pack_manager::vote_on_proposal(&member.pubkey(), None, &pack, &proposal, VoteChoice::For);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    let passed: Proposal = fetch(&mut ctx, proposal).await;
+    assert!(passed.status == ProposalStatus::Passed);
+    assert!(passed.executable_at >= passed.created_at + Proposal::EXECUTION_DELAY);
+
+    // Passing starts the timelock rather than executing
+    assert_error(
+        send(&mut ctx, pack_manager::execute_proposal(&admin, &pack, &proposal, &action), &[]).await,
+        PackManagerError::TimelockNotElapsed,
+    );
+    let pack_account: Pack = fetch(&mut ctx, pack).await;
+    assert!(pack_account.risk_level == RiskLevel::Medium);
+
+    warp_by(&mut ctx, Proposal::EXECUTION_DELAY).await;
+    // Anyone may execute once the timelock has elapsed
+    let ix = pack_manager::execute_proposal(&member.pubkey(), &pack, &proposal, &action);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    let executed: Proposal = fetch(&mut ctx, proposal).await;
+    assert!(executed.status == ProposalStatus::Executed);
+    let pack_account: Pack = fetch(&mut ctx, pack).await;
+    assert!(pack_account.strategy_focus == StrategyFocus::Arbitrage);
+    assert!(pack_account.risk_level == RiskLevel::Low);
+
+    // And only once
+    assert_error(
+        send(&mut ctx, pack_manager::execute_proposal(&admin, &pack, &proposal, &action), &[]).await,
+        PackManagerError::ProposalNotPassed,
+    );
+}
+
+#[tokio::test]
+async fn weighted_votes_follow_the_snapshot_capped_by_current_shares() {
+    let (mut ctx, member, outsider, pack) = setup().await;
+    let admin = ctx.payer.pubkey();
+
+    // The admin holds three times the member's shares
+    send(&mut ctx, core::set_role(&admin, Role::PriceOracle, Some(admin)), &[]).await.unwrap();
+    let mint = create_mint(&mut ctx).await;
+    send(&mut ctx, pack_manager::initialize_treasury(&admin, &pack), &[]).await.unwrap();
+    send(&mut ctx, pack_manager::add_treasury_token(&admin, &pack, &mint, 100), &[]).await.unwrap();
+    fund_tokens(&mut ctx, &mint, &admin, 3_000_000).await;
+    fund_tokens(&mut ctx, &mint, &member.pubkey(), 1_000_000).await;
+    let prices = vec![TokenPrice { mint, price_usd: 1_000_000 }];
+    send(&mut ctx, pack_manager::update_token_prices(&admin, &pack, prices), &[]).await.unwrap();
+    send(&mut ctx, pack_manager::deposit_to_treasury(&admin, &pack, &mint, 3_000_000), &[]).await.unwrap();
+    let ix = pack_manager::deposit_to_treasury(&member.pubkey(), &pack, &mint, 1_000_000);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+
+    send(&mut ctx, pack_manager::initialize_governance_config(&admin, &pack), &[]).await.unwrap();
+    let equal_voters = pack_manager::voter_accounts(&pack, VotingMode::Equal, &[admin, member.pubkey()]);
+    change_voting_mode(&mut ctx, &member, &pack, 0, VotingMode::DepositWeighted, &equal_voters).await;
+
+    // Joining after the deposits leaves the outsider with no weight, so no place in the snapshot
+    send(&mut ctx, core::join_pack(&outsider.pubkey(), &pack), &[&outsider]).await.unwrap();
+    let members = [admin, member.pubkey(), outsider.pubkey()];
+    let action = ProposalAction::StrategyChange(StrategyChangeAction {
+        strategy_focus: StrategyFocus::Social,
+        risk_level: RiskLevel::High,
+    });
+    let voters = pack_manager::voter_accounts(&pack, VotingMode::DepositWeighted, &members);
+    let proposal = propose(&mut ctx, &pack, 1, &action, &voters).await;
+    let opened: Proposal = fetch(&mut ctx, proposal).await;
+    assert_eq!(
+        opened.eligible_voters,
+        vec![
+            EligibleVoter { voter: admin, weight: 3_000_000 },
+            EligibleVoter { voter: member.pubkey(), weight: 1_000_000 },
+        ]
+    );
+    assert_eq!(opened.total_weight, 4_000_000);
+
+    let ix = pack_manager::vote_on_proposal(&outsider.pubkey(), None, &pack, &proposal, VoteChoice::For);
+    assert_error(send(&mut ctx, ix, &[&outsider]).await, PackManagerError::NotEligibleToVote);
+
+    // Shares redeemed after the snapshot no longer count
+    let ix = pack_manager::withdraw_from_treasury(&member.pubkey(), &pack, &[mint], 500_000);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    let ix = pack_manager::vote_on_proposal(&member.pubkey(), None, &pack, &proposal, VoteChoice::Against);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    let receipt: VoteReceipt = fetch(&mut ctx, pda::vote_receipt(&proposal, &member.pubkey()).0).await;
+    assert_eq!(receipt.weight, 500_000);
+
+    let ix = pack_manager::vote_on_proposal(&admin, None, &pack, &proposal, VoteChoice::For);
+    send(&mut ctx, ix, &[]).await.unwrap();
+    let decided: Proposal = fetch(&mut ctx, proposal).await;
+    assert_eq!((decided.votes_for, decided.votes_against), (3_000_000, 500_000));
+    assert!(decided.status == ProposalStatus::Passed);
+
+    // Quadratic weights are the square roots of the shares held
+    change_voting_mode(&mut ctx, &member, &pack, 2, VotingMode::Quadratic, &voters).await;
+    let proposal = propose(&mut ctx, &pack, 3, &action, &voters).await;
+    let opened: Proposal = fetch(&mut ctx, proposal).await;
+    assert_eq!(
+        opened.eligible_voters,
+        vec![
+            EligibleVoter { voter: admin, weight: 1_732 },
+            EligibleVoter { voter: member.pubkey(), weight: 707 },
+        ]
+    );
+
+    // A member who has redeemed everything has nothing left to vote with
+    let ix = pack_manager::withdraw_from_treasury(&member.pubkey(), &pack, &[mint], 500_000);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    let ix = pack_manager::vote_on_proposal(&member.pubkey(), None, &pack, &proposal, VoteChoice::For);
+    assert_error(send(&mut ctx, ix, &[&member]).await, PackManagerError::NoVotingWeight);
+}
+
+#[tokio::test]
+async fn finalize_settles_lapsed_proposals_and_unlocks_cleanup() {
+    let (mut ctx, member, outsider, pack) = setup().await;
+    let admin = ctx.payer.pubkey();
+    send(&mut ctx, core::join_pack(&outsider.pubkey(), &pack), &[&outsider]).await.unwrap();
+    let members = [admin, member.pubkey(), outsider.pubkey()];
+    let voters = pack_manager::voter_accounts(&pack, VotingMode::Equal, &members);
+    let action = ProposalAction::StrategyChange(StrategyChangeAction {
+        strategy_focus: StrategyFocus::Social,
+        risk_level: RiskLevel::Low,
+    });
+
+    // One vote in three misses the 50% quorum
+    let lapsed = propose(&mut ctx, &pack, 0, &action, &voters).await;
+    send(&mut ctx, pack_manager::vote_on_proposal(&admin, None, &pack, &lapsed, VoteChoice::For), &[]).await.unwrap();
+    assert_error(
+        send(&mut ctx, pack_manager::finalize_proposal(&lapsed), &[]).await,
+        PackManagerError::VotingPeriodNotEnded,
+    );
+    assert_error(
+        send(&mut ctx, pack_manager::close_vote_receipt(&admin, &lapsed), &[]).await,
+        PackManagerError::ProposalNotSettled,
+    );
+
+    // Abstaining counts towards quorum but not the threshold
+    let passed = propose(&mut ctx, &pack, 1, &action, &voters).await;
+    send(&mut ctx, pack_manager::vote_on_proposal(&admin, None, &pack, &passed, VoteChoice::For), &[]).await.unwrap();
+    let ix = pack_manager::vote_on_proposal(&member.pubkey(), None, &pack, &passed, VoteChoice::Abstain);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    assert!(fetch::<Proposal>(&mut ctx, passed).await.status == ProposalStatus::Active);
+
+    warp_by(&mut ctx, GovernanceConfig::DEFAULT_VOTING_PERIOD).await;
+    let ix = pack_manager::vote_on_proposal(&outsider.pubkey(), None, &pack, &lapsed, VoteChoice::For);
+    assert_error(send(&mut ctx, ix, &[&outsider]).await, PackManagerError::VotingPeriodEnded);
+
+    send(&mut ctx, pack_manager::finalize_proposal(&lapsed), &[]).await.unwrap();
+    assert!(fetch::<Proposal>(&mut ctx, lapsed).await.status == ProposalStatus::Expired);
+    send(&mut ctx, pack_manager::finalize_proposal(&passed), &[]).await.unwrap();
+    assert!(fetch::<Proposal>(&mut ctx, passed).await.status == ProposalStatus::Passed);
+    assert_error(
+        send(&mut ctx, pack_manager::finalize_proposal(&passed), &[]).await,
+        PackManagerError::ProposalNotActive,
+    );
+
+    // Settled proposals and their receipts can be closed
+    send(&mut ctx, pack_manager::close_vote_receipt(&admin, &lapsed), &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(pda::vote_receipt(&lapsed, &admin).0).await.unwrap().is_none());
+    send(&mut ctx, pack_manager::close_proposal(&admin, &lapsed), &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(lapsed).await.unwrap().is_none());
+
+    // A passed proposal stays open while it can still be executed
+    assert_error(
+        send(&mut ctx, pack_manager::close_proposal(&admin, &passed), &[]).await,
+        PackManagerError::ProposalNotSettled,
+    );
+    let ix = pack_manager::close_vote_receipt(&member.pubkey(), &passed);
+    assert_error(send(&mut ctx, ix, &[&member]).await, PackManagerError::ProposalNotSettled);
+
+    // Once its execution window lapses it can no longer run, only be cleaned up
+    warp_by(&mut ctx, Proposal::EXECUTION_DELAY + Proposal::EXECUTION_WINDOW).await;
+    assert_error(
+        send(&mut ctx, pack_manager::execute_proposal(&admin, &pack, &passed, &action), &[]).await,
+        PackManagerError::ExecutionWindowClosed,
+    );
+    let ix = pack_manager::close_vote_receipt(&member.pubkey(), &passed);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    send(&mut ctx, pack_manager::close_proposal(&admin, &passed), &[]).await.unwrap();
+    // Receipts outlive their proposal and can still be closed afterwards
+    send(&mut ctx, pack_manager::close_vote_receipt(&admin, &passed), &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(pda::vote_receipt(&passed, &admin).0).await.unwrap().is_none());
+}
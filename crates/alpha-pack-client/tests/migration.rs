@@ -0,0 +1,320 @@
+use alpha_pack_client::{arbitrage, bridge, competition, core, decode, pack_manager, pda};
+use alpha_pack_core::{
+    GlobalState, GlobalStateV1, Pack, PackStatus, PackV1, RecruitmentMode, RiskLevel, StrategyFocus, User, UserV1,
+};
+use anchor_lang::{AnchorSerialize, Discriminator};
+use arbitrage_executor::{GlobalArbitrageState, GlobalArbitrageStateV1};
+use competition_engine::{Competition, CompetitionStatus, CompetitionType, CompetitionV1};
+use cross_chain_bridge::{ChainInfo, CrossChainBridge, CrossChainBridgeV1};
+use ::pack_manager::{
+    EligibleVoter, PackTreasury, PackTreasuryV1, Proposal, ProposalStatus, ProposalType, ProposalV1, TreasuryToken,
+    Vote, VotingMode,
+};
+use solana_program_test::{tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+// Serialises a legacy layout the way Anchor allocated it: discriminator, Borsh data, zero padding
+fn legacy_fixture<T: Discriminator>(legacy: &impl AnchorSerialize, legacy_space: usize, owner: Pubkey) -> Account {
+    let mut data = T::discriminator().to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(8 + legacy_space, 0);
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+async fn send(ctx: &mut ProgramTestContext, ix: Instruction) -> Result<(), BanksClientError> {
+    let blockhash = ctx.get_new_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &[&ctx.payer], blockhash);
+    ctx.banks_client.process_transaction(tx).await
+}
+
+// Decodes the migrated account and checks it was grown to the current layout and stays rent exempt
+async fn fetch<T: anchor_lang::AccountDeserialize>(ctx: &mut ProgramTestContext, address: Pubkey, init_space: usize) -> T {
+    let account = ctx.banks_client.get_account(address).await.unwrap().expect("account not found");
+    assert_eq!(account.data.len(), 8 + init_space);
+    assert!(account.lamports >= Rent::default().minimum_balance(account.data.len()));
+    decode(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn migrates_legacy_fixtures_to_current_layouts() {
+    let admin = Keypair::new().pubkey();
+    let authority = Keypair::new().pubkey();
+    let pack_address = Pubkey::new_unique();
+    let competition_address = Pubkey::new_unique();
+    let (treasury_address, treasury_bump) = pda::treasury(&pack_address);
+    let (global_state_address, global_state_bump) = pda::global_state();
+
+    let global_state_v1 = GlobalStateV1 {
+        authority: admin,
+        total_packs: 1,
+        total_users: 1,
+        total_volume: 5_000,
+        bump: global_state_bump,
+        paused: false,
+    };
+    let user_v1 = UserV1 {
+        authority,
+        username: "legacy".to_string(),
+        wallet_address: authority,
+        pack_id: Some(pack_address),
+        total_trades: 10,
+        total_volume: 5_000,
+        win_rate: 60,
+        profit_loss: 250,
+        social_score: 40,
+        rank: 3,
+        created_at: 1_600_000_000,
+        updated_at: 1_600_000_000,
+    };
+    let pack_v1 = PackV1 {
+        authority,
+        name: "Legacy Wolves".to_string(),
+        description: "Created before versioning".to_string(),
+        leader: authority,
+        members: vec![authority],
+        max_members: 5,
+        status: PackStatus::Recruiting,
+        score: 12,
+        total_volume: 5_000,
+        win_rate: 60,
+        social_score: 40,
+        treasury_value: 900,
+        strategy_focus: StrategyFocus::Balanced,
+        risk_level: RiskLevel::Medium,
+        created_at: 1_600_000_000,
+        updated_at: 1_600_000_000,
+    };
+    let treasury_v1 = PackTreasuryV1 {
+        pack: pack_address,
+        authority,
+        total_value_usd: 900,
+        tokens: vec![TreasuryToken {
+            mint: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            balance: 1_000,
+            value_usd: 900,
+            allocation_percentage: 100,
+            last_price: 900_000,
+            price_updated_at: 1_600_000_000,
+        }],
+        token_count: 1,
+        bump: treasury_bump,
+        created_at: 1_600_000_000,
+        updated_at: 1_600_000_000,
+    };
+    let proposal_address = pda::proposal(&pack_address, 0).0;
+    let proposal_v1 = ProposalV1 {
+        pack: pack_address,
+        proposer: authority,
+        title: "Legacy vote".to_string(),
+        description: "Opened before vote receipts".to_string(),
+        proposal_type: ProposalType::StrategyChange,
+        execution_data: Vec::new(),
+        status: ProposalStatus::Active,
+        votes_for: 1,
+        votes_against: 0,
+        total_voters: 1,
+        voters: vec![Vote {
+            voter: pda::user(&authority).0,
+            vote: true,
+            timestamp: 1_600_000_100,
+        }],
+        created_at: 1_600_000_000,
+        voting_ends_at: 1_600_604_800,
+        executed_at: None,
+    };
+    let competition_v1 = CompetitionV1 {
+        authority: admin,
+        name: "Legacy Cup".to_string(),
+        description: "Created before versioning".to_string(),
+        competition_type: CompetitionType::TradingVolume,
+        status: CompetitionStatus::Completed,
+        entry_fee: 10,
+        prize_pool: 1_000,
+        max_participants: 8,
+        current_participants: 0,
+        participants: Vec::new(),
+        leaderboard: Vec::new(),
+        start_time: 1_600_003_600,
+        end_time: 1_600_007_200,
+        registration_deadline: 1_600_001_800,
+        created_at: 1_600_000_000,
+        updated_at: 1_600_000_000,
+    };
+    let bridge_v1 = CrossChainBridgeV1 {
+        authority: admin,
+        supported_chains: vec![ChainInfo {
+            chain_id: 1,
+            name: "Ethereum".to_string(),
+            rpc_url: "https://eth.example".to_string(),
+            is_active: true,
+        }],
+        bridge_fee_bps: 30,
+        min_transfer_amount: 1,
+        max_transfer_amount: 1_000_000,
+        total_volume: 7_000,
+        total_transfers: 3,
+        total_fees_collected: 21,
+        is_active: true,
+        created_at: 1_600_000_000,
+        updated_at: 1_600_000_000,
+    };
+    let arbitrage_state_v1 = GlobalArbitrageStateV1 {
+        authority: admin,
+        emergency_stop: false,
+        total_opportunities: 2,
+        total_executions: 1,
+        total_volume: 400,
+        total_profit: 8,
+        created_at: 1_600_000_000,
+        updated_at: 1_600_000_000,
+    };
+
+    let mut program_test = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None);
+    program_test.add_program("pack_manager", ::pack_manager::ID, None);
+    program_test.add_program("competition_engine", competition_engine::ID, None);
+    program_test.add_program("cross_chain_bridge", cross_chain_bridge::ID, None);
+    program_test.add_program("arbitrage_executor", arbitrage_executor::ID, None);
+    program_test.add_account(
+        global_state_address,
+        legacy_fixture::<GlobalState>(&global_state_v1, GlobalStateV1::INIT_SPACE, alpha_pack_core::ID),
+    );
+    program_test.add_account(
+        pda::user(&authority).0,
+        legacy_fixture::<User>(&user_v1, UserV1::INIT_SPACE, alpha_pack_core::ID),
+    );
+    program_test.add_account(
+        pack_address,
+        legacy_fixture::<Pack>(&pack_v1, PackV1::INIT_SPACE, alpha_pack_core::ID),
+    );
+    program_test.add_account(
+        treasury_address,
+        legacy_fixture::<PackTreasury>(&treasury_v1, PackTreasuryV1::INIT_SPACE, ::pack_manager::ID),
+    );
+    program_test.add_account(
+        proposal_address,
+        legacy_fixture::<Proposal>(&proposal_v1, ProposalV1::INIT_SPACE, ::pack_manager::ID),
+    );
+    program_test.add_account(
+        competition_address,
+        legacy_fixture::<Competition>(&competition_v1, CompetitionV1::INIT_SPACE, competition_engine::ID),
+    );
+    program_test.add_account(
+        pda::bridge().0,
+        legacy_fixture::<CrossChainBridge>(&bridge_v1, CrossChainBridgeV1::INIT_SPACE, cross_chain_bridge::ID),
+    );
+    program_test.add_account(
+        pda::arbitrage_state().0,
+        legacy_fixture::<GlobalArbitrageState>(
+            &arbitrage_state_v1,
+            GlobalArbitrageStateV1::INIT_SPACE,
+            arbitrage_executor::ID,
+        ),
+    );
+    let mut ctx = program_test.start_with_context().await;
+    let payer = ctx.payer.pubkey();
+
+    send(&mut ctx, core::migrate_global_state(&payer)).await.unwrap();
+    let global_state: GlobalState = fetch(&mut ctx, global_state_address, GlobalState::INIT_SPACE).await;
+    assert_eq!(global_state.version, GlobalState::VERSION);
+    assert_eq!(global_state.authority, admin);
+    assert_eq!(global_state.total_volume, 5_000);
+    assert_eq!(global_state.bump, global_state_bump);
+    assert_eq!(global_state.trade_attestor, None);
+    assert!(!global_state.verified_trades_only);
+    assert!(global_state.settlement_programs.is_empty());
+    assert_eq!(global_state.price_oracle, None);
+    assert_eq!(global_state.score_oracle, None);
+    assert_eq!(global_state.pauser, None);
+    assert_eq!(global_state.admin.pending_authority, None);
+    assert!(global_state.admin.approvers.is_empty());
+    assert_eq!(global_state.epoch_length, GlobalState::DEFAULT_EPOCH_LENGTH);
+    assert_eq!(global_state.epoch_base_index, 0);
+
+    send(&mut ctx, core::migrate_user(&payer, &authority)).await.unwrap();
+    let user: User = fetch(&mut ctx, pda::user(&authority).0, User::INIT_SPACE).await;
+    assert_eq!(user.version, User::VERSION);
+    assert_eq!(user.username, "legacy");
+    assert_eq!(user.pack_id, Some(pack_address));
+    assert_eq!(user.total_trades, 10);
+    assert_eq!(user.profit_loss, 250);
+    assert_eq!(user.rank, 3);
+    // The deployed layout has no packs_created, so created_at must not shift into it
+    assert_eq!(user.packs_created, 0);
+    assert_eq!(user.created_at, 1_600_000_000);
+    assert_eq!(user.winning_trades, 6);
+    assert_eq!(user.losing_trades, 4);
+    assert_eq!(user.win_rate_bps, 6_000);
+    assert_eq!(user.peak_profit_loss, 250);
+    assert_eq!(user.total_fees, 0);
+    assert!(user.metadata_uri.is_empty());
+    assert_eq!(user.pending_authority, None);
+
+    send(&mut ctx, core::migrate_pack(&payer, &pack_address)).await.unwrap();
+    let pack: Pack = fetch(&mut ctx, pack_address, Pack::INIT_SPACE).await;
+    assert_eq!(pack.version, Pack::VERSION);
+    assert_eq!(pack.name, "Legacy Wolves");
+    assert_eq!(pack.members, vec![authority]);
+    assert_eq!(pack.treasury_value, 900);
+    assert!(pack.recruitment_mode == RecruitmentMode::Open);
+    assert_eq!(pack.total_trades, 0);
+    assert_eq!(pack.min_social_score, 0);
+
+    send(&mut ctx, pack_manager::migrate_treasury(&payer, &pack_address)).await.unwrap();
+    let treasury: PackTreasury = fetch(&mut ctx, treasury_address, PackTreasury::INIT_SPACE).await;
+    assert_eq!(treasury.version, PackTreasury::VERSION);
+    assert_eq!(treasury.pack, pack_address);
+    assert_eq!(treasury.tokens.len(), 1);
+    assert_eq!(treasury.tokens[0].balance, 1_000);
+    assert_eq!(treasury.total_shares, 0);
+
+    send(&mut ctx, pack_manager::migrate_proposal(&payer, &pack_address, &proposal_address)).await.unwrap();
+    let proposal: Proposal = fetch(&mut ctx, proposal_address, Proposal::INIT_SPACE).await;
+    assert_eq!(proposal.version, Proposal::VERSION);
+    assert_eq!(proposal.title, "Legacy vote");
+    assert_eq!(proposal.votes_for, 1);
+    assert_eq!(proposal.eligible_voters, vec![EligibleVoter { voter: authority, weight: 1 }]);
+    assert_eq!(proposal.total_weight, 1);
+    assert!(proposal.voting_mode == VotingMode::Equal);
+    // Its votes have no receipts, so it is closed rather than left open for re-voting
+    assert!(proposal.status == ProposalStatus::Expired);
+    assert_eq!(proposal.executable_at, 0);
+
+    send(&mut ctx, competition::migrate_competition(&payer, &competition_address)).await.unwrap();
+    let competition: Competition = fetch(&mut ctx, competition_address, Competition::INIT_SPACE).await;
+    assert_eq!(competition.version, Competition::VERSION);
+    assert_eq!(competition.name, "Legacy Cup");
+    assert_eq!(competition.prize_pool, 1_000);
+
+    send(&mut ctx, bridge::migrate_bridge(&payer)).await.unwrap();
+    let bridge: CrossChainBridge = fetch(&mut ctx, pda::bridge().0, CrossChainBridge::INIT_SPACE).await;
+    assert_eq!(bridge.version, CrossChainBridge::VERSION);
+    assert_eq!(bridge.authority, admin);
+    assert_eq!(bridge.supported_chains.len(), 1);
+    assert_eq!(bridge.total_fees_collected, 21);
+    assert_eq!(bridge.admin.threshold, 0);
+
+    send(&mut ctx, arbitrage::migrate_arbitrage_state(&payer)).await.unwrap();
+    let arbitrage_state: GlobalArbitrageState =
+        fetch(&mut ctx, pda::arbitrage_state().0, GlobalArbitrageState::INIT_SPACE).await;
+    assert_eq!(arbitrage_state.version, GlobalArbitrageState::VERSION);
+    assert_eq!(arbitrage_state.total_profit, 8);
+    assert!(arbitrage_state.admin.approvers.is_empty());
+
+    // Accounts already on the current layout are rejected
+    assert!(send(&mut ctx, core::migrate_user(&payer, &authority)).await.is_err());
+    assert!(send(&mut ctx, core::migrate_pack(&payer, &pack_address)).await.is_err());
+}
@@ -0,0 +1,218 @@
+use alpha_pack_client::{arbitrage, bridge, competition, core, decode, pack_manager, pda};
+use alpha_pack_core::{
+    GlobalState, Leaderboard, LeaderboardScope, Pack, RankingMetric, RiskLevel, StrategyFocus, TradeLog, User,
+    UsernameRecord,
+};
+use anchor_lang::AccountDeserialize;
+use arbitrage_executor::GlobalArbitrageState;
+use competition_engine::CompetitionState;
+use cross_chain_bridge::{ChainInfo, CrossChainBridge};
+use ::pack_manager::{
+    EligibleVoter, GovernanceChangeAction, GovernanceConfig, ManagerState, PackTreasury, Proposal, ProposalAction,
+    ProposalStatus, ProposalType, StrategyChangeAction, VoteChoice, VoteReceipt, VotingMode,
+};
+use solana_program_test::{tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    clock::Clock,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+async fn send(ctx: &mut ProgramTestContext, ix: Instruction, signers: &[&Keypair]) {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+    ctx.banks_client.process_transaction(tx).await.unwrap();
+}
+
+// Decodes the account and checks it was allocated with exactly the program's declared space
+async fn fetch<T: AccountDeserialize>(ctx: &mut ProgramTestContext, address: Pubkey, init_space: usize) -> T {
+    let account = ctx.banks_client.get_account(address).await.unwrap().expect("account not found");
+    assert_eq!(account.data.len(), 8 + init_space);
+    decode(&account.data).unwrap()
+}
+
+#[tokio::test]
+async fn builders_match_program_seeds_and_space() {
+    let mut program_test = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None);
+    program_test.add_program("pack_manager", ::pack_manager::ID, None);
+    program_test.add_program("competition_engine", competition_engine::ID, None);
+    program_test.add_program("arbitrage_executor", arbitrage_executor::ID, None);
+    program_test.add_program("cross_chain_bridge", cross_chain_bridge::ID, None);
+    let mut ctx = program_test.start_with_context().await;
+    let admin = ctx.payer.pubkey();
+
+    send(&mut ctx, core::initialize(&admin), &[]).await;
+    let global_state: GlobalState = fetch(&mut ctx, pda::global_state().0, GlobalState::INIT_SPACE).await;
+    assert_eq!(global_state.authority, admin);
+
+    send(&mut ctx, core::create_user(&admin, "leader".to_string(), admin), &[]).await;
+    let user: User = fetch(&mut ctx, pda::user(&admin).0, User::INIT_SPACE).await;
+    assert_eq!(user.authority, admin);
+    let record: UsernameRecord = fetch(&mut ctx, pda::username("Leader").0, UsernameRecord::INIT_SPACE).await;
+    assert_eq!(record.user, pda::user(&admin).0);
+
+    // Zero-copy, so only the allocation is checked
+    send(&mut ctx, core::create_trade_log(&admin), &[]).await;
+    let trade_log = ctx.banks_client.get_account(pda::trade_log(&pda::user(&admin).0).0).await.unwrap().unwrap();
+    assert_eq!(trade_log.data.len(), 8 + TradeLog::INIT_SPACE);
+
+    send(
+        &mut ctx,
+        core::create_pack(
+            &admin,
+            0,
+            "Wolves".to_string(),
+            "Client test pack".to_string(),
+            5,
+            StrategyFocus::Balanced,
+            RiskLevel::Medium,
+        ),
+        &[],
+    )
+    .await;
+    let pack_address = pda::pack(&admin, 0).0;
+    let pack: Pack = fetch(&mut ctx, pack_address, Pack::INIT_SPACE).await;
+    assert_eq!(pack.leader, admin);
+
+    let scope = LeaderboardScope::Global;
+    send(&mut ctx, core::create_leaderboard(&admin, scope, RankingMetric::Volume, None), &[]).await;
+    send(&mut ctx, core::crank_leaderboard(&admin, scope, &[pda::user(&admin).0]), &[]).await;
+    let leaderboard: Leaderboard = fetch(&mut ctx, pda::leaderboard(&scope).0, Leaderboard::INIT_SPACE).await;
+    assert_eq!(leaderboard.rank_of(&pda::user(&admin).0), 1);
+    let user: User = fetch(&mut ctx, pda::user(&admin).0, User::INIT_SPACE).await;
+    assert_eq!(user.rank, 1);
+
+    send(&mut ctx, pack_manager::initialize_manager_state(&admin), &[]).await;
+    fetch::<ManagerState>(&mut ctx, pda::manager_state().0, ManagerState::INIT_SPACE).await;
+
+    send(&mut ctx, pack_manager::initialize_treasury(&admin, &pack_address), &[]).await;
+    let treasury: PackTreasury = fetch(&mut ctx, pda::treasury(&pack_address).0, PackTreasury::INIT_SPACE).await;
+    assert_eq!(treasury.pack, pack_address);
+
+    send(&mut ctx, pack_manager::initialize_governance_config(&admin, &pack_address), &[]).await;
+    let config: GovernanceConfig =
+        fetch(&mut ctx, pda::governance_config(&pack_address).0, GovernanceConfig::INIT_SPACE).await;
+    assert!(config.voting_mode == VotingMode::Equal);
+
+    // The voting rules change only through a passed proposal
+    let voting_mode = VotingMode::ReputationWeighted;
+    let change = ProposalAction::GovernanceChange(GovernanceChangeAction {
+        quorum_bps: 6_000,
+        threshold_bps: 6_000,
+        voting_mode,
+        voting_period: 3 * 24 * 60 * 60,
+    });
+    let change_address = pda::proposal(&pack_address, 0).0;
+    send(
+        &mut ctx,
+        pack_manager::create_proposal(
+            &admin,
+            &pack_address,
+            0,
+            "Weigh by reputation".to_string(),
+            "New voting rules".to_string(),
+            &change,
+            &pack_manager::voter_accounts(&pack_address, VotingMode::Equal, &[admin]),
+        ),
+        &[],
+    )
+    .await;
+    send(
+        &mut ctx,
+        pack_manager::vote_on_proposal(&admin, None, &pack_address, &change_address, VoteChoice::For),
+        &[],
+    )
+    .await;
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += Proposal::EXECUTION_DELAY;
+    ctx.set_sysvar(&clock);
+    send(&mut ctx, pack_manager::execute_proposal(&admin, &pack_address, &change_address, &change), &[]).await;
+    let config: GovernanceConfig =
+        fetch(&mut ctx, pda::governance_config(&pack_address).0, GovernanceConfig::INIT_SPACE).await;
+    assert_eq!((config.quorum_bps, config.threshold_bps), (6_000, 6_000));
+    assert!(config.voting_mode == voting_mode);
+
+    let action = ProposalAction::StrategyChange(StrategyChangeAction {
+        strategy_focus: StrategyFocus::Balanced,
+        risk_level: RiskLevel::Low,
+    });
+    send(
+        &mut ctx,
+        pack_manager::create_proposal(
+            &admin,
+            &pack_address,
+            1,
+            "Rebalance".to_string(),
+            "Shift into stables".to_string(),
+            &action,
+            &pack_manager::voter_accounts(&pack_address, voting_mode, &[admin]),
+        ),
+        &[],
+    )
+    .await;
+    let proposal: Proposal = fetch(&mut ctx, pda::proposal(&pack_address, 1).0, Proposal::INIT_SPACE).await;
+    assert!(proposal.proposal_type == ProposalType::StrategyChange);
+    assert!(ProposalAction::decode(&proposal.proposal_type, &proposal.execution_data).unwrap() == action);
+    // No social score or win rate yet, so the leader votes with the minimum weight
+    assert_eq!(proposal.eligible_voters, vec![EligibleVoter { voter: admin, weight: 1 }]);
+    assert_eq!(proposal.total_weight, 1);
+    assert_eq!(proposal.quorum_bps, 6_000);
+    assert_eq!(proposal.voting_ends_at - proposal.created_at, 3 * 24 * 60 * 60);
+
+    let proposal_address = pda::proposal(&pack_address, 1).0;
+    send(
+        &mut ctx,
+        pack_manager::vote_on_proposal(&admin, None, &pack_address, &proposal_address, VoteChoice::For),
+        &[],
+    )
+    .await;
+    let receipt: VoteReceipt =
+        fetch(&mut ctx, pda::vote_receipt(&proposal_address, &admin).0, VoteReceipt::INIT_SPACE).await;
+    assert!(receipt.choice == VoteChoice::For);
+    // The only member voted for, so the outcome is settled before the deadline
+    let proposal: Proposal = fetch(&mut ctx, proposal_address, Proposal::INIT_SPACE).await;
+    assert!(proposal.status == ProposalStatus::Passed);
+    assert_eq!(proposal.votes_for, 1);
+
+    // A second proposal is withdrawn before any votes and its rent reclaimed
+    let cancelled_address = pda::proposal(&pack_address, 2).0;
+    send(
+        &mut ctx,
+        pack_manager::create_proposal(
+            &admin,
+            &pack_address,
+            2,
+            "Withdrawn".to_string(),
+            "Opened by mistake".to_string(),
+            &action,
+            &pack_manager::voter_accounts(&pack_address, voting_mode, &[admin]),
+        ),
+        &[],
+    )
+    .await;
+    send(&mut ctx, pack_manager::cancel_proposal(&admin, &pack_address, &cancelled_address), &[]).await;
+    let cancelled: Proposal = fetch(&mut ctx, cancelled_address, Proposal::INIT_SPACE).await;
+    assert!(cancelled.status == ProposalStatus::Cancelled);
+    send(&mut ctx, pack_manager::close_proposal(&admin, &cancelled_address), &[]).await;
+    assert!(ctx.banks_client.get_account(cancelled_address).await.unwrap().is_none());
+
+    send(&mut ctx, competition::initialize_competition_state(&admin), &[]).await;
+    fetch::<CompetitionState>(&mut ctx, pda::competition_state().0, CompetitionState::INIT_SPACE).await;
+
+    send(&mut ctx, arbitrage::initialize_arbitrage_state(&admin), &[]).await;
+    fetch::<GlobalArbitrageState>(&mut ctx, pda::arbitrage_state().0, GlobalArbitrageState::INIT_SPACE).await;
+
+    let chain = ChainInfo {
+        chain_id: 1,
+        name: "Ethereum".to_string(),
+        rpc_url: "https://eth.example".to_string(),
+        is_active: true,
+    };
+    send(&mut ctx, bridge::initialize_bridge(&admin, vec![chain], 30, 1, 1_000_000), &[]).await;
+    let bridge: CrossChainBridge = fetch(&mut ctx, pda::bridge().0, CrossChainBridge::INIT_SPACE).await;
+    assert_eq!(bridge.authority, admin);
+}
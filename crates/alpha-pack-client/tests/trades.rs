@@ -0,0 +1,189 @@
+mod common;
+
+use alpha_pack_client::{core, pda};
+use alpha_pack_core::{
+    AlphaPackError, GlobalState, PriceFeed, RiskLevel, Role, StrategyFocus, Trade, TradeLog, TradeType, User, UserCounters,
+};
+use anchor_lang::error::ErrorCode;
+use common::{assert_error, create_mint, fetch, send, warp_by};
+use solana_program_test::{tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+
+// Zero-copy, so read past the discriminator directly
+async fn fetch_trade_log(ctx: &mut ProgramTestContext, user: &Pubkey) -> TradeLog {
+    let account = ctx.banks_client.get_account(pda::trade_log(user).0).await.unwrap().expect("trade log not found");
+    bytemuck::pod_read_unaligned(&account.data[8..])
+}
+
+async fn current_epoch(ctx: &mut ProgramTestContext) -> u64 {
+    let clock: Clock = ctx.banks_client.get_sysvar().await.unwrap();
+    let global_state: GlobalState = fetch(ctx, pda::global_state().0).await;
+    global_state.current_epoch(clock.unix_timestamp)
+}
+
+// Swaps one whole $2 token for 2.1 $1 tokens, paying 0.01 of the first as the fee
+async fn swap(
+    ctx: &mut ProgramTestContext,
+    authority: &Pubkey,
+    use_trade_log: bool,
+    trade_index: u64,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+) -> Instruction {
+    let epoch = current_epoch(ctx).await;
+    core::record_trade(
+        authority,
+        None,
+        None,
+        use_trade_log,
+        trade_index,
+        epoch,
+        TradeType::Spot,
+        from_mint,
+        to_mint,
+        1_000_000,
+        2_100_000,
+        10_000,
+        format!("sig-{trade_index}"),
+    )
+}
+
+#[tokio::test]
+async fn trades_are_valued_stored_and_closed() {
+    let program_test = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None);
+    let mut ctx = program_test.start_with_context().await;
+    let admin = ctx.payer.pubkey();
+    let user = pda::user(&admin).0;
+
+    send(&mut ctx, core::initialize(&admin), &[]).await.unwrap();
+    send(&mut ctx, core::set_role(&admin, Role::PriceOracle, Some(admin)), &[]).await.unwrap();
+    send(&mut ctx, core::create_user(&admin, "trader".to_string(), admin), &[]).await.unwrap();
+    let from_mint = create_mint(&mut ctx).await;
+    let to_mint = create_mint(&mut ctx).await;
+    send(&mut ctx, core::create_price_feed(&admin, &from_mint, 2_000_000), &[]).await.unwrap();
+    send(&mut ctx, core::create_price_feed(&admin, &to_mint, 1_000_000), &[]).await.unwrap();
+
+    // A trade in its own account is valued in quote units: $2.10 out, $2 in and $0.02 of fees
+    let ix = swap(&mut ctx, &admin, false, 0, &from_mint, &to_mint).await;
+    send(&mut ctx, ix, &[]).await.unwrap();
+    let trade: Trade = fetch(&mut ctx, pda::trade(&admin, 0).0).await;
+    assert_eq!(trade.from_value, 2_000_000);
+    assert_eq!(trade.to_value, 2_100_000);
+    assert_eq!(trade.fee_value, 20_000);
+    assert_eq!(trade.profit_loss, 80_000);
+    let trader: User = fetch(&mut ctx, user).await;
+    assert_eq!(trader.total_trades, 1);
+    assert_eq!(trader.profit_loss, 80_000);
+
+    // Indices must follow the user's trade count
+    let ix = swap(&mut ctx, &admin, false, 5, &from_mint, &to_mint).await;
+    assert_error(send(&mut ctx, ix, &[]).await, AlphaPackError::InvalidCounter);
+
+    // Stale prices are refused until the oracle posts again
+    warp_by(&mut ctx, PriceFeed::MAX_AGE + 1).await;
+    let ix = swap(&mut ctx, &admin, false, 1, &from_mint, &to_mint).await;
+    assert_error(send(&mut ctx, ix, &[]).await, AlphaPackError::StalePrice);
+    send(&mut ctx, core::update_price_feed(&admin, &from_mint, 2_000_000), &[]).await.unwrap();
+    send(&mut ctx, core::update_price_feed(&admin, &to_mint, 1_000_000), &[]).await.unwrap();
+
+    // A trade goes to exactly one of its own account and the log
+    send(&mut ctx, core::create_trade_log(&admin), &[]).await.unwrap();
+    let mut ix = swap(&mut ctx, &admin, false, 1, &from_mint, &to_mint).await;
+    ix.accounts[1] = AccountMeta::new(pda::trade_log(&user).0, false);
+    assert_error(send(&mut ctx, ix, &[]).await, AlphaPackError::InvalidTradeStorage);
+
+    // Logged trades keep their index and valuation but get no account of their own
+    for trade_index in 1..3 {
+        let ix = swap(&mut ctx, &admin, true, trade_index, &from_mint, &to_mint).await;
+        send(&mut ctx, ix, &[]).await.unwrap();
+    }
+    let trade_log = fetch_trade_log(&mut ctx, &user).await;
+    assert_eq!(trade_log.user, user);
+    assert_eq!(trade_log.total_logged, 2);
+    let entries: Vec<_> = trade_log.recent().collect();
+    assert_eq!(entries.iter().map(|entry| entry.trade_index).collect::<Vec<_>>(), vec![1, 2]);
+    assert!(entries.iter().all(|entry| entry.profit_loss == 80_000 && entry.fee_value == 20_000));
+    assert!(ctx.banks_client.get_account(pda::trade(&admin, 1).0).await.unwrap().is_none());
+    let trader: User = fetch(&mut ctx, user).await;
+    assert_eq!(trader.total_trades, 3);
+
+    // Closing returns the rent of a Trade account; a logged trade has nothing to close
+    send(&mut ctx, core::close_trade(&admin, 0), &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(pda::trade(&admin, 0).0).await.unwrap().is_none());
+    assert_error(send(&mut ctx, core::close_trade(&admin, 1), &[]).await, ErrorCode::AccountNotInitialized);
+}
+
+#[tokio::test]
+async fn closed_users_resume_their_counters() {
+    let program_test = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None);
+    let mut ctx = program_test.start_with_context().await;
+    let admin = ctx.payer.pubkey();
+
+    send(&mut ctx, core::initialize(&admin), &[]).await.unwrap();
+    send(&mut ctx, core::set_role(&admin, Role::PriceOracle, Some(admin)), &[]).await.unwrap();
+    send(&mut ctx, core::create_user(&admin, "trader".to_string(), admin), &[]).await.unwrap();
+    let from_mint = create_mint(&mut ctx).await;
+    let to_mint = create_mint(&mut ctx).await;
+    send(&mut ctx, core::create_price_feed(&admin, &from_mint, 2_000_000), &[]).await.unwrap();
+    send(&mut ctx, core::create_price_feed(&admin, &to_mint, 1_000_000), &[]).await.unwrap();
+    let ix = swap(&mut ctx, &admin, false, 0, &from_mint, &to_mint).await;
+    send(&mut ctx, ix, &[]).await.unwrap();
+    let ix = core::create_pack(
+        &admin,
+        0,
+        "Wolves".to_string(),
+        "Counter test pack".to_string(),
+        5,
+        StrategyFocus::Balanced,
+        RiskLevel::Medium,
+    );
+    send(&mut ctx, ix, &[]).await.unwrap();
+    send(&mut ctx, core::disband_pack(&admin, &pda::pack(&admin, 0).0), &[]).await.unwrap();
+
+    // A user with history can close; its counters stay behind under the authority
+    send(&mut ctx, core::close_user(&admin, "trader"), &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(pda::user(&admin).0).await.unwrap().is_none());
+    let counters: UserCounters = fetch(&mut ctx, pda::user_counters(&admin).0).await;
+    assert_eq!((counters.total_trades, counters.packs_created), (1, 1));
+
+    // Re-creating the user resumes them, so trade 0 is not recorded again over the first one
+    send(&mut ctx, core::create_user(&admin, "trader".to_string(), admin), &[]).await.unwrap();
+    assert!(ctx.banks_client.get_account(pda::user_counters(&admin).0).await.unwrap().is_none());
+    let trader: User = fetch(&mut ctx, pda::user(&admin).0).await;
+    assert_eq!((trader.total_trades, trader.packs_created), (1, 1));
+    let ix = swap(&mut ctx, &admin, false, 0, &from_mint, &to_mint).await;
+    assert_error(send(&mut ctx, ix, &[]).await, AlphaPackError::InvalidCounter);
+    let ix = swap(&mut ctx, &admin, false, 1, &from_mint, &to_mint).await;
+    send(&mut ctx, ix, &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn traders_cannot_rotate_their_authority() {
+    let program_test = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None);
+    let mut ctx = program_test.start_with_context().await;
+    let admin = ctx.payer.pubkey();
+    let successor = Keypair::new();
+    let ix = system_instruction::transfer(&admin, &successor.pubkey(), 1_000_000_000);
+    send(&mut ctx, ix, &[]).await.unwrap();
+
+    send(&mut ctx, core::initialize(&admin), &[]).await.unwrap();
+    send(&mut ctx, core::set_role(&admin, Role::PriceOracle, Some(admin)), &[]).await.unwrap();
+    send(&mut ctx, core::create_user(&admin, "trader".to_string(), admin), &[]).await.unwrap();
+    let from_mint = create_mint(&mut ctx).await;
+    let to_mint = create_mint(&mut ctx).await;
+    send(&mut ctx, core::create_price_feed(&admin, &from_mint, 2_000_000), &[]).await.unwrap();
+    send(&mut ctx, core::create_price_feed(&admin, &to_mint, 1_000_000), &[]).await.unwrap();
+    let ix = swap(&mut ctx, &admin, false, 0, &from_mint, &to_mint).await;
+    send(&mut ctx, ix, &[]).await.unwrap();
+
+    // The trade is seeded by the old authority and would be stranded by the move
+    send(&mut ctx, core::rotate_authority(&admin, Some(successor.pubkey())), &[]).await.unwrap();
+    let ix = core::accept_authority(&successor.pubkey(), &admin, "trader");
+    assert_error(send(&mut ctx, ix, &[&successor]).await, AlphaPackError::UserHasHistory);
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use alpha_pack_client::{core, pack_manager, pda};
+use alpha_pack_core::{RiskLevel, Role, StrategyFocus};
+use ::pack_manager::{MemberShare, PackManagerError, PackTreasury, TokenPrice, TreasuryToken};
+use common::{assert_error, create_mint, fetch, fund_tokens, send, token_balance, warp_by};
+use solana_program_test::{tokio, ProgramTest};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+fn price(mint: &Pubkey, price_usd: u64) -> Vec<TokenPrice> {
+    vec![TokenPrice { mint: *mint, price_usd }]
+}
+
+#[tokio::test]
+async fn shares_track_deposits_and_redemptions_at_fresh_prices() {
+    let mut program_test = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None);
+    program_test.add_program("pack_manager", ::pack_manager::ID, None);
+    let mut ctx = program_test.start_with_context().await;
+    let admin = ctx.payer.pubkey();
+    let member = Keypair::new();
+    let outsider = Keypair::new();
+    for kp in [&member, &outsider] {
+        let ix = system_instruction::transfer(&admin, &kp.pubkey(), 1_000_000_000);
+        send(&mut ctx, ix, &[]).await.unwrap();
+    }
+
+    send(&mut ctx, core::initialize(&admin), &[]).await.unwrap();
+    send(&mut ctx, core::set_role(&admin, Role::PriceOracle, Some(admin)), &[]).await.unwrap();
+    send(&mut ctx, core::create_user(&admin, "leader".to_string(), admin), &[]).await.unwrap();
+    for (kp, name) in [(&member, "member"), (&outsider, "outsider")] {
+        let ix = core::create_user(&kp.pubkey(), name.to_string(), kp.pubkey());
+        send(&mut ctx, ix, &[kp]).await.unwrap();
+    }
+    let ix = core::create_pack(
+        &admin,
+        0,
+        "Wolves".to_string(),
+        "Treasury test pack".to_string(),
+        5,
+        StrategyFocus::Balanced,
+        RiskLevel::Medium,
+    );
+    send(&mut ctx, ix, &[]).await.unwrap();
+    let pack = pda::pack(&admin, 0).0;
+    send(&mut ctx, core::join_pack(&member.pubkey(), &pack), &[&member]).await.unwrap();
+
+    let treasury_address = pda::treasury(&pack).0;
+    let mint = create_mint(&mut ctx).await;
+    // Only the pack leader opens the treasury, so nobody else can claim it first
+    let ix = pack_manager::initialize_treasury(&outsider.pubkey(), &pack);
+    assert_error(send(&mut ctx, ix, &[&outsider]).await, PackManagerError::Unauthorized);
+    send(&mut ctx, pack_manager::initialize_treasury(&admin, &pack), &[]).await.unwrap();
+    let ix = pack_manager::add_treasury_token(&outsider.pubkey(), &pack, &mint, 100);
+    assert_error(send(&mut ctx, ix, &[&outsider]).await, PackManagerError::Unauthorized);
+    send(&mut ctx, pack_manager::add_treasury_token(&admin, &pack, &mint, 100), &[]).await.unwrap();
+    for owner in [admin, member.pubkey(), outsider.pubkey()] {
+        fund_tokens(&mut ctx, &mint, &owner, 10_000_000).await;
+    }
+
+    // Nothing is priced yet, so no shares can be issued
+    assert_error(
+        send(&mut ctx, pack_manager::deposit_to_treasury(&admin, &pack, &mint, 1_000_000), &[]).await,
+        PackManagerError::StalePrice,
+    );
+
+    // The first deposit issues one share per unit of value: 1 token at $2
+    send(&mut ctx, pack_manager::update_token_prices(&admin, &pack, price(&mint, 2_000_000)), &[]).await.unwrap();
+    send(&mut ctx, pack_manager::deposit_to_treasury(&admin, &pack, &mint, 1_000_000), &[]).await.unwrap();
+    let admin_share: MemberShare = fetch(&mut ctx, pda::member_share(&treasury_address, &admin).0).await;
+    assert_eq!(admin_share.shares, 2_000_000);
+    assert_eq!(admin_share.deposited_value_usd, 2_000_000);
+
+    // After the price doubles, the same deposit buys the same number of shares at twice the value
+    send(&mut ctx, pack_manager::update_token_prices(&admin, &pack, price(&mint, 4_000_000)), &[]).await.unwrap();
+    let ix = pack_manager::deposit_to_treasury(&member.pubkey(), &pack, &mint, 1_000_000);
+    send(&mut ctx, ix, &[&member]).await.unwrap();
+    let member_share: MemberShare = fetch(&mut ctx, pda::member_share(&treasury_address, &member.pubkey()).0).await;
+    assert_eq!(member_share.shares, 2_000_000);
+    assert_eq!(member_share.deposited_value_usd, 4_000_000);
+    let treasury: PackTreasury = fetch(&mut ctx, treasury_address).await;
+    assert_eq!(treasury.total_shares, 4_000_000);
+    assert_eq!(treasury.tokens[0].balance, 2_000_000);
+    assert_eq!(treasury.total_value_usd, 8_000_000);
+
+    // Once the price ages out, deposits are refused until the oracle posts again
+    warp_by(&mut ctx, TreasuryToken::MAX_PRICE_AGE + 1).await;
+    let ix = pack_manager::deposit_to_treasury(&member.pubkey(), &pack, &mint, 500_000);
+    assert_error(send(&mut ctx, ix, &[&member]).await, PackManagerError::StalePrice);
+    send(&mut ctx, pack_manager::update_token_prices(&admin, &pack, price(&mint, 4_000_001)), &[]).await.unwrap();
+
+    // Only pack members may deposit, and a deposit must be worth at least one share
+    let ix = pack_manager::deposit_to_treasury(&outsider.pubkey(), &pack, &mint, 1_000_000);
+    assert_error(send(&mut ctx, ix, &[&outsider]).await, PackManagerError::NotPackMember);
+    assert_error(
+        send(&mut ctx, pack_manager::deposit_to_treasury(&admin, &pack, &mint, 0), &[]).await,
+        PackManagerError::DepositTooSmall,
+    );
+
+    // Redeeming a quarter of the supply pays out a quarter of the holdings
+    let admin_ata = get_associated_token_address(&admin, &mint);
+    let before = token_balance(&mut ctx, admin_ata).await;
+    send(&mut ctx, pack_manager::withdraw_from_treasury(&admin, &pack, &[mint], 1_000_000), &[]).await.unwrap();
+    assert_eq!(token_balance(&mut ctx, admin_ata).await - before, 500_000);
+    let admin_share: MemberShare = fetch(&mut ctx, pda::member_share(&treasury_address, &admin).0).await;
+    assert_eq!(admin_share.shares, 1_000_000);
+    let treasury: PackTreasury = fetch(&mut ctx, treasury_address).await;
+    assert_eq!(treasury.total_shares, 3_000_000);
+    assert_eq!(treasury.tokens[0].balance, 1_500_000);
+
+    // Nobody redeems more than they hold
+    assert_error(
+        send(&mut ctx, pack_manager::withdraw_from_treasury(&admin, &pack, &[mint], 1_000_001), &[]).await,
+        PackManagerError::InsufficientShares,
+    );
+}
@@ -0,0 +1,299 @@
+//! Decoder for the events emitted by the Alpha Pack programs.
+//!
+//! Anchor's `emit!` logs every event as a `Program data: <base64>` line holding the event
+//! discriminator followed by its Borsh encoding. `decode_logs` walks a transaction's log
+//! messages, tracks which program is executing and returns each event as an
+//! [`AlphaPackEvent`]. Current events start with an [`EventHeader`] and decode into their typed
+//! struct; payloads in any other layout, such as the headerless events logged before the header
+//! was introduced, come back as [`AlphaPackEvent::Legacy`] with their raw bytes.
+
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::fmt;
+use thiserror::Error;
+
+pub use alpha_pack_core::EventHeader;
+
+/// Programs whose `Program data:` logs are decoded; anything else is skipped
+pub const PROGRAM_IDS: [Pubkey; 6] = [
+    alpha_pack_core::ID,
+    pack_manager::ID,
+    competition_engine::ID,
+    arbitrage_executor::ID,
+    social_rewards::ID,
+    cross_chain_bridge::ID,
+];
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("invalid base64 in program data: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("event data is shorter than a discriminator")]
+    TooShort,
+    #[error("unknown event discriminator {0:?}")]
+    UnknownDiscriminator([u8; 8]),
+    #[error("program data logged outside of an Alpha Pack program invocation")]
+    UnattributedData,
+}
+
+pub type Result<T> = std::result::Result<T, DecodeError>;
+
+/// A `Program data:` line that could not be decoded, kept so a consumer can record it and move on
+#[derive(Debug)]
+pub struct UndecodableEvent {
+    /// The program that logged it, unless it was logged outside any invocation
+    pub program_id: Option<Pubkey>,
+    /// The base64 payload as logged
+    pub data: String,
+    pub error: DecodeError,
+}
+
+/// An event with a known discriminator whose payload is not in the current layout: either a
+/// headerless event logged before [`EventHeader`] existed or a schema version this decoder does
+/// not read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyEvent {
+    pub name: &'static str,
+    /// Discriminator followed by the payload, as logged
+    pub data: Vec<u8>,
+}
+
+/// An event together with the program that emitted it
+#[derive(Debug)]
+pub struct DecodedEvent {
+    pub program_id: Pubkey,
+    pub event: AlphaPackEvent,
+}
+
+// Events shared between programs (AccountMigrated, AuthorityProposed, ...) are defined in
+// alpha_pack_core and listed once; DecodedEvent::program_id tells the emitters apart
+macro_rules! alpha_pack_events {
+    ($($program:ident { $($name:ident),* $(,)? })*) => {
+        /// Every event emitted by the Alpha Pack programs
+        pub enum AlphaPackEvent {
+            $($($name($program::$name),)*)*
+            Legacy(LegacyEvent),
+        }
+
+        impl AlphaPackEvent {
+            /// The event's struct name
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($(Self::$name(_) => stringify!($name),)*)*
+                    Self::Legacy(event) => event.name,
+                }
+            }
+
+            /// The event's header; legacy payloads are not decoded, so have none
+            pub fn header(&self) -> Option<&EventHeader> {
+                match self {
+                    $($(Self::$name(event) => Some(&event.header),)*)*
+                    Self::Legacy(_) => None,
+                }
+            }
+
+            /// Discriminator followed by the Borsh payload, the bytes behind a `Program data:` log
+            pub fn data(&self) -> Vec<u8> {
+                match self {
+                    $($(Self::$name(event) => Event::data(event),)*)*
+                    Self::Legacy(event) => event.data.clone(),
+                }
+            }
+
+            /// Decode the bytes behind a `Program data:` log
+            pub fn decode(data: &[u8]) -> Result<Self> {
+                if data.len() < 8 {
+                    return Err(DecodeError::TooShort);
+                }
+                let (discriminator, payload) = data.split_at(8);
+                $($(
+                    if discriminator == <$program::$name as Discriminator>::DISCRIMINATOR {
+                        if is_current_layout(payload) {
+                            if let Ok(event) = <$program::$name as AnchorDeserialize>::try_from_slice(payload) {
+                                return Ok(Self::$name(event));
+                            }
+                        }
+                        return Ok(Self::Legacy(LegacyEvent { name: stringify!($name), data: data.to_vec() }));
+                    }
+                )*)*
+                Err(DecodeError::UnknownDiscriminator(discriminator.try_into().unwrap()))
+            }
+        }
+    };
+}
+
+alpha_pack_events! {
+    alpha_pack_core {
+        UserCreated,
+        UserRenamed,
+        UserProfileUpdated,
+        AuthorityRotationProposed,
+        AuthorityRotated,
+        UserClosed,
+        SessionKeyCreated,
+        SessionKeyRevoked,
+        PackCreated,
+        UserJoinedPack,
+        UserLeftPack,
+        RecruitmentPolicyUpdated,
+        MemberInvited,
+        ApplicationSubmitted,
+        ApplicationResolved,
+        InviteClosed,
+        ApplicationClosed,
+        MemberRemoved,
+        LeadershipTransferred,
+        PackDisbanded,
+        PackStrategyUpdated,
+        TradeRecorded,
+        RoleUpdated,
+        TradeVerificationConfigured,
+        PriceUpdated,
+        AuthorityProposed,
+        AuthorityTransferred,
+        AdminApprovalsConfigured,
+        PauseStateChanged,
+        TradeLogCreated,
+        TradeClosed,
+        EpochLengthUpdated,
+        LeaderboardConfigured,
+        LeaderboardCranked,
+        LeaderboardEntriesEvicted,
+        AccountMigrated,
+        TreasuryValueSynced,
+        SocialScoreUpdated,
+    }
+    pack_manager {
+        TreasuryTokenAdded,
+        TreasuryDeposit,
+        TreasuryWithdrawal,
+        SharesIssued,
+        SharesRedeemed,
+        TreasuryRebalanced,
+        TokenPricesUpdated,
+        ProposalCreated,
+        VoteCast,
+        VoteRecorded,
+        GovernanceConfigUpdated,
+        ProposalExecuted,
+        ProposalCancelled,
+        ProposalFinalized,
+        ProposalClosed,
+        VoteReceiptClosed,
+    }
+    competition_engine {
+        CompetitionCreated,
+        PackRegistered,
+        CompetitionStarted,
+        PackScoreUpdated,
+        CompetitionEnded,
+        PrizeAwarded,
+        TournamentCreated,
+        TournamentRoundAdvanced,
+    }
+    arbitrage_executor {
+        ArbitrageBotInitialized,
+        BotStatusChanged,
+        OpportunityCreated,
+        ArbitrageExecuted,
+        BotSettingsUpdated,
+        OpportunityClosed,
+        EmergencyStopActivated,
+    }
+    social_rewards {
+        RewardsSystemInitialized,
+        ContentPostCreated,
+        EngagementUpdated,
+        ContentWentViral,
+        RewardsClaimed,
+        ViralNFTMinted,
+        InfluenceLeaderboardUpdated,
+        SeasonalRewardsDistributed,
+    }
+    cross_chain_bridge {
+        BridgeInitialized,
+        TransferInitiated,
+        TransferCompleted,
+        IncomingTransferProcessed,
+        ChainAdded,
+        BridgeSettingsUpdated,
+        BridgeEmergencyPaused,
+    }
+}
+
+impl fmt::Debug for AlphaPackEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Legacy(event) => f.debug_tuple("Legacy").field(event).finish(),
+            _ => f
+                .debug_struct(self.name())
+                .field("header", &self.header())
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+// The header is the first field of every current event, so its version byte leads the payload.
+// A headerless payload can start with the same byte by chance, which is why the typed decode
+// must also consume the payload exactly before it is trusted.
+fn is_current_layout(payload: &[u8]) -> bool {
+    payload.first() == Some(&EventHeader::SCHEMA_VERSION)
+}
+
+/// Decode a single log line; `None` unless it is a `Program data:` line
+pub fn decode_log_line(line: &str) -> Option<Result<AlphaPackEvent>> {
+    let encoded = line.strip_prefix("Program data: ")?;
+    Some(
+        STANDARD
+            .decode(encoded.trim())
+            .map_err(DecodeError::from)
+            .and_then(|data| AlphaPackEvent::decode(&data)),
+    )
+}
+
+/// Decode every Alpha Pack event in a transaction's log messages, in emission order. Lines that
+/// fail to decode are returned in place rather than aborting the rest of the transaction.
+/// Data logged by other programs, including programs invoked through CPI, is skipped.
+pub fn decode_logs<S: AsRef<str>>(logs: &[S]) -> Vec<std::result::Result<DecodedEvent, UndecodableEvent>> {
+    let mut invocations: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs {
+        let line = line.as_ref();
+        if let Some(program_id) = parse_invoke(line) {
+            invocations.push(program_id);
+        } else if is_exit(line) {
+            invocations.pop();
+        } else if let Some(event) = decode_log_line(line) {
+            let program_id = invocations.last().copied();
+            if program_id.is_some_and(|program_id| !PROGRAM_IDS.contains(&program_id)) {
+                continue;
+            }
+            let event = match program_id {
+                Some(program_id) => event.map(|event| DecodedEvent { program_id, event }),
+                None => Err(DecodeError::UnattributedData),
+            };
+            events.push(event.map_err(|error| UndecodableEvent {
+                program_id,
+                data: line.trim_start_matches("Program data: ").trim().to_string(),
+                error,
+            }));
+        }
+    }
+    events
+}
+
+// Matches `Program <id> invoke [<depth>]`
+fn parse_invoke(line: &str) -> Option<Pubkey> {
+    let rest = line.strip_prefix("Program ")?;
+    let (program_id, depth) = rest.split_once(" invoke [")?;
+    depth.strip_suffix(']')?;
+    program_id.parse().ok()
+}
+
+// Matches `Program <id> success` and `Program <id> failed: <reason>`
+fn is_exit(line: &str) -> bool {
+    let Some((program_id, outcome)) = line.strip_prefix("Program ").and_then(|rest| rest.split_once(' ')) else {
+        return false;
+    };
+    program_id.parse::<Pubkey>().is_ok() && (outcome == "success" || outcome.starts_with("failed: "))
+}
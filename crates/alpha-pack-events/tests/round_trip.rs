@@ -0,0 +1,255 @@
+use alpha_pack_events::{
+    decode_log_line, decode_logs, AlphaPackEvent, DecodeError, DecodedEvent, EventHeader, LegacyEvent, UndecodableEvent,
+};
+use anchor_lang::{AnchorSerialize, Discriminator};
+use anchor_lang::{prelude::Pubkey, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+fn header(slot: u64) -> EventHeader {
+    EventHeader {
+        schema_version: EventHeader::SCHEMA_VERSION,
+        slot,
+    }
+}
+
+fn invoke(program_id: &Pubkey, depth: u8) -> String {
+    format!("Program {} invoke [{}]", program_id, depth)
+}
+
+fn success(program_id: &Pubkey) -> String {
+    format!("Program {} success", program_id)
+}
+
+fn program_data(data: &[u8]) -> String {
+    format!("Program data: {}", STANDARD.encode(data))
+}
+
+fn decode_all(logs: &[String]) -> Vec<DecodedEvent> {
+    decode_logs(logs).into_iter().map(|event| event.unwrap()).collect()
+}
+
+// One event from each program, as (emitting program, emitted bytes)
+fn sample_events() -> Vec<(Pubkey, Vec<u8>)> {
+    vec![
+        (
+            alpha_pack_core::ID,
+            alpha_pack_core::UserClosed {
+                header: header(0),
+                user: Pubkey::new_unique(),
+                authority: Pubkey::new_unique(),
+                timestamp: 1_700_000_000,
+            }
+            .data(),
+        ),
+        (
+            pack_manager::ID,
+            pack_manager::VoteCast {
+                header: header(1),
+                proposal: Pubkey::new_unique(),
+                voter: Pubkey::new_unique(),
+                vote: true,
+                votes_for: 3,
+                votes_against: 1,
+                timestamp: 1_700_000_001,
+            }
+            .data(),
+        ),
+        (
+            competition_engine::ID,
+            competition_engine::CompetitionStarted {
+                header: header(2),
+                competition: Pubkey::new_unique(),
+                participants: 8,
+                timestamp: 1_700_000_002,
+            }
+            .data(),
+        ),
+        (
+            arbitrage_executor::ID,
+            arbitrage_executor::EmergencyStopActivated {
+                header: header(3),
+                authority: Pubkey::new_unique(),
+                timestamp: 1_700_000_003,
+            }
+            .data(),
+        ),
+        (
+            social_rewards::ID,
+            social_rewards::ContentWentViral {
+                header: header(4),
+                post: Pubkey::new_unique(),
+                creator: Pubkey::new_unique(),
+                engagement_score: 1_200,
+                virality_score: 95,
+                timestamp: 1_700_000_004,
+            }
+            .data(),
+        ),
+        (
+            cross_chain_bridge::ID,
+            cross_chain_bridge::BridgeEmergencyPaused {
+                header: header(5),
+                bridge: Pubkey::new_unique(),
+                authority: Pubkey::new_unique(),
+                timestamp: 1_700_000_005,
+            }
+            .data(),
+        ),
+    ]
+}
+
+#[test]
+fn events_from_every_program_round_trip() {
+    for (slot, (program_id, data)) in sample_events().into_iter().enumerate() {
+        let logs = [invoke(&program_id, 1), program_data(&data), success(&program_id)];
+        let decoded = decode_all(&logs);
+
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].program_id, program_id);
+        assert_eq!(decoded[0].event.header(), Some(&header(slot as u64)));
+        assert_eq!(decoded[0].event.data(), data);
+    }
+}
+
+#[test]
+fn decodes_fields_into_the_typed_event() {
+    let user = Pubkey::new_unique();
+    let event = alpha_pack_core::UserClosed {
+        header: header(7),
+        user,
+        authority: Pubkey::new_unique(),
+        timestamp: 1_700_000_000,
+    };
+
+    match decode_log_line(&program_data(&event.data())).unwrap().unwrap() {
+        AlphaPackEvent::UserClosed(decoded) => {
+            assert_eq!(decoded.user, user);
+            assert_eq!(decoded.timestamp, 1_700_000_000);
+        }
+        other => panic!("decoded as {}", other.name()),
+    }
+}
+
+#[test]
+fn cpi_events_are_attributed_to_the_callee() {
+    let pack = Pubkey::new_unique();
+    let synced = alpha_pack_core::TreasuryValueSynced {
+        header: header(40),
+        pack,
+        treasury_value: 5_000,
+        timestamp: 1_700_000_000,
+    }
+    .data();
+    let prices = pack_manager::TokenPricesUpdated {
+        header: header(12),
+        treasury: Pubkey::new_unique(),
+        total_value: 5_000,
+        timestamp: 1_700_000_000,
+    }
+    .data();
+    let logs = [
+        invoke(&pack_manager::ID, 1),
+        "Program log: Instruction: UpdateTokenPrices".to_string(),
+        invoke(&alpha_pack_core::ID, 2),
+        program_data(&synced),
+        "Program log: Treasury value synced, success".to_string(),
+        format!("Program {} consumed 4000 of 190000 compute units", alpha_pack_core::ID),
+        success(&alpha_pack_core::ID),
+        program_data(&prices),
+        success(&pack_manager::ID),
+    ];
+
+    let decoded = decode_all(&logs);
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded[0].program_id, alpha_pack_core::ID);
+    assert_eq!(decoded[0].event.name(), "TreasuryValueSynced");
+    assert_eq!(decoded[1].program_id, pack_manager::ID);
+    assert_eq!(decoded[1].event.name(), "TokenPricesUpdated");
+}
+
+#[test]
+fn data_from_other_programs_is_skipped() {
+    let other = Pubkey::new_unique();
+    let logs = [invoke(&other, 1), program_data(&[1, 2, 3]), success(&other)];
+    assert!(decode_all(&logs).is_empty());
+}
+
+#[test]
+fn other_schema_versions_decode_as_legacy() {
+    let mut event = alpha_pack_core::TradeClosed {
+        header: header(0),
+        trade: Pubkey::new_unique(),
+        trade_index: 3,
+        authority: Pubkey::new_unique(),
+        timestamp: 1_700_000_000,
+    };
+    event.header.schema_version = EventHeader::SCHEMA_VERSION + 1;
+    let data = event.data();
+
+    let decoded = decode_log_line(&program_data(&data)).unwrap().unwrap();
+    assert_eq!(decoded.header(), None);
+    assert!(matches!(decoded, AlphaPackEvent::Legacy(LegacyEvent { name: "TradeClosed", data: ref d }) if *d == data));
+}
+
+#[test]
+fn headerless_baseline_events_decode_as_legacy() {
+    // UserClosed as logged before the header: user, authority, timestamp. The user key starts
+    // with the current schema version byte, so only the length check tells the layouts apart.
+    let mut data = alpha_pack_core::UserClosed::DISCRIMINATOR.to_vec();
+    let mut user = [7u8; 32];
+    user[0] = EventHeader::SCHEMA_VERSION;
+    (Pubkey::new_from_array(user), Pubkey::new_unique(), 1_700_000_000i64)
+        .serialize(&mut data)
+        .unwrap();
+    let logs = [invoke(&alpha_pack_core::ID, 1), program_data(&data), success(&alpha_pack_core::ID)];
+
+    let decoded = decode_all(&logs);
+    assert_eq!(decoded.len(), 1);
+    assert_eq!(decoded[0].event.name(), "UserClosed");
+    assert!(matches!(decoded[0].event, AlphaPackEvent::Legacy(_)));
+    assert_eq!(decoded[0].event.data(), data);
+}
+
+#[test]
+fn unknown_discriminators_are_rejected() {
+    let logs = [
+        invoke(&alpha_pack_core::ID, 1),
+        program_data(&[0u8; 16]),
+        success(&alpha_pack_core::ID),
+    ];
+    let decoded = decode_logs(&logs);
+    assert_eq!(decoded.len(), 1);
+    let Err(UndecodableEvent { program_id, data, error }) = &decoded[0] else {
+        panic!("unknown discriminator decoded");
+    };
+    assert_eq!(*program_id, Some(alpha_pack_core::ID));
+    assert_eq!(*data, STANDARD.encode([0u8; 16]));
+    assert!(matches!(error, DecodeError::UnknownDiscriminator(d) if *d == [0; 8]));
+}
+
+#[test]
+fn undecodable_lines_do_not_hide_the_rest() {
+    let closed = alpha_pack_core::UserClosed {
+        header: header(9),
+        user: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        timestamp: 1_700_000_000,
+    }
+    .data();
+    let logs = [
+        program_data(&closed),
+        invoke(&alpha_pack_core::ID, 1),
+        "Program data: not base64!".to_string(),
+        program_data(&closed),
+        success(&alpha_pack_core::ID),
+    ];
+
+    let decoded = decode_logs(&logs);
+    assert_eq!(decoded.len(), 3);
+    assert!(matches!(
+        &decoded[0],
+        Err(UndecodableEvent { program_id: None, error: DecodeError::UnattributedData, .. })
+    ));
+    assert!(matches!(&decoded[1], Err(UndecodableEvent { error: DecodeError::Base64(_), .. })));
+    assert_eq!(decoded[2].as_ref().unwrap().event.data(), closed);
+}
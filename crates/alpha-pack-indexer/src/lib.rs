@@ -0,0 +1,37 @@
+//! Off-chain indexer for the Alpha Pack programs.
+//!
+//! `source` reads transaction logs from a JSONL dump or a validator's RPC endpoint, and
+//! `store` decodes their events and materialises users, packs, trades, competitions,
+//! treasuries, bridge transfers and content posts into SQLite. Every decoded event is kept
+//! in the database, so the materialised tables can always be rebuilt from it.
+
+// IndexerError carries the RPC client's error, which is large but only built on failure
+#![allow(clippy::result_large_err)]
+
+pub mod source;
+pub mod store;
+
+use thiserror::Error;
+
+pub use source::TransactionLogs;
+pub use store::Store;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("sqlite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("failed to decode event: {0}")]
+    Decode(#[from] alpha_pack_events::DecodeError),
+    #[error("invalid JSONL record on line {line}: {source}")]
+    Json { line: usize, source: serde_json::Error },
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("rpc error: {0}")]
+    Rpc(#[from] solana_client::client_error::ClientError),
+    #[error("invalid transaction signature {0}")]
+    InvalidSignature(String),
+    #[error("transaction {0} is missing from its slot's block")]
+    MissingFromBlock(String),
+}
+
+pub type Result<T> = std::result::Result<T, IndexerError>;
@@ -0,0 +1,72 @@
+// IndexerError carries the RPC client's error, which is large but only built on failure
+#![allow(clippy::result_large_err)]
+
+use alpha_pack_indexer::{source, Result, Store};
+use clap::{Parser, Subcommand};
+use solana_client::rpc_client::RpcClient;
+use std::{fs::File, io::BufReader, path::PathBuf, process::ExitCode};
+
+#[derive(Parser)]
+#[command(name = "alpha-pack-indexer", about = "Index Alpha Pack program events into SQLite")]
+struct Cli {
+    /// SQLite database to create or update
+    #[arg(long, default_value = "alpha-pack.db")]
+    db: PathBuf,
+
+    /// Drop everything indexed at or after this slot and re-ingest from it
+    #[arg(long)]
+    from_slot: Option<u64>,
+
+    #[command(subcommand)]
+    source: Source,
+}
+
+#[derive(Subcommand)]
+enum Source {
+    /// Read transactions from a JSONL dump, one `{slot, block_index, signature, failed, logs}` record per line
+    Jsonl { path: PathBuf },
+    /// Fetch transactions from a validator, resuming after the last indexed slot
+    Rpc {
+        #[arg(long, default_value = "http://127.0.0.1:8899")]
+        url: String,
+    },
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let mut store = Store::open(&cli.db)?;
+    if let Some(slot) = cli.from_slot {
+        store.replay_from(slot)?;
+    }
+
+    let transactions = match cli.source {
+        // Already-ingested signatures are skipped, so a dump can be replayed in full
+        Source::Jsonl { path } => source::read_jsonl(BufReader::new(File::open(path)?), cli.from_slot.unwrap_or(0))?,
+        Source::Rpc { url } => {
+            // The last indexed slot is fetched again in case it was only partly ingested
+            let from_slot = match cli.from_slot {
+                Some(slot) => slot,
+                None => store.last_slot()?.unwrap_or(0),
+            };
+            source::fetch_rpc(&RpcClient::new(url), from_slot)?
+        }
+    };
+
+    let ingested = store.ingest_all(&transactions)?;
+    println!(
+        "ingested {} of {} transactions, last slot {}",
+        ingested,
+        transactions.len(),
+        store.last_slot()?.map_or_else(|| "none".to_string(), |slot| slot.to_string())
+    );
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Transaction log sources: JSONL dumps for tests and backfills, and a validator's RPC endpoint.
+
+use alpha_pack_events::PROGRAM_IDS;
+use serde::{Deserialize, Serialize};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::{RpcBlockConfig, RpcTransactionConfig},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, signature::Signature};
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+use std::{
+    collections::{HashMap, HashSet},
+    io::BufRead,
+    str::FromStr,
+};
+
+use crate::{IndexerError, Result};
+
+/// One transaction's log messages; also the JSONL record format, one transaction per line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionLogs {
+    pub slot: u64,
+    /// Position of the transaction in its slot's block, which orders transactions within a slot
+    pub block_index: u32,
+    pub signature: String,
+    /// Failed transactions are recorded as processed but their events are ignored
+    #[serde(default)]
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+/// Read a JSONL dump, keeping transactions at or after `from_slot` in chain order
+pub fn read_jsonl(reader: impl BufRead, from_slot: u64) -> Result<Vec<TransactionLogs>> {
+    let mut transactions = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let transaction: TransactionLogs =
+            serde_json::from_str(&line).map_err(|source| IndexerError::Json { line: index + 1, source })?;
+        if transaction.slot >= from_slot {
+            transactions.push(transaction);
+        }
+    }
+    transactions.sort_by_key(|transaction| (transaction.slot, transaction.block_index));
+    Ok(transactions)
+}
+
+/// Fetch every transaction that touched an Alpha Pack program at or after `from_slot`, oldest
+/// first. Signatures are listed per program, so each slot's block is fetched to order them by
+/// their position in it.
+pub fn fetch_rpc(rpc: &RpcClient, from_slot: u64) -> Result<Vec<TransactionLogs>> {
+    let commitment = Some(CommitmentConfig::confirmed());
+    let mut seen = HashSet::new();
+    let mut statuses = Vec::new();
+
+    for program_id in PROGRAM_IDS {
+        let mut program_statuses = Vec::new();
+        let mut before = None;
+        loop {
+            let page = rpc.get_signatures_for_address_with_config(
+                &program_id,
+                GetConfirmedSignaturesForAddress2Config {
+                    before,
+                    until: None,
+                    limit: None,
+                    commitment,
+                },
+            )?;
+            let Some(oldest) = page.last() else {
+                break;
+            };
+            let reached_start = oldest.slot < from_slot;
+            before = Some(parse_signature(&oldest.signature)?);
+            program_statuses.extend(page.into_iter().filter(|status| status.slot >= from_slot));
+            if reached_start {
+                break;
+            }
+        }
+        // Pages come newest first
+        program_statuses.reverse();
+        statuses.extend(program_statuses.into_iter().filter(|status| seen.insert(status.signature.clone())));
+    }
+
+    let mut block_indices = HashMap::new();
+    for slot in statuses.iter().map(|status| status.slot).collect::<HashSet<_>>() {
+        let block = rpc.get_block_with_config(
+            slot,
+            RpcBlockConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                transaction_details: Some(TransactionDetails::Signatures),
+                rewards: Some(false),
+                commitment,
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        for (index, signature) in block.signatures.unwrap_or_default().into_iter().enumerate() {
+            block_indices.insert(signature, index as u32);
+        }
+    }
+    let mut statuses = statuses
+        .into_iter()
+        .map(|status| {
+            let block_index = *block_indices
+                .get(&status.signature)
+                .ok_or_else(|| IndexerError::MissingFromBlock(status.signature.clone()))?;
+            Ok((block_index, status))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    statuses.sort_by_key(|(block_index, status)| (status.slot, *block_index));
+
+    statuses
+        .into_iter()
+        .map(|(block_index, status)| {
+            let transaction = rpc.get_transaction_with_config(
+                &parse_signature(&status.signature)?,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment,
+                    max_supported_transaction_version: Some(0),
+                },
+            )?;
+            let logs = transaction
+                .transaction
+                .meta
+                .and_then(|meta| Option::<Vec<String>>::from(meta.log_messages))
+                .unwrap_or_default();
+            Ok(TransactionLogs {
+                slot: status.slot,
+                block_index,
+                signature: status.signature,
+                failed: status.err.is_some(),
+                logs,
+            })
+        })
+        .collect()
+}
+
+fn parse_signature(signature: &str) -> Result<Signature> {
+    Signature::from_str(signature).map_err(|_| IndexerError::InvalidSignature(signature.to_string()))
+}
@@ -0,0 +1,742 @@
+//! SQLite state database.
+//!
+//! `transactions` and `events` are the source of truth: every ingested transaction is keyed by
+//! its signature, so ingesting it again is a no-op, and every decoded event is stored with its
+//! raw bytes. Events are ordered on chain by `(slot, block_index, log_index)`: the slot, the
+//! transaction's position in that slot's block and the event's position in the transaction.
+//! Lines that fail to decode go to `undecodable_events` with their raw payload, so one bad line
+//! neither hides the transaction's other events nor stops ingestion. The remaining tables are a
+//! fold over `events` in that order and are rebuilt from it by `replay_from`.
+
+use alpha_pack_events::{decode_logs, AlphaPackEvent, DecodedEvent, UndecodableEvent};
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+
+use crate::{Result, TransactionLogs};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_index INTEGER NOT NULL,
+    failed INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS transactions_slot ON transactions (slot);
+
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    slot INTEGER NOT NULL,
+    block_index INTEGER NOT NULL,
+    program_id TEXT NOT NULL,
+    log_index INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    data BLOB NOT NULL,
+    UNIQUE (signature, log_index)
+);
+CREATE INDEX IF NOT EXISTS events_order ON events (slot, block_index, log_index);
+
+CREATE TABLE IF NOT EXISTS undecodable_events (
+    signature TEXT NOT NULL REFERENCES transactions (signature),
+    slot INTEGER NOT NULL,
+    block_index INTEGER NOT NULL,
+    program_id TEXT,
+    log_index INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    error TEXT NOT NULL,
+    PRIMARY KEY (signature, log_index)
+);
+CREATE INDEX IF NOT EXISTS undecodable_events_slot ON undecodable_events (slot);
+
+CREATE TABLE IF NOT EXISTS users (
+    address TEXT PRIMARY KEY,
+    authority TEXT NOT NULL,
+    username TEXT NOT NULL,
+    wallet_address TEXT,
+    metadata_uri TEXT,
+    pack TEXT,
+    social_score INTEGER NOT NULL DEFAULT 0,
+    total_trades INTEGER NOT NULL DEFAULT 0,
+    total_volume INTEGER NOT NULL DEFAULT 0,
+    profit_loss INTEGER NOT NULL DEFAULT 0,
+    closed INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS packs (
+    address TEXT PRIMARY KEY,
+    leader TEXT NOT NULL,
+    name TEXT NOT NULL,
+    max_members INTEGER NOT NULL,
+    member_count INTEGER NOT NULL,
+    treasury_value INTEGER NOT NULL DEFAULT 0,
+    total_trades INTEGER NOT NULL DEFAULT 0,
+    total_volume INTEGER NOT NULL DEFAULT 0,
+    profit_loss INTEGER NOT NULL DEFAULT 0,
+    disbanded INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS trades (
+    user TEXT NOT NULL,
+    trade_index INTEGER NOT NULL,
+    trade TEXT NOT NULL,
+    pack TEXT,
+    from_amount INTEGER NOT NULL,
+    to_amount INTEGER NOT NULL,
+    volume INTEGER NOT NULL,
+    profit_loss INTEGER NOT NULL,
+    verification TEXT NOT NULL,
+    closed INTEGER NOT NULL DEFAULT 0,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY (user, trade_index)
+);
+CREATE INDEX IF NOT EXISTS trades_trade ON trades (trade);
+
+CREATE TABLE IF NOT EXISTS competitions (
+    address TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    competition_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+    entry_fee INTEGER NOT NULL,
+    prize_pool INTEGER NOT NULL,
+    max_participants INTEGER NOT NULL,
+    participants INTEGER NOT NULL DEFAULT 0,
+    winner TEXT,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS competition_entries (
+    competition TEXT NOT NULL,
+    pack TEXT NOT NULL,
+    pack_name TEXT NOT NULL,
+    leader TEXT NOT NULL,
+    score INTEGER NOT NULL DEFAULT 0,
+    rank INTEGER,
+    volume INTEGER NOT NULL DEFAULT 0,
+    profit_loss INTEGER NOT NULL DEFAULT 0,
+    place TEXT,
+    prize INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (competition, pack)
+);
+
+CREATE TABLE IF NOT EXISTS treasuries (
+    address TEXT PRIMARY KEY,
+    total_value INTEGER NOT NULL DEFAULT 0,
+    total_shares INTEGER NOT NULL DEFAULT 0,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS treasury_members (
+    treasury TEXT NOT NULL,
+    member TEXT NOT NULL,
+    shares INTEGER NOT NULL DEFAULT 0,
+    deposited_value INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (treasury, member)
+);
+
+CREATE TABLE IF NOT EXISTS treasury_tokens (
+    treasury TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    allocation_percentage INTEGER,
+    balance INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (treasury, mint)
+);
+
+CREATE TABLE IF NOT EXISTS bridge_transfers (
+    address TEXT PRIMARY KEY,
+    direction TEXT NOT NULL,
+    account TEXT NOT NULL,
+    chain TEXT NOT NULL,
+    remote_address TEXT,
+    amount INTEGER NOT NULL,
+    bridge_fee INTEGER NOT NULL,
+    nonce INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    tx_hash TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS content_posts (
+    address TEXT PRIMARY KEY,
+    creator TEXT NOT NULL,
+    pack TEXT,
+    platform TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    likes INTEGER NOT NULL DEFAULT 0,
+    shares INTEGER NOT NULL DEFAULT 0,
+    comments INTEGER NOT NULL DEFAULT 0,
+    views INTEGER NOT NULL DEFAULT 0,
+    engagement_score INTEGER NOT NULL DEFAULT 0,
+    virality_score INTEGER NOT NULL DEFAULT 0,
+    is_viral INTEGER NOT NULL DEFAULT 0,
+    rewards_claimed INTEGER NOT NULL DEFAULT 0,
+    total_reward INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+";
+
+// Tables derived from `events`, cleared and refolded on replay
+const MATERIALISED_TABLES: [&str; 10] = [
+    "users",
+    "packs",
+    "trades",
+    "competitions",
+    "competition_entries",
+    "treasuries",
+    "treasury_tokens",
+    "treasury_members",
+    "bridge_transfers",
+    "content_posts",
+];
+
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// The underlying connection, for queries against the materialised tables
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Highest slot ingested so far
+    pub fn last_slot(&self) -> Result<Option<u64>> {
+        let slot: Option<i64> = self
+            .conn
+            .query_row("SELECT MAX(slot) FROM transactions", [], |row| row.get(0))
+            .optional()?
+            .flatten();
+        Ok(slot.map(|slot| slot as u64))
+    }
+
+    /// Ingest one transaction, returning false if its signature was already ingested.
+    /// Failed transactions are recorded so they are not fetched again, but their logs are
+    /// not decoded.
+    pub fn ingest(&mut self, transaction: &TransactionLogs) -> Result<bool> {
+        let events = if transaction.failed {
+            Vec::new()
+        } else {
+            decode_logs(&transaction.logs)
+        };
+
+        let tx = self.conn.transaction()?;
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_index, failed) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                transaction.signature,
+                transaction.slot as i64,
+                transaction.block_index,
+                transaction.failed
+            ],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        for (log_index, event) in events.iter().enumerate() {
+            let DecodedEvent { program_id, event } = match event {
+                Ok(event) => event,
+                Err(UndecodableEvent { program_id, data, error }) => {
+                    tx.execute(
+                        "INSERT INTO undecodable_events (signature, slot, block_index, program_id, log_index, data, error)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        params![
+                            transaction.signature,
+                            transaction.slot as i64,
+                            transaction.block_index,
+                            optional_key(program_id),
+                            log_index as i64,
+                            data,
+                            error.to_string(),
+                        ],
+                    )?;
+                    continue;
+                }
+            };
+            tx.execute(
+                "INSERT INTO events (signature, slot, block_index, program_id, log_index, name, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    transaction.signature,
+                    transaction.slot as i64,
+                    transaction.block_index,
+                    key(program_id),
+                    log_index as i64,
+                    event.name(),
+                    event.data(),
+                ],
+            )?;
+            apply(&tx, event)?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// Ingest transactions in chain order, returning how many were new
+    pub fn ingest_all<'a>(&mut self, transactions: impl IntoIterator<Item = &'a TransactionLogs>) -> Result<usize> {
+        let mut ingested = 0;
+        for transaction in transactions {
+            if self.ingest(transaction)? {
+                ingested += 1;
+            }
+        }
+        Ok(ingested)
+    }
+
+    /// Forget every transaction at or after `slot` and rebuild the materialised tables from
+    /// the events before it, so those slots can be ingested again
+    pub fn replay_from(&mut self, slot: u64) -> Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM events WHERE slot >= ?1", [slot as i64])?;
+        tx.execute("DELETE FROM undecodable_events WHERE slot >= ?1", [slot as i64])?;
+        tx.execute("DELETE FROM transactions WHERE slot >= ?1", [slot as i64])?;
+        for table in MATERIALISED_TABLES {
+            tx.execute(&format!("DELETE FROM {}", table), [])?;
+        }
+
+        {
+            let mut stmt = tx.prepare("SELECT data FROM events ORDER BY slot, block_index, log_index")?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                let data: Vec<u8> = row.get(0)?;
+                apply(&tx, &AlphaPackEvent::decode(&data)?)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn key(pubkey: &Pubkey) -> String {
+    pubkey.to_string()
+}
+
+fn optional_key(pubkey: &Option<Pubkey>) -> Option<String> {
+    pubkey.as_ref().map(key)
+}
+
+// Fold one event into the materialised tables. Events that only touch configuration, touch
+// accounts the indexer does not materialise, or are in a legacy layout are kept in `events` and
+// otherwise ignored.
+fn apply(db: &Connection, event: &AlphaPackEvent) -> rusqlite::Result<()> {
+    use AlphaPackEvent::*;
+
+    match event {
+        // Users
+        UserCreated(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO users (address, authority, username, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?4)",
+                params![key(&e.user), key(&e.authority), e.username, e.timestamp],
+            )?;
+        }
+        UserRenamed(e) => {
+            db.execute(
+                "UPDATE users SET username = ?2, updated_at = ?3 WHERE address = ?1",
+                params![key(&e.user), e.new_username, e.timestamp],
+            )?;
+        }
+        UserProfileUpdated(e) => {
+            db.execute(
+                "UPDATE users SET wallet_address = ?2, metadata_uri = ?3, updated_at = ?4 WHERE address = ?1",
+                params![key(&e.user), key(&e.wallet_address), e.metadata_uri, e.timestamp],
+            )?;
+        }
+        AuthorityRotated(e) => {
+            // The profile moves to the PDA of the new authority, its trades and a leader's pack
+            // follow it
+            db.execute(
+                "UPDATE users SET address = ?2, authority = ?3, updated_at = ?4 WHERE address = ?1",
+                params![key(&e.old_user), key(&e.new_user), key(&e.new_authority), e.timestamp],
+            )?;
+            db.execute(
+                "UPDATE trades SET user = ?2 WHERE user = ?1",
+                params![key(&e.old_user), key(&e.new_user)],
+            )?;
+            db.execute(
+                "UPDATE packs SET leader = ?2, updated_at = ?3 WHERE leader = ?1 AND disbanded = 0",
+                params![key(&e.old_authority), key(&e.new_authority), e.timestamp],
+            )?;
+        }
+        UserClosed(e) => {
+            db.execute(
+                "UPDATE users SET closed = 1, pack = NULL, updated_at = ?2 WHERE address = ?1",
+                params![key(&e.user), e.timestamp],
+            )?;
+        }
+        SocialScoreUpdated(e) => {
+            db.execute(
+                "UPDATE users SET social_score = ?2, updated_at = ?3 WHERE address = ?1",
+                params![key(&e.user), e.new_score as i64, e.timestamp],
+            )?;
+        }
+
+        // Packs
+        PackCreated(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO packs (address, leader, name, max_members, member_count, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, 1, ?5, ?5)",
+                params![key(&e.pack), key(&e.leader), e.name, e.max_members, e.timestamp],
+            )?;
+            db.execute(
+                "UPDATE users SET pack = ?1, updated_at = ?3 WHERE authority = ?2 AND closed = 0",
+                params![key(&e.pack), key(&e.leader), e.timestamp],
+            )?;
+        }
+        UserJoinedPack(e) => {
+            set_membership(db, &e.pack, &e.user, Some(&e.pack), e.member_count, e.timestamp)?;
+        }
+        UserLeftPack(e) => {
+            set_membership(db, &e.pack, &e.user, None, e.member_count, e.timestamp)?;
+        }
+        MemberRemoved(e) => {
+            set_membership(db, &e.pack, &e.user, None, e.member_count, e.timestamp)?;
+        }
+        LeadershipTransferred(e) => {
+            db.execute(
+                "UPDATE packs SET leader = ?2, updated_at = ?3 WHERE address = ?1",
+                params![key(&e.pack), key(&e.new_leader), e.timestamp],
+            )?;
+        }
+        PackDisbanded(e) => {
+            db.execute(
+                "UPDATE packs SET disbanded = 1, member_count = 0, updated_at = ?2 WHERE address = ?1",
+                params![key(&e.pack), e.timestamp],
+            )?;
+            db.execute(
+                "UPDATE users SET pack = NULL, updated_at = ?2 WHERE pack = ?1",
+                params![key(&e.pack), e.timestamp],
+            )?;
+        }
+        TreasuryValueSynced(e) => {
+            db.execute(
+                "UPDATE packs SET treasury_value = ?2, updated_at = ?3 WHERE address = ?1",
+                params![key(&e.pack), e.treasury_value as i64, e.timestamp],
+            )?;
+        }
+
+        // Trades
+        TradeRecorded(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO trades
+                 (user, trade_index, trade, pack, from_amount, to_amount, volume, profit_loss, verification, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    key(&e.user),
+                    e.trade_index as i64,
+                    key(&e.trade),
+                    optional_key(&e.pack),
+                    e.from_amount as i64,
+                    e.to_amount as i64,
+                    e.volume as i64,
+                    e.profit_loss,
+                    format!("{:?}", e.verification),
+                    e.timestamp,
+                ],
+            )?;
+            db.execute(
+                "UPDATE users SET total_trades = total_trades + 1, total_volume = total_volume + ?2,
+                 profit_loss = profit_loss + ?3, updated_at = ?4 WHERE address = ?1",
+                params![key(&e.user), e.volume as i64, e.profit_loss, e.timestamp],
+            )?;
+            if let Some(pack) = &e.pack {
+                db.execute(
+                    "UPDATE packs SET total_trades = total_trades + 1, total_volume = total_volume + ?2,
+                     profit_loss = profit_loss + ?3, updated_at = ?4 WHERE address = ?1",
+                    params![key(pack), e.volume as i64, e.profit_loss, e.timestamp],
+                )?;
+            }
+        }
+        TradeClosed(e) => {
+            db.execute("UPDATE trades SET closed = 1 WHERE trade = ?1", params![key(&e.trade)])?;
+        }
+
+        // Treasuries
+        TreasuryTokenAdded(e) => {
+            touch_treasury(db, &e.treasury, e.timestamp)?;
+            db.execute(
+                "INSERT INTO treasury_tokens (treasury, mint, allocation_percentage) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (treasury, mint) DO UPDATE SET allocation_percentage = excluded.allocation_percentage",
+                params![key(&e.treasury), key(&e.mint), e.allocation_percentage],
+            )?;
+        }
+        TreasuryDeposit(e) => {
+            touch_treasury(db, &e.treasury, e.timestamp)?;
+            adjust_balance(db, &e.treasury, &e.mint, e.amount as i64)?;
+        }
+        TreasuryWithdrawal(e) => {
+            touch_treasury(db, &e.treasury, e.timestamp)?;
+            adjust_balance(db, &e.treasury, &e.mint, -(e.amount as i64))?;
+        }
+        SharesIssued(e) => {
+            set_treasury_shares(db, &e.treasury, e.total_shares, e.treasury_value, e.timestamp)?;
+            db.execute(
+                "INSERT INTO treasury_members (treasury, member, shares, deposited_value) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (treasury, member) DO UPDATE SET shares = shares + excluded.shares,
+                 deposited_value = deposited_value + excluded.deposited_value",
+                params![key(&e.treasury), key(&e.member), e.shares as i64, e.value_usd as i64],
+            )?;
+        }
+        SharesRedeemed(e) => {
+            set_treasury_shares(db, &e.treasury, e.total_shares, e.treasury_value, e.timestamp)?;
+            db.execute(
+                "UPDATE treasury_members SET shares = shares - ?3 WHERE treasury = ?1 AND member = ?2",
+                params![key(&e.treasury), key(&e.member), e.shares as i64],
+            )?;
+        }
+        TreasuryRebalanced(e) => {
+            set_treasury_value(db, &e.treasury, e.total_value, e.timestamp)?;
+        }
+        TokenPricesUpdated(e) => {
+            set_treasury_value(db, &e.treasury, e.total_value, e.timestamp)?;
+        }
+
+        // Competitions
+        CompetitionCreated(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO competitions
+                 (address, name, competition_type, status, entry_fee, prize_pool, max_participants,
+                  start_time, end_time, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, 'Registration', ?4, ?5, ?6, ?7, ?8, ?9, ?9)",
+                params![
+                    key(&e.competition),
+                    e.name,
+                    format!("{:?}", e.competition_type),
+                    e.entry_fee as i64,
+                    e.prize_pool as i64,
+                    e.max_participants,
+                    e.start_time,
+                    e.end_time,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        PackRegistered(e) => {
+            db.execute(
+                "INSERT OR IGNORE INTO competition_entries (competition, pack, pack_name, leader) VALUES (?1, ?2, ?3, ?4)",
+                params![key(&e.competition), key(&e.pack), e.pack_name, key(&e.leader)],
+            )?;
+            db.execute(
+                "UPDATE competitions SET participants = participants + 1, prize_pool = prize_pool + ?2,
+                 updated_at = ?3 WHERE address = ?1",
+                params![key(&e.competition), e.entry_fee as i64, e.timestamp],
+            )?;
+        }
+        CompetitionStarted(e) => {
+            db.execute(
+                "UPDATE competitions SET status = 'Active', participants = ?2, updated_at = ?3 WHERE address = ?1",
+                params![key(&e.competition), e.participants, e.timestamp],
+            )?;
+        }
+        PackScoreUpdated(e) => {
+            db.execute(
+                "UPDATE competition_entries SET score = ?3, rank = ?4, volume = ?5, profit_loss = ?6
+                 WHERE competition = ?1 AND pack = ?2",
+                params![
+                    key(&e.competition),
+                    key(&e.pack),
+                    e.new_score as i64,
+                    e.rank,
+                    e.volume as i64,
+                    e.profit_loss,
+                ],
+            )?;
+        }
+        CompetitionEnded(e) => {
+            db.execute(
+                "UPDATE competitions SET status = 'Completed', winner = ?2, participants = ?3, prize_pool = ?4,
+                 updated_at = ?5 WHERE address = ?1",
+                params![
+                    key(&e.competition),
+                    optional_key(&e.winner),
+                    e.total_participants,
+                    e.total_prize_pool as i64,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        PrizeAwarded(e) => {
+            db.execute(
+                "UPDATE competition_entries SET place = ?3, prize = ?4 WHERE competition = ?1 AND pack = ?2",
+                params![key(&e.competition), key(&e.pack), e.place, e.prize_amount as i64],
+            )?;
+        }
+
+        // Bridge transfers
+        TransferInitiated(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO bridge_transfers
+                 (address, direction, account, chain, remote_address, amount, bridge_fee, nonce, status,
+                  created_at, updated_at)
+                 VALUES (?1, 'outgoing', ?2, ?3, ?4, ?5, ?6, ?7, 'Pending', ?8, ?8)",
+                params![
+                    key(&e.transfer),
+                    key(&e.initiator),
+                    format!("{:?}", e.target_chain),
+                    e.target_address,
+                    e.amount as i64,
+                    e.bridge_fee as i64,
+                    e.nonce as i64,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        TransferCompleted(e) => {
+            db.execute(
+                "UPDATE bridge_transfers SET status = ?2, tx_hash = ?3, updated_at = ?4 WHERE address = ?1",
+                params![
+                    key(&e.transfer),
+                    if e.success { "Completed" } else { "Failed" },
+                    e.tx_hash,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        IncomingTransferProcessed(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO bridge_transfers
+                 (address, direction, account, chain, amount, bridge_fee, nonce, status, created_at, updated_at)
+                 VALUES (?1, 'incoming', ?2, ?3, ?4, 0, ?5, 'Completed', ?6, ?6)",
+                params![
+                    key(&e.transfer),
+                    key(&e.recipient),
+                    format!("{:?}", e.source_chain),
+                    e.amount as i64,
+                    e.nonce as i64,
+                    e.timestamp,
+                ],
+            )?;
+        }
+
+        // Content posts
+        ContentPostCreated(e) => {
+            db.execute(
+                "INSERT OR REPLACE INTO content_posts (address, creator, pack, platform, content_type, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)",
+                params![
+                    key(&e.post),
+                    key(&e.creator),
+                    optional_key(&e.pack),
+                    format!("{:?}", e.platform),
+                    format!("{:?}", e.content_type),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        EngagementUpdated(e) => {
+            db.execute(
+                "UPDATE content_posts SET likes = ?2, shares = ?3, comments = ?4, views = ?5, engagement_score = ?6,
+                 virality_score = ?7, updated_at = ?8 WHERE address = ?1",
+                params![
+                    key(&e.post),
+                    e.likes as i64,
+                    e.shares as i64,
+                    e.comments as i64,
+                    e.views as i64,
+                    e.engagement_score as i64,
+                    e.virality_score as i64,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ContentWentViral(e) => {
+            db.execute(
+                "UPDATE content_posts SET is_viral = 1, updated_at = ?2 WHERE address = ?1",
+                params![key(&e.post), e.timestamp],
+            )?;
+        }
+        RewardsClaimed(e) => {
+            db.execute(
+                "UPDATE content_posts SET rewards_claimed = 1, total_reward = ?2, updated_at = ?3 WHERE address = ?1",
+                params![key(&e.post), e.total_reward as i64, e.timestamp],
+            )?;
+        }
+
+        _ => {}
+    }
+    Ok(())
+}
+
+fn set_membership(
+    db: &Connection,
+    pack: &Pubkey,
+    user: &Pubkey,
+    user_pack: Option<&Pubkey>,
+    member_count: u8,
+    timestamp: i64,
+) -> rusqlite::Result<()> {
+    db.execute(
+        "UPDATE users SET pack = ?2, updated_at = ?3 WHERE address = ?1",
+        params![key(user), user_pack.map(key), timestamp],
+    )?;
+    db.execute(
+        "UPDATE packs SET member_count = ?2, updated_at = ?3 WHERE address = ?1",
+        params![key(pack), member_count, timestamp],
+    )?;
+    Ok(())
+}
+
+// Treasuries have no creation event, so the first event that mentions one creates its row
+fn touch_treasury(db: &Connection, treasury: &Pubkey, timestamp: i64) -> rusqlite::Result<()> {
+    db.execute(
+        "INSERT INTO treasuries (address, updated_at) VALUES (?1, ?2)
+         ON CONFLICT (address) DO UPDATE SET updated_at = excluded.updated_at",
+        params![key(treasury), timestamp],
+    )?;
+    Ok(())
+}
+
+fn set_treasury_value(db: &Connection, treasury: &Pubkey, total_value: u64, timestamp: i64) -> rusqlite::Result<()> {
+    touch_treasury(db, treasury, timestamp)?;
+    db.execute(
+        "UPDATE treasuries SET total_value = ?2 WHERE address = ?1",
+        params![key(treasury), total_value as i64],
+    )?;
+    Ok(())
+}
+
+fn set_treasury_shares(
+    db: &Connection,
+    treasury: &Pubkey,
+    total_shares: u64,
+    total_value: u64,
+    timestamp: i64,
+) -> rusqlite::Result<()> {
+    touch_treasury(db, treasury, timestamp)?;
+    db.execute(
+        "UPDATE treasuries SET total_shares = ?2, total_value = ?3 WHERE address = ?1",
+        params![key(treasury), total_shares as i64, total_value as i64],
+    )?;
+    Ok(())
+}
+
+fn adjust_balance(db: &Connection, treasury: &Pubkey, mint: &Pubkey, delta: i64) -> rusqlite::Result<()> {
+    db.execute(
+        "INSERT INTO treasury_tokens (treasury, mint, balance) VALUES (?1, ?2, ?3)
+         ON CONFLICT (treasury, mint) DO UPDATE SET balance = balance + excluded.balance",
+        params![key(treasury), key(mint), delta],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,309 @@
+use alpha_pack_core::{EventHeader, TradeVerification};
+use alpha_pack_indexer::{source::read_jsonl, Store, TransactionLogs};
+use anchor_lang::{prelude::Pubkey, Event};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+fn header(slot: u64) -> EventHeader {
+    EventHeader {
+        schema_version: EventHeader::SCHEMA_VERSION,
+        slot,
+    }
+}
+
+// A transaction at `block_index` in its slot invoking `program_id` once and emitting `events` from it
+fn transaction(
+    slot: u64,
+    block_index: u32,
+    signature: &str,
+    program_id: Pubkey,
+    events: &[Vec<u8>],
+) -> TransactionLogs {
+    let mut logs = vec![format!("Program {} invoke [1]", program_id)];
+    logs.extend(events.iter().map(|data| format!("Program data: {}", STANDARD.encode(data))));
+    logs.push(format!("Program {} success", program_id));
+    TransactionLogs {
+        slot,
+        block_index,
+        signature: signature.to_string(),
+        failed: false,
+        logs,
+    }
+}
+
+struct Fixture {
+    user: Pubkey,
+    pack: Pubkey,
+    treasury: Pubkey,
+    mint: Pubkey,
+    transactions: Vec<TransactionLogs>,
+}
+
+fn trade(slot: u64, user: Pubkey, pack: Pubkey, trade_index: u64, volume: u64, timestamp: i64) -> Vec<u8> {
+    alpha_pack_core::TradeRecorded {
+        header: header(slot),
+        trade: Pubkey::new_unique(),
+        trade_index,
+        user,
+        pack: Some(pack),
+        from_amount: volume,
+        to_amount: volume + 10,
+        volume,
+        profit_loss: 10,
+        verification: TradeVerification::Attested,
+        timestamp,
+    }
+    .data()
+}
+
+// A user creates a pack (slot 10), trades twice (slots 11 and 12) and the pack's treasury
+// takes a deposit for 250 shares (slot 12)
+fn fixture() -> Fixture {
+    let authority = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let pack = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+
+    let created = alpha_pack_core::UserCreated {
+        header: header(10),
+        user,
+        authority,
+        username: "alpha".to_string(),
+        timestamp: 1_000,
+    }
+    .data();
+    let pack_created = alpha_pack_core::PackCreated {
+        header: header(10),
+        pack,
+        leader: authority,
+        name: "wolves".to_string(),
+        max_members: 10,
+        timestamp: 1_001,
+    }
+    .data();
+    let deposit = pack_manager::TreasuryDeposit {
+        header: header(12),
+        treasury,
+        depositor: authority,
+        mint,
+        amount: 500,
+        timestamp: 1_003,
+    }
+    .data();
+    let shares = pack_manager::SharesIssued {
+        header: header(12),
+        treasury,
+        member: authority,
+        shares: 250,
+        value_usd: 250,
+        total_shares: 250,
+        treasury_value: 250,
+        timestamp: 1_003,
+    }
+    .data();
+
+    Fixture {
+        user,
+        pack,
+        treasury,
+        mint,
+        transactions: vec![
+            transaction(10, 0, "tx-create", alpha_pack_core::ID, &[created, pack_created]),
+            transaction(11, 3, "tx-trade-0", alpha_pack_core::ID, &[trade(11, user, pack, 0, 100, 1_002)]),
+            transaction(12, 1, "tx-trade-1", alpha_pack_core::ID, &[trade(12, user, pack, 1, 300, 1_003)]),
+            transaction(12, 4, "tx-deposit", pack_manager::ID, &[deposit, shares]),
+        ],
+    }
+}
+
+fn user_stats(store: &Store, user: &Pubkey) -> (Option<String>, i64, i64) {
+    store
+        .connection()
+        .query_row(
+            "SELECT pack, total_trades, total_volume FROM users WHERE address = ?1",
+            [user.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap()
+}
+
+fn count(store: &Store, table: &str) -> i64 {
+    store
+        .connection()
+        .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+        .unwrap()
+}
+
+fn treasury_balance(store: &Store, treasury: &Pubkey, mint: &Pubkey) -> Option<i64> {
+    store
+        .connection()
+        .query_row(
+            "SELECT balance FROM treasury_tokens WHERE treasury = ?1 AND mint = ?2",
+            [treasury.to_string(), mint.to_string()],
+            |row| row.get(0),
+        )
+        .ok()
+}
+
+#[test]
+fn events_are_materialised() {
+    let fixture = fixture();
+    let mut store = Store::open_in_memory().unwrap();
+    assert_eq!(store.ingest_all(&fixture.transactions).unwrap(), 4);
+
+    assert_eq!(
+        user_stats(&store, &fixture.user),
+        (Some(fixture.pack.to_string()), 2, 400)
+    );
+    let (members, pack_volume): (i64, i64) = store
+        .connection()
+        .query_row(
+            "SELECT member_count, total_volume FROM packs WHERE address = ?1",
+            [fixture.pack.to_string()],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((members, pack_volume), (1, 400));
+
+    let verification: String = store
+        .connection()
+        .query_row("SELECT verification FROM trades WHERE trade_index = 1", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(verification, "Attested");
+    assert_eq!(treasury_balance(&store, &fixture.treasury, &fixture.mint), Some(500));
+    let (shares, total_shares): (i64, i64) = store
+        .connection()
+        .query_row(
+            "SELECT m.shares, t.total_shares FROM treasury_members m JOIN treasuries t ON t.address = m.treasury",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((shares, total_shares), (250, 250));
+    assert_eq!(count(&store, "events"), 6);
+    assert_eq!(store.last_slot().unwrap(), Some(12));
+}
+
+#[test]
+fn reingesting_is_a_no_op() {
+    let fixture = fixture();
+    let mut store = Store::open_in_memory().unwrap();
+    store.ingest_all(&fixture.transactions).unwrap();
+
+    assert_eq!(store.ingest_all(&fixture.transactions).unwrap(), 0);
+    assert_eq!(user_stats(&store, &fixture.user).1, 2);
+    assert_eq!(user_stats(&store, &fixture.user).2, 400);
+    assert_eq!(treasury_balance(&store, &fixture.treasury, &fixture.mint), Some(500));
+    assert_eq!(count(&store, "events"), 6);
+}
+
+#[test]
+fn replay_from_slot_rebuilds_state() {
+    let fixture = fixture();
+    let mut store = Store::open_in_memory().unwrap();
+    store.ingest_all(&fixture.transactions).unwrap();
+
+    store.replay_from(12).unwrap();
+    assert_eq!(user_stats(&store, &fixture.user), (Some(fixture.pack.to_string()), 1, 100));
+    assert_eq!(treasury_balance(&store, &fixture.treasury, &fixture.mint), None);
+    assert_eq!(count(&store, "treasury_members"), 0);
+    assert_eq!(count(&store, "trades"), 1);
+    assert_eq!(store.last_slot().unwrap(), Some(11));
+
+    let replayed: Vec<_> = fixture.transactions.iter().filter(|tx| tx.slot >= 12).collect();
+    assert_eq!(store.ingest_all(replayed).unwrap(), 2);
+    assert_eq!(user_stats(&store, &fixture.user), (Some(fixture.pack.to_string()), 2, 400));
+    assert_eq!(treasury_balance(&store, &fixture.treasury, &fixture.mint), Some(500));
+}
+
+#[test]
+fn failed_transactions_are_skipped() {
+    let fixture = fixture();
+    let mut store = Store::open_in_memory().unwrap();
+    let mut transactions = fixture.transactions;
+    transactions[2].failed = true;
+
+    assert_eq!(store.ingest_all(&transactions).unwrap(), 4);
+    assert_eq!(user_stats(&store, &fixture.user).1, 1);
+    assert_eq!(count(&store, "transactions"), 4);
+    assert_eq!(count(&store, "events"), 5);
+}
+
+#[test]
+fn jsonl_dumps_are_filtered_by_slot() {
+    let fixture = fixture();
+    let dump: String = fixture
+        .transactions
+        .iter()
+        .map(|tx| serde_json::to_string(tx).unwrap() + "\n")
+        .collect();
+
+    let transactions = read_jsonl(dump.as_bytes(), 11).unwrap();
+    assert_eq!(transactions, fixture.transactions[1..]);
+}
+
+#[test]
+fn jsonl_dumps_are_read_in_block_order() {
+    let fixture = fixture();
+    let dump: String = fixture
+        .transactions
+        .iter()
+        .rev()
+        .map(|tx| serde_json::to_string(tx).unwrap() + "\n")
+        .collect();
+
+    let transactions = read_jsonl(dump.as_bytes(), 0).unwrap();
+    assert_eq!(transactions, fixture.transactions);
+}
+
+#[test]
+fn undecodable_events_are_recorded_and_skipped() {
+    let fixture = fixture();
+    let mut store = Store::open_in_memory().unwrap();
+    let mut transactions = fixture.transactions;
+    // An event this indexer does not know, logged ahead of a trade in the same transaction
+    let trade = trade(11, fixture.user, fixture.pack, 0, 100, 1_002);
+    transactions[1] = transaction(11, 3, "tx-trade-0", alpha_pack_core::ID, &[vec![9; 16], trade]);
+
+    assert_eq!(store.ingest_all(&transactions).unwrap(), 4);
+    assert_eq!(user_stats(&store, &fixture.user), (Some(fixture.pack.to_string()), 2, 400));
+    assert_eq!(count(&store, "events"), 6);
+    let (log_index, data): (i64, String) = store
+        .connection()
+        .query_row("SELECT log_index, data FROM undecodable_events WHERE signature = 'tx-trade-0'", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!((log_index, data), (0, STANDARD.encode([9u8; 16])));
+
+    store.replay_from(11).unwrap();
+    assert_eq!(count(&store, "undecodable_events"), 0);
+}
+
+#[test]
+fn rotated_users_keep_their_trades() {
+    let fixture = fixture();
+    let mut store = Store::open_in_memory().unwrap();
+    store.ingest_all(&fixture.transactions).unwrap();
+
+    let new_user = Pubkey::new_unique();
+    let rotated = alpha_pack_core::AuthorityRotated {
+        header: header(13),
+        old_user: fixture.user,
+        new_user,
+        old_authority: Pubkey::new_unique(),
+        new_authority: Pubkey::new_unique(),
+        timestamp: 1_004,
+    }
+    .data();
+    store
+        .ingest(&transaction(13, 0, "tx-rotate", alpha_pack_core::ID, &[rotated]))
+        .unwrap();
+
+    assert_eq!(user_stats(&store, &new_user), (Some(fixture.pack.to_string()), 2, 400));
+    let trades: i64 = store
+        .connection()
+        .query_row("SELECT COUNT(*) FROM trades WHERE user = ?1", [new_user.to_string()], |row| row.get(0))
+        .unwrap();
+    assert_eq!(trades, 2);
+}
@@ -1,22 +1,44 @@
+// Anchor handlers take one argument per instruction field and return its large Error type
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_lang::solana_program::pubkey;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{TokenAccount, Mint};
+use anchor_lang::solana_program::sysvar::instructions::{self as sysvar_instructions, load_current_index_checked, load_instruction_at_checked};
 
 declare_id!("AlphaPackCoreProgram111111111111111111111");
 
+// pack_manager signs sync_treasury_value with its treasury PDA
+pub const PACK_MANAGER_PROGRAM_ID: Pubkey = pubkey!("PackManager1111111111111111111111111111111");
+
+// social_rewards signs update_social_score with its rewards_system PDA
+pub const SOCIAL_REWARDS_PROGRAM_ID: Pubkey = pubkey!("SocialRewards111111111111111111111111111111");
+
 #[program]
 pub mod alpha_pack_core {
     use super::*;
 
     /// Initialize the Alpha Pack program
-    pub fn initialize(ctx: Context<Initialize>, bump: u8) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let global_state = &mut ctx.accounts.global_state;
+        global_state.version = GlobalState::VERSION;
         global_state.authority = ctx.accounts.authority.key();
         global_state.total_packs = 0;
         global_state.total_users = 0;
         global_state.total_volume = 0;
-        global_state.bump = bump;
+        global_state.bump = *ctx.bumps.get("global_state").unwrap();
         global_state.paused = false;
+        global_state.trade_attestor = None;
+        global_state.verified_trades_only = false;
+        global_state.settlement_programs = Vec::new();
+        global_state.price_oracle = None;
+        global_state.score_oracle = None;
+        global_state.pauser = None;
+        global_state.admin = AdminConfig::default();
+        global_state.epoch_length = GlobalState::DEFAULT_EPOCH_LENGTH;
+        global_state.epoch_base_index = 0;
+        global_state.epoch_base_time = Clock::get()?.unix_timestamp;
         
         msg!("Alpha Pack program initialized");
         Ok(())
@@ -29,8 +51,10 @@ pub mod alpha_pack_core {
         wallet_address: Pubkey,
     ) -> Result<()> {
         require!(username.len() <= 50, AlphaPackError::UsernameTooLong);
+        let normalized = normalize_username(&username)?;
         
         let user = &mut ctx.accounts.user;
+        user.version = User::VERSION;
         user.authority = ctx.accounts.authority.key();
         user.username = username;
         user.wallet_address = wallet_address;
@@ -41,14 +65,44 @@ pub mod alpha_pack_core {
         user.profit_loss = 0;
         user.social_score = 0;
         user.rank = 0;
+        user.packs_created = 0;
         user.created_at = Clock::get()?.unix_timestamp;
         user.updated_at = Clock::get()?.unix_timestamp;
+        user.winning_trades = 0;
+        user.losing_trades = 0;
+        user.win_rate_bps = 0;
+        user.peak_profit_loss = 0;
+        user.max_drawdown = 0;
+        user.best_trade = 0;
+        user.worst_trade = 0;
+        user.current_streak = 0;
+        user.total_fees = 0;
+        user.metadata_uri = String::new();
+        user.pending_authority = None;
+        user.session_keys = 0;
+        
+        // Resume the counters of a closed user of this authority, as trade and pack addresses
+        // are seeded by them
+        let counters_info = ctx.accounts.user_counters.to_account_info();
+        if counters_info.owner == &crate::ID {
+            let counters = UserCounters::try_deserialize(&mut &counters_info.try_borrow_data()?[..])?;
+            user.total_trades = counters.total_trades;
+            user.packs_created = counters.packs_created;
+            close_record(&counters_info, &ctx.accounts.authority.to_account_info())?;
+        }
         
         // Update global state
         let global_state = &mut ctx.accounts.global_state;
         global_state.total_users = global_state.total_users.checked_add(1).unwrap();
         
+        let username_record = &mut ctx.accounts.username_record;
+        username_record.username = normalized;
+        username_record.user = user.key();
+        username_record.created_at = user.created_at;
+        username_record.bump = *ctx.bumps.get("username_record").unwrap();
+        
         emit!(UserCreated {
+            header: EventHeader::now()?,
             user: user.key(),
             authority: user.authority,
             username: user.username.clone(),
@@ -59,9 +113,245 @@ pub mod alpha_pack_core {
         Ok(())
     }
 
+    /// Release the user's current handle and claim a new one
+    pub fn rename_user(ctx: Context<RenameUser>, new_username: String) -> Result<()> {
+        require!(new_username.len() <= 50, AlphaPackError::UsernameTooLong);
+        let normalized = normalize_username(&new_username)?;
+        let user_key = ctx.accounts.user.key();
+        
+        // Users created before the registry may have no record, or share a handle that
+        // someone else has since registered; only a record owned by this user is released
+        let old_record = ctx.accounts.old_username_record.to_account_info();
+        if old_record.owner == &crate::ID {
+            let record = UsernameRecord::try_deserialize(&mut &old_record.try_borrow_data()?[..])?;
+            if record.user == user_key {
+                close_record(&old_record, &ctx.accounts.authority.to_account_info())?;
+            }
+        }
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let username_record = &mut ctx.accounts.new_username_record;
+        username_record.username = normalized;
+        username_record.user = user_key;
+        username_record.created_at = current_time;
+        username_record.bump = *ctx.bumps.get("new_username_record").unwrap();
+        
+        let user = &mut ctx.accounts.user;
+        let old_username = std::mem::replace(&mut user.username, new_username);
+        user.updated_at = current_time;
+        
+        emit!(UserRenamed {
+            header: EventHeader::now()?,
+            user: user_key,
+            old_username,
+            new_username: user.username.clone(),
+            timestamp: current_time,
+        });
+        
+        msg!("User renamed to {}", user.username);
+        Ok(())
+    }
+
+    /// Update the user's payout wallet and profile metadata
+    pub fn update_user_profile(
+        ctx: Context<UpdateUserProfile>,
+        wallet_address: Option<Pubkey>,
+        metadata_uri: Option<String>,
+    ) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        
+        if let Some(wallet_address) = wallet_address {
+            user.wallet_address = wallet_address;
+        }
+        if let Some(metadata_uri) = metadata_uri {
+            require!(metadata_uri.len() <= User::MAX_METADATA_URI_LENGTH, AlphaPackError::MetadataUriTooLong);
+            user.metadata_uri = metadata_uri;
+        }
+        user.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(UserProfileUpdated {
+            header: EventHeader::now()?,
+            user: user.key(),
+            wallet_address: user.wallet_address,
+            metadata_uri: user.metadata_uri.clone(),
+            timestamp: user.updated_at,
+        });
+        
+        msg!("Profile updated for {}", user.username);
+        Ok(())
+    }
+
+    /// Nominate a new authority for the user; `None` cancels a pending rotation
+    pub fn rotate_authority(ctx: Context<RotateAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        
+        if let Some(new_authority) = new_authority {
+            require_keys_neq!(new_authority, user.authority, AlphaPackError::InvalidAuthority);
+        }
+        user.pending_authority = new_authority;
+        user.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(AuthorityRotationProposed {
+            header: EventHeader::now()?,
+            user: user.key(),
+            authority: user.authority,
+            pending_authority: new_authority,
+            timestamp: user.updated_at,
+        });
+        
+        msg!("Authority rotation updated for {}", user.username);
+        Ok(())
+    }
+
+    /// Accept a pending rotation, moving the user to the PDA of the new authority. The user must
+    /// first leave its pack and revoke its session keys, which reference the old authority, and
+    /// must have no trades, trade log or social score: trades, the trade log and epoch stats are
+    /// PDAs of the old authority or user and cannot move with it. A global leaderboard entry is
+    /// re-keyed to the new user.
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let old_user_key = ctx.accounts.old_user.key();
+        let new_user_key = ctx.accounts.new_user.key();
+        let new_authority = ctx.accounts.new_authority.key();
+        let old_authority = ctx.accounts.old_user.authority;
+        let current_time = Clock::get()?.unix_timestamp;
+        
+        // Point the registry entry at the new PDA; users created before the registry have none
+        let record_info = ctx.accounts.username_record.to_account_info();
+        if record_info.owner == &crate::ID {
+            let mut record = UsernameRecord::try_deserialize(&mut &record_info.try_borrow_data()?[..])?;
+            if record.user == old_user_key {
+                record.user = new_user_key;
+                record.try_serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+            }
+        }
+        
+        let leaderboard_info = ctx.accounts.global_leaderboard.to_account_info();
+        if leaderboard_info.owner == &crate::ID {
+            let mut leaderboard = Leaderboard::try_deserialize(&mut &leaderboard_info.try_borrow_data()?[..])?;
+            if let Some(entry) = leaderboard.entries.iter_mut().find(|entry| entry.key == old_user_key) {
+                entry.key = new_user_key;
+                leaderboard.try_serialize(&mut &mut leaderboard_info.try_borrow_mut_data()?[..])?;
+            }
+        }
+        
+        let mut user = (*ctx.accounts.old_user).clone();
+        user.authority = new_authority;
+        user.pending_authority = None;
+        user.updated_at = current_time;
+        ctx.accounts.new_user.set_inner(user);
+        
+        emit!(AuthorityRotated {
+            header: EventHeader::now()?,
+            old_user: old_user_key,
+            new_user: new_user_key,
+            old_authority,
+            new_authority,
+            timestamp: current_time,
+        });
+        
+        msg!("Authority rotated for {}", ctx.accounts.new_user.username);
+        Ok(())
+    }
+
+    /// Close a user that is not in a pack and has no session keys, returning rent and releasing
+    /// its username. Its trade and pack counters are kept for the next user of this authority.
+    pub fn close_user(ctx: Context<CloseUser>) -> Result<()> {
+        let user_key = ctx.accounts.user.key();
+        
+        let user_counters = &mut ctx.accounts.user_counters;
+        user_counters.authority = ctx.accounts.authority.key();
+        user_counters.total_trades = ctx.accounts.user.total_trades;
+        user_counters.packs_created = ctx.accounts.user.packs_created;
+        user_counters.bump = *ctx.bumps.get("user_counters").unwrap();
+        
+        let record_info = ctx.accounts.username_record.to_account_info();
+        if record_info.owner == &crate::ID {
+            let record = UsernameRecord::try_deserialize(&mut &record_info.try_borrow_data()?[..])?;
+            if record.user == user_key {
+                close_record(&record_info, &ctx.accounts.authority.to_account_info())?;
+            }
+        }
+        
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.total_users = global_state.total_users.checked_sub(1).unwrap();
+        
+        emit!(UserClosed {
+            header: EventHeader::now()?,
+            user: user_key,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("User closed: {}", ctx.accounts.user.username);
+        Ok(())
+    }
+
+    /// Let `delegate` call the instructions in `allowed_instructions` on the user's behalf
+    pub fn create_session_key(
+        ctx: Context<CreateSessionKey>,
+        delegate: Pubkey,
+        allowed_instructions: u8,
+        duration_seconds: i64,
+        spend_cap: u64,
+    ) -> Result<()> {
+        require_keys_neq!(delegate, ctx.accounts.authority.key(), AlphaPackError::InvalidAuthority);
+        require!(
+            allowed_instructions != 0 && allowed_instructions & !SessionKey::ALL == 0,
+            AlphaPackError::InvalidSessionPermissions
+        );
+        require!(
+            duration_seconds > 0 && duration_seconds <= SessionKey::MAX_DURATION,
+            AlphaPackError::InvalidExpiry
+        );
+        
+        let current_time = Clock::get()?.unix_timestamp;
+        let session_key = &mut ctx.accounts.session_key;
+        session_key.user = ctx.accounts.user.key();
+        session_key.delegate = delegate;
+        session_key.allowed_instructions = allowed_instructions;
+        session_key.expires_at = current_time + duration_seconds;
+        session_key.spend_cap = spend_cap;
+        session_key.spent = 0;
+        session_key.created_at = current_time;
+        session_key.bump = *ctx.bumps.get("session_key").unwrap();
+        
+        let user = &mut ctx.accounts.user;
+        user.session_keys = user.session_keys.checked_add(1).ok_or(AlphaPackError::TooManySessionKeys)?;
+        
+        emit!(SessionKeyCreated {
+            header: EventHeader::now()?,
+            user: session_key.user,
+            delegate,
+            allowed_instructions,
+            expires_at: session_key.expires_at,
+            spend_cap,
+            timestamp: current_time,
+        });
+        
+        msg!("Session key created for delegate {}", delegate);
+        Ok(())
+    }
+
+    /// Revoke a session key and return its rent to the user authority
+    pub fn revoke_session_key(ctx: Context<RevokeSessionKey>) -> Result<()> {
+        let user = &mut ctx.accounts.user;
+        user.session_keys = user.session_keys.saturating_sub(1);
+        
+        emit!(SessionKeyRevoked {
+            header: EventHeader::now()?,
+            user: ctx.accounts.user.key(),
+            delegate: ctx.accounts.session_key.delegate,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("Session key revoked for delegate {}", ctx.accounts.session_key.delegate);
+        Ok(())
+    }
+
     /// Create a new pack
     pub fn create_pack(
         ctx: Context<CreatePack>,
+        pack_index: u64,
         name: String,
         description: String,
         max_members: u8,
@@ -70,9 +360,10 @@ pub mod alpha_pack_core {
     ) -> Result<()> {
         require!(name.len() <= 100, AlphaPackError::PackNameTooLong);
         require!(description.len() <= 500, AlphaPackError::PackDescriptionTooLong);
-        require!(max_members >= 2 && max_members <= 20, AlphaPackError::InvalidMaxMembers);
+        require!((2..=20).contains(&max_members), AlphaPackError::InvalidMaxMembers);
         
         let pack = &mut ctx.accounts.pack;
+        pack.version = Pack::VERSION;
         pack.authority = ctx.accounts.authority.key();
         pack.name = name;
         pack.description = description;
@@ -85,14 +376,21 @@ pub mod alpha_pack_core {
         pack.win_rate = 0;
         pack.social_score = 0;
         pack.treasury_value = 0;
+        pack.total_trades = 0;
+        pack.winning_trades = 0;
+        pack.profit_loss = 0;
         pack.strategy_focus = strategy_focus;
         pack.risk_level = risk_level;
+        pack.recruitment_mode = RecruitmentMode::Open;
+        pack.min_social_score = 0;
+        pack.min_win_rate = 0;
         pack.created_at = Clock::get()?.unix_timestamp;
         pack.updated_at = Clock::get()?.unix_timestamp;
         
         // Update user's pack_id
         let user = &mut ctx.accounts.user;
         user.pack_id = Some(pack.key());
+        user.packs_created = user.packs_created.checked_add(1).unwrap();
         user.updated_at = Clock::get()?.unix_timestamp;
         
         // Update global state
@@ -100,6 +398,7 @@ pub mod alpha_pack_core {
         global_state.total_packs = global_state.total_packs.checked_add(1).unwrap();
         
         emit!(PackCreated {
+            header: EventHeader::now()?,
             pack: pack.key(),
             leader: pack.leader,
             name: pack.name.clone(),
@@ -107,7 +406,7 @@ pub mod alpha_pack_core {
             timestamp: pack.created_at,
         });
         
-        msg!("Pack created: {}", pack.name);
+        msg!("Pack {} created: {}", pack_index, pack.name);
         Ok(())
     }
 
@@ -116,32 +415,218 @@ pub mod alpha_pack_core {
         let pack = &mut ctx.accounts.pack;
         let user = &mut ctx.accounts.user;
         
+        require!(pack.recruitment_mode == RecruitmentMode::Open, AlphaPackError::RecruitmentModeMismatch);
+        check_recruitment_requirements(pack, user)?;
+        
+        add_member(pack, user)
+    }
+
+    /// Set how new members may join the pack (leader only)
+    pub fn set_recruitment_policy(
+        ctx: Context<SetRecruitmentPolicy>,
+        recruitment_mode: RecruitmentMode,
+        min_social_score: u64,
+        min_win_rate: u8,
+    ) -> Result<()> {
+        require!(min_win_rate <= 100, AlphaPackError::InvalidWinRate);
+        
+        let pack = &mut ctx.accounts.pack;
+        pack.recruitment_mode = recruitment_mode;
+        pack.min_social_score = min_social_score;
+        pack.min_win_rate = min_win_rate;
+        pack.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(RecruitmentPolicyUpdated {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            recruitment_mode: pack.recruitment_mode.clone(),
+            min_social_score,
+            min_win_rate,
+            timestamp: pack.updated_at,
+        });
+        
+        msg!("Recruitment policy updated for pack {}", pack.name);
+        Ok(())
+    }
+
+    /// Invite a user to the pack (leader only). Invites bypass the score requirements
+    pub fn invite_member(ctx: Context<InviteMember>, duration_seconds: i64) -> Result<()> {
+        require!(
+            duration_seconds > 0 && duration_seconds <= PackInvite::MAX_DURATION,
+            AlphaPackError::InvalidExpiry
+        );
+        require!(ctx.accounts.invitee.pack_id.is_none(), AlphaPackError::UserAlreadyInPack);
+        
+        let now = Clock::get()?.unix_timestamp;
+        let invite = &mut ctx.accounts.invite;
+        invite.pack = ctx.accounts.pack.key();
+        invite.invitee = ctx.accounts.invitee.authority;
+        invite.inviter = ctx.accounts.leader.key();
+        invite.expires_at = now.checked_add(duration_seconds).unwrap();
+        invite.created_at = now;
+        invite.bump = *ctx.bumps.get("invite").unwrap();
+        
+        emit!(MemberInvited {
+            header: EventHeader::now()?,
+            pack: invite.pack,
+            invitee: invite.invitee,
+            inviter: invite.inviter,
+            expires_at: invite.expires_at,
+            timestamp: now,
+        });
+        
+        msg!("User {} invited to pack {}", ctx.accounts.invitee.username, ctx.accounts.pack.name);
+        Ok(())
+    }
+
+    /// Join a pack through a pending invite, closing the invite
+    pub fn accept_invite(ctx: Context<AcceptInvite>) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < ctx.accounts.invite.expires_at,
+            AlphaPackError::InviteExpired
+        );
+        
+        add_member(&mut ctx.accounts.pack, &mut ctx.accounts.user)
+    }
+
+    /// Apply to join a pack that recruits by application
+    pub fn apply_to_pack(ctx: Context<ApplyToPack>, duration_seconds: i64) -> Result<()> {
+        let pack = &ctx.accounts.pack;
+        let user = &ctx.accounts.user;
+        
+        require!(
+            duration_seconds > 0 && duration_seconds <= PackApplication::MAX_DURATION,
+            AlphaPackError::InvalidExpiry
+        );
         require!(pack.status == PackStatus::Recruiting, AlphaPackError::PackNotRecruiting);
-        require!(pack.members.len() < pack.max_members as usize, AlphaPackError::PackFull);
-        require!(!pack.members.contains(&user.authority), AlphaPackError::AlreadyInPack);
+        require!(pack.recruitment_mode == RecruitmentMode::Application, AlphaPackError::RecruitmentModeMismatch);
         require!(user.pack_id.is_none(), AlphaPackError::UserAlreadyInPack);
+        check_recruitment_requirements(pack, user)?;
         
-        // Add user to pack
-        pack.members.push(user.authority);
-        pack.updated_at = Clock::get()?.unix_timestamp;
+        let now = Clock::get()?.unix_timestamp;
+        let application = &mut ctx.accounts.application;
+        application.pack = pack.key();
+        application.applicant = ctx.accounts.authority.key();
+        application.expires_at = now.checked_add(duration_seconds).unwrap();
+        application.created_at = now;
+        application.bump = *ctx.bumps.get("application").unwrap();
         
-        // Update user's pack_id
-        user.pack_id = Some(pack.key());
-        user.updated_at = Clock::get()?.unix_timestamp;
+        emit!(ApplicationSubmitted {
+            header: EventHeader::now()?,
+            pack: application.pack,
+            applicant: application.applicant,
+            expires_at: application.expires_at,
+            timestamp: now,
+        });
         
-        // If pack is full, change status to active
-        if pack.members.len() == pack.max_members as usize {
-            pack.status = PackStatus::Active;
-        }
+        msg!("User {} applied to pack {}", user.username, pack.name);
+        Ok(())
+    }
+
+    /// Accept an application and add the applicant to the pack (leader only)
+    pub fn approve_application(ctx: Context<ApproveApplication>) -> Result<()> {
+        require!(
+            Clock::get()?.unix_timestamp < ctx.accounts.application.expires_at,
+            AlphaPackError::ApplicationExpired
+        );
+        check_recruitment_requirements(&ctx.accounts.pack, &ctx.accounts.applicant_user)?;
         
-        emit!(UserJoinedPack {
-            pack: pack.key(),
-            user: user.key(),
-            member_count: pack.members.len() as u8,
-            timestamp: pack.updated_at,
+        emit!(ApplicationResolved {
+            header: EventHeader::now()?,
+            pack: ctx.accounts.pack.key(),
+            applicant: ctx.accounts.application.applicant,
+            approved: true,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        add_member(
+            &mut ctx.accounts.pack,
+            &mut ctx.accounts.applicant_user,
+        )
+    }
+
+    /// Decline an application, refunding its rent to the applicant (leader only)
+    pub fn reject_application(ctx: Context<RejectApplication>) -> Result<()> {
+        emit!(ApplicationResolved {
+            header: EventHeader::now()?,
+            pack: ctx.accounts.pack.key(),
+            applicant: ctx.accounts.application.applicant,
+            approved: false,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("Application to pack {} rejected", ctx.accounts.pack.name);
+        Ok(())
+    }
+
+    /// Withdraw an invite, refunding its rent to the inviter (inviter, pack leader or invitee)
+    pub fn cancel_invite(ctx: Context<CancelInvite>) -> Result<()> {
+        let invite = &ctx.accounts.invite;
+        let signer = ctx.accounts.authority.key();
+        require!(
+            signer == invite.inviter || signer == invite.invitee || signer == ctx.accounts.pack.leader,
+            AlphaPackError::NotInviteParty
+        );
+        
+        emit!(InviteClosed {
+            header: EventHeader::now()?,
+            pack: invite.pack,
+            invitee: invite.invitee,
+            closed_by: signer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("Invite to pack {} cancelled", ctx.accounts.pack.name);
+        Ok(())
+    }
+    
+    /// Close an expired invite, refunding its rent to the inviter (permissionless)
+    pub fn close_expired_invite(ctx: Context<CloseExpiredInvite>) -> Result<()> {
+        let invite = &ctx.accounts.invite;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= invite.expires_at, AlphaPackError::NotExpired);
+        
+        emit!(InviteClosed {
+            header: EventHeader::now()?,
+            pack: invite.pack,
+            invitee: invite.invitee,
+            closed_by: ctx.accounts.closer.key(),
+            timestamp: now,
+        });
+        
+        msg!("Expired invite closed");
+        Ok(())
+    }
+    
+    /// Withdraw a pending application, refunding its rent to the applicant
+    pub fn withdraw_application(ctx: Context<WithdrawApplication>) -> Result<()> {
+        emit!(ApplicationClosed {
+            header: EventHeader::now()?,
+            pack: ctx.accounts.application.pack,
+            applicant: ctx.accounts.application.applicant,
+            closed_by: ctx.accounts.applicant.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("Application withdrawn");
+        Ok(())
+    }
+    
+    /// Close an expired application, refunding its rent to the applicant (permissionless)
+    pub fn close_expired_application(ctx: Context<CloseExpiredApplication>) -> Result<()> {
+        let application = &ctx.accounts.application;
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= application.expires_at, AlphaPackError::NotExpired);
+        
+        emit!(ApplicationClosed {
+            header: EventHeader::now()?,
+            pack: application.pack,
+            applicant: application.applicant,
+            closed_by: ctx.accounts.closer.key(),
+            timestamp: now,
         });
         
-        msg!("User {} joined pack {}", user.username, pack.name);
+        msg!("Expired application closed");
         Ok(())
     }
 
@@ -161,14 +646,10 @@ pub mod alpha_pack_core {
         user.pack_id = None;
         user.updated_at = Clock::get()?.unix_timestamp;
         
-        // If pack becomes empty, disband it
-        if pack.members.is_empty() {
-            pack.status = PackStatus::Disbanded;
-        } else if pack.status == PackStatus::Active && pack.members.len() < 2 {
-            pack.status = PackStatus::Recruiting;
-        }
+        update_pack_status_after_departure(pack);
         
         emit!(UserLeftPack {
+            header: EventHeader::now()?,
             pack: pack.key(),
             user: user.key(),
             member_count: pack.members.len() as u8,
@@ -179,158 +660,2359 @@ pub mod alpha_pack_core {
         Ok(())
     }
 
-    /// Record a trade execution
-    pub fn record_trade(
-        ctx: Context<RecordTrade>,
-        trade_type: TradeType,
-        from_token: String,
-        to_token: String,
-        from_amount: u64,
-        to_amount: u64,
-        fee: u64,
-        signature: String,
-    ) -> Result<()> {
-        let trade = &mut ctx.accounts.trade;
-        let user = &mut ctx.accounts.user;
+    /// Remove a member from the pack (leader only)
+    pub fn remove_member(ctx: Context<RemoveMember>) -> Result<()> {
+        let pack = &mut ctx.accounts.pack;
+        let member = &mut ctx.accounts.member;
         
-        trade.authority = ctx.accounts.authority.key();
-        trade.user = user.key();
-        trade.pack = user.pack_id;
-        trade.trade_type = trade_type;
-        trade.from_token = from_token;
-        trade.to_token = to_token;
-        trade.from_amount = from_amount;
-        trade.to_amount = to_amount;
-        trade.fee = fee;
-        trade.signature = signature;
-        trade.timestamp = Clock::get()?.unix_timestamp;
-        
-        // Calculate profit/loss
-        let profit_loss = (to_amount as i64) - (from_amount as i64) - (fee as i64);
-        trade.profit_loss = profit_loss;
-        
-        // Update user stats
-        user.total_trades = user.total_trades.checked_add(1).unwrap();
-        user.total_volume = user.total_volume.checked_add(from_amount).unwrap();
-        user.profit_loss = user.profit_loss.checked_add(profit_loss).unwrap();
-        
-        // Update win rate (simplified calculation)
-        if profit_loss > 0 {
-            user.win_rate = ((user.win_rate as u64 * (user.total_trades - 1) + 100) / user.total_trades as u64) as u8;
-        } else {
-            user.win_rate = ((user.win_rate as u64 * (user.total_trades - 1)) / user.total_trades as u64) as u8;
-        }
+        remove_pack_member(pack, member)?;
         
-        user.updated_at = Clock::get()?.unix_timestamp;
+        emit!(MemberRemoved {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            user: member.key(),
+            removed_by: ctx.accounts.leader.key(),
+            member_count: pack.members.len() as u8,
+            timestamp: pack.updated_at,
+        });
         
-        // Update pack stats if user is in a pack
-        if let Some(pack_key) = user.pack_id {
-            if let Some(pack_account) = ctx.remaining_accounts.get(0) {
-                let mut pack_data = pack_account.try_borrow_mut_data()?;
-                let mut pack: Pack = Pack::try_deserialize(&mut pack_data.as_ref())?;
-                
-                pack.total_volume = pack.total_volume.checked_add(from_amount).unwrap();
-                pack.score = pack.score.checked_add(if profit_loss > 0 { profit_loss as u64 } else { 0 }).unwrap();
-                pack.updated_at = Clock::get()?.unix_timestamp;
-                
-                pack.try_serialize(&mut pack_data.as_mut())?;
-            }
-        }
+        msg!("User {} removed from pack {}", member.username, pack.name);
+        Ok(())
+    }
+
+    /// Hand pack leadership to another member (leader only)
+    pub fn transfer_leadership(ctx: Context<TransferLeadership>) -> Result<()> {
+        let pack = &mut ctx.accounts.pack;
+        let new_leader = &ctx.accounts.new_leader;
         
-        // Update global state
-        let global_state = &mut ctx.accounts.global_state;
-        global_state.total_volume = global_state.total_volume.checked_add(from_amount).unwrap();
+        let previous_leader = hand_over_leadership(pack, new_leader)?;
         
-        emit!(TradeRecorded {
-            trade: trade.key(),
-            user: user.key(),
-            pack: user.pack_id,
-            from_amount,
-            to_amount,
-            profit_loss,
-            timestamp: trade.timestamp,
+        emit!(LeadershipTransferred {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            previous_leader,
+            new_leader: pack.leader,
+            timestamp: pack.updated_at,
         });
         
-        msg!("Trade recorded for user {}", user.username);
+        msg!("Pack {} leadership transferred to {}", pack.name, new_leader.username);
         Ok(())
     }
 
-    /// Update social score
-    pub fn update_social_score(
-        ctx: Context<UpdateSocialScore>,
-        score_increase: u64,
-    ) -> Result<()> {
-        let user = &mut ctx.accounts.user;
+    /// Disband a pack once the leader is its only member, closing the account (leader only)
+    pub fn disband_pack(ctx: Context<DisbandPack>) -> Result<()> {
+        let pack = &mut ctx.accounts.pack;
         
-        user.social_score = user.social_score.checked_add(score_increase).unwrap();
-        user.updated_at = Clock::get()?.unix_timestamp;
+        require!(
+            pack.members.len() == 1 && pack.members[0] == pack.leader,
+            AlphaPackError::PackNotEmpty
+        );
         
-        // Update pack social score if user is in a pack
-        if let Some(pack_key) = user.pack_id {
-            if let Some(pack_account) = ctx.remaining_accounts.get(0) {
-                let mut pack_data = pack_account.try_borrow_mut_data()?;
-                let mut pack: Pack = Pack::try_deserialize(&mut pack_data.as_ref())?;
-                
-                pack.social_score = pack.social_score.checked_add(score_increase / 2).unwrap();
-                pack.updated_at = Clock::get()?.unix_timestamp;
-                
-                pack.try_serialize(&mut pack_data.as_mut())?;
-            }
-        }
+        pack.members.clear();
+        pack.status = PackStatus::Disbanded;
         
-        emit!(SocialScoreUpdated {
-            user: user.key(),
-            pack: user.pack_id,
-            score_increase,
-            new_score: user.social_score,
-            timestamp: user.updated_at,
+        let leader_user = &mut ctx.accounts.leader_user;
+        leader_user.pack_id = None;
+        leader_user.updated_at = Clock::get()?.unix_timestamp;
+        
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.total_packs = global_state.total_packs.checked_sub(1).unwrap();
+        
+        emit!(PackDisbanded {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            leader: pack.leader,
+            timestamp: leader_user.updated_at,
         });
         
-        msg!("Social score updated for user {}", user.username);
+        msg!("Pack disbanded: {}", pack.name);
         Ok(())
     }
 
-    /// Pause/unpause the program (admin only)
-    pub fn set_pause_state(ctx: Context<SetPauseState>, paused: bool) -> Result<()> {
-        let global_state = &mut ctx.accounts.global_state;
-        global_state.paused = paused;
-        
-        msg!("Program pause state set to: {}", paused);
-        Ok(())
+    /// Record a trade execution
+    pub fn record_trade(
+        ctx: Context<RecordTrade>,
+        trade_index: u64,
+        epoch: u64,
+        trade_type: TradeType,
+        from_amount: u64,
+        to_amount: u64,
+        fee: u64,
+        signature: String,
+    ) -> Result<()> {
+        require!(
+            !ctx.accounts.global_state.verified_trades_only,
+            AlphaPackError::VerifiedTradeRequired
+        );
+
+        let trade = build_trade(
+            &ctx.accounts.user,
+            ctx.accounts.user.authority,
+            trade_type,
+            &ctx.accounts.from_mint,
+            &ctx.accounts.to_mint,
+            &ctx.accounts.from_price,
+            &ctx.accounts.to_price,
+            from_amount,
+            to_amount,
+            fee,
+            signature,
+            TradeVerification::Unverified,
+        )?;
+
+        authorize_signer(
+            &ctx.accounts.user,
+            ctx.accounts.authority.key(),
+            ctx.accounts.session_key.as_mut(),
+            SessionKey::RECORD_TRADE,
+            trade.from_value,
+        )?;
+
+        let trade_key = store_trade(
+            &trade,
+            trade_index,
+            ctx.accounts.trade.as_mut(),
+            ctx.accounts.trade_log.as_ref(),
+        )?;
+
+        apply_trade(
+            &trade,
+            trade_key,
+            &mut ctx.accounts.user,
+            ctx.accounts.pack.as_mut(),
+            &mut ctx.accounts.global_state,
+            epoch,
+            &mut ctx.accounts.user_epoch_stats,
+            ctx.accounts.pack_epoch_stats.as_deref_mut(),
+        )
     }
-}
 
-// Account structures
-#[derive(Accounts)]
-#[instruction(bump: u8)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + GlobalState::INIT_SPACE,
-        seeds = [b"global_state"],
-        bump
-    )]
-    pub global_state: Account<'info, GlobalState>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+    /// Record a trade co-signed by the registered trade attestor
+    pub fn record_attested_trade(
+        ctx: Context<RecordAttestedTrade>,
+        trade_index: u64,
+        epoch: u64,
+        trade_type: TradeType,
+        from_amount: u64,
+        to_amount: u64,
+        fee: u64,
+        signature: String,
+    ) -> Result<()> {
+        let trade = build_trade(
+            &ctx.accounts.user,
+            ctx.accounts.user.authority,
+            trade_type,
+            &ctx.accounts.from_mint,
+            &ctx.accounts.to_mint,
+            &ctx.accounts.from_price,
+            &ctx.accounts.to_price,
+            from_amount,
+            to_amount,
+            fee,
+            signature,
+            TradeVerification::Attested,
+        )?;
+
+        authorize_signer(
+            &ctx.accounts.user,
+            ctx.accounts.authority.key(),
+            ctx.accounts.session_key.as_mut(),
+            SessionKey::RECORD_TRADE,
+            trade.from_value,
+        )?;
+
+        let trade_key = store_trade(
+            &trade,
+            trade_index,
+            ctx.accounts.trade.as_mut(),
+            ctx.accounts.trade_log.as_ref(),
+        )?;
+
+        apply_trade(
+            &trade,
+            trade_key,
+            &mut ctx.accounts.user,
+            ctx.accounts.pack.as_mut(),
+            &mut ctx.accounts.global_state,
+            epoch,
+            &mut ctx.accounts.user_epoch_stats,
+            ctx.accounts.pack_epoch_stats.as_deref_mut(),
+        )
+    }
+
+    /// Snapshot token balances before a swap settled later in the same transaction
+    pub fn begin_trade_settlement(ctx: Context<BeginTradeSettlement>) -> Result<()> {
+        require!(
+            ctx.accounts.from_token_account.key() != ctx.accounts.to_token_account.key(),
+            AlphaPackError::InvalidSettlementAccounts
+        );
+
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let current_index = load_current_index_checked(&instructions)?;
+        verify_settlement_window(
+            &instructions,
+            current_index,
+            &ctx.accounts.global_state.settlement_programs,
+        )?;
+
+        let settlement = &mut ctx.accounts.settlement;
+        settlement.authority = ctx.accounts.authority.key();
+        settlement.from_token_account = ctx.accounts.from_token_account.key();
+        settlement.to_token_account = ctx.accounts.to_token_account.key();
+        settlement.from_balance_before = ctx.accounts.from_token_account.amount;
+        settlement.to_balance_before = ctx.accounts.to_token_account.amount;
+        settlement.instruction_index = current_index;
+        settlement.slot = Clock::get()?.slot;
+        settlement.bump = *ctx.bumps.get("settlement").unwrap();
+
+        msg!("Trade settlement started at instruction {}", current_index);
+        Ok(())
+    }
+
+    /// Record a trade from the token balance deltas since begin_trade_settlement
+    pub fn record_settled_trade(
+        ctx: Context<RecordSettledTrade>,
+        trade_index: u64,
+        epoch: u64,
+        trade_type: TradeType,
+        signature: String,
+    ) -> Result<()> {
+        let settlement = &ctx.accounts.settlement;
+        require!(
+            settlement.slot == Clock::get()?.slot,
+            AlphaPackError::SettlementExpired
+        );
+
+        let from_after = ctx.accounts.from_token_account.amount;
+        let to_after = ctx.accounts.to_token_account.amount;
+        let from_amount = settlement.from_balance_before
+            .checked_sub(from_after)
+            .ok_or(AlphaPackError::InvalidSettlementDelta)?;
+        let to_amount = to_after
+            .checked_sub(settlement.to_balance_before)
+            .ok_or(AlphaPackError::InvalidSettlementDelta)?;
+        require!(from_amount > 0 && to_amount > 0, AlphaPackError::InvalidSettlementDelta);
+
+        // Balance deltas are already net of venue fees
+        let trade = build_trade(
+            &ctx.accounts.user,
+            ctx.accounts.authority.key(),
+            trade_type,
+            &ctx.accounts.from_mint,
+            &ctx.accounts.to_mint,
+            &ctx.accounts.from_price,
+            &ctx.accounts.to_price,
+            from_amount,
+            to_amount,
+            0,
+            signature,
+            TradeVerification::Settled,
+        )?;
+
+        let trade_key = store_trade(
+            &trade,
+            trade_index,
+            ctx.accounts.trade.as_mut(),
+            ctx.accounts.trade_log.as_ref(),
+        )?;
+
+        apply_trade(
+            &trade,
+            trade_key,
+            &mut ctx.accounts.user,
+            ctx.accounts.pack.as_mut(),
+            &mut ctx.accounts.global_state,
+            epoch,
+            &mut ctx.accounts.user_epoch_stats,
+            ctx.accounts.pack_epoch_stats.as_deref_mut(),
+        )
+    }
+
+    /// Create the caller's TradeLog, a fixed-size ring buffer trades can be recorded into
+    pub fn create_trade_log(ctx: Context<CreateTradeLog>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let mut trade_log = ctx.accounts.trade_log.load_init()?;
+        trade_log.user = ctx.accounts.user.key();
+        trade_log.created_at = now;
+        trade_log.bump = *ctx.bumps.get("trade_log").unwrap();
+
+        emit!(TradeLogCreated {
+            header: EventHeader::now()?,
+            trade_log: ctx.accounts.trade_log.key(),
+            user: ctx.accounts.user.key(),
+            timestamp: now,
+        });
+
+        msg!("Trade log created for user {}", ctx.accounts.user.username);
+        Ok(())
+    }
+
+    /// Close a Trade account and return its rent to the user authority
+    pub fn close_trade(ctx: Context<CloseTrade>, trade_index: u64) -> Result<()> {
+        emit!(TradeClosed {
+            header: EventHeader::now()?,
+            trade: ctx.accounts.trade.key(),
+            trade_index,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Trade {} closed", trade_index);
+        Ok(())
+    }
+
+    /// Configure verified trade recording (admin only)
+    pub fn configure_trade_verification(
+        ctx: Context<ConfigureTradeVerification>,
+        verified_trades_only: bool,
+        settlement_programs: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            settlement_programs.len() <= GlobalState::MAX_SETTLEMENT_PROGRAMS,
+            AlphaPackError::TooManySettlementPrograms
+        );
+
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.verified_trades_only = verified_trades_only;
+        global_state.admin.check_approval(&global_state.authority, ctx.remaining_accounts)?;
+        global_state.settlement_programs = settlement_programs;
+
+        emit!(TradeVerificationConfigured {
+            header: EventHeader::now()?,
+            verified_trades_only,
+            settlement_programs: global_state.settlement_programs.len() as u8,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Trade verification configured, verified only: {}", verified_trades_only);
+        Ok(())
+    }
+
+    /// Assign or clear a protocol role (admin only)
+    pub fn set_role(ctx: Context<SetRole>, role: Role, account: Option<Pubkey>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.admin.check_approval(&global_state.authority, ctx.remaining_accounts)?;
+        match role {
+            Role::ScoreOracle => global_state.score_oracle = account,
+            Role::TradeAttestor => global_state.trade_attestor = account,
+            Role::PriceOracle => global_state.price_oracle = account,
+            Role::Pauser => global_state.pauser = account,
+        }
+        
+        emit!(RoleUpdated {
+            header: EventHeader::now()?,
+            role: role.clone(),
+            account,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("Role {:?} set to {:?}", role, account);
+        Ok(())
+    }
+
+    /// Create the quote price feed for a mint (price oracle only)
+    pub fn create_price_feed(ctx: Context<CreatePriceFeed>, price: u64) -> Result<()> {
+        require!(price > 0, AlphaPackError::InvalidPrice);
+        
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.mint = ctx.accounts.mint.key();
+        price_feed.price = price;
+        price_feed.updated_at = Clock::get()?.unix_timestamp;
+        price_feed.bump = *ctx.bumps.get("price_feed").unwrap();
+        
+        emit!(PriceUpdated {
+            header: EventHeader::now()?,
+            mint: price_feed.mint,
+            price,
+            timestamp: price_feed.updated_at,
+        });
+        
+        msg!("Price feed created for mint {}", price_feed.mint);
+        Ok(())
+    }
+
+    /// Publish a new quote price for a mint (price oracle only)
+    pub fn update_price_feed(ctx: Context<UpdatePriceFeed>, price: u64) -> Result<()> {
+        require!(price > 0, AlphaPackError::InvalidPrice);
+        
+        let price_feed = &mut ctx.accounts.price_feed;
+        price_feed.price = price;
+        price_feed.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(PriceUpdated {
+            header: EventHeader::now()?,
+            mint: price_feed.mint,
+            price,
+            timestamp: price_feed.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Apply a social score reward or penalty (score oracle or social_rewards CPI only)
+    pub fn update_social_score(
+        ctx: Context<UpdateSocialScore>,
+        epoch: u64,
+        score_delta: i64,
+    ) -> Result<()> {
+        let scorer = ctx.accounts.scorer.key();
+        let (rewards_signer, _) = Pubkey::find_program_address(&[b"rewards_system"], &SOCIAL_REWARDS_PROGRAM_ID);
+        require!(
+            ctx.accounts.global_state.score_oracle == Some(scorer) || scorer == rewards_signer,
+            AlphaPackError::UnauthorizedScorer
+        );
+        
+        let now = Clock::get()?.unix_timestamp;
+        let global_state = &ctx.accounts.global_state;
+        require!(epoch == global_state.current_epoch(now), AlphaPackError::InvalidEpoch);
+        let started_at = global_state.epoch_start(epoch);
+        
+        let user = &mut ctx.accounts.user;
+        
+        let (social_score, applied_delta) = apply_score_delta(user.social_score, score_delta)?;
+        user.social_score = social_score;
+        user.updated_at = now;
+        
+        // Epoch stats record what was applied, so a penalty clamped at zero counts only in part
+        let user_stats = &mut ctx.accounts.user_epoch_stats;
+        user_stats.open(user.key(), epoch, started_at);
+        user_stats.social_score_delta = user_stats
+            .social_score_delta
+            .checked_add(applied_delta)
+            .ok_or(AlphaPackError::ValueOverflow)?;
+        user_stats.updated_at = now;
+        
+        // Update pack social score if user is in a pack
+        if let Some(pack_id) = user.pack_id {
+            let pack = ctx.accounts.pack.as_mut().ok_or(AlphaPackError::PackAccountRequired)?;
+            let (social_score, applied_delta) = apply_score_delta(pack.social_score, score_delta / 2)?;
+            pack.social_score = social_score;
+            pack.updated_at = now;
+            
+            let pack_stats = ctx.accounts.pack_epoch_stats.as_mut().ok_or(AlphaPackError::PackAccountRequired)?;
+            pack_stats.open(pack_id, epoch, started_at);
+            pack_stats.social_score_delta = pack_stats
+                .social_score_delta
+                .checked_add(applied_delta)
+                .ok_or(AlphaPackError::ValueOverflow)?;
+            pack_stats.updated_at = now;
+        }
+        
+        emit!(SocialScoreUpdated {
+            header: EventHeader::now()?,
+            user: user.key(),
+            pack: user.pack_id,
+            score_delta,
+            new_score: user.social_score,
+            timestamp: user.updated_at,
+        });
+        
+        msg!("Social score updated for user {}", user.username);
+        Ok(())
+    }
+
+    /// Upgrade a v1 GlobalState account to the current layout (permissionless)
+    pub fn migrate_global_state(ctx: Context<MigrateGlobalState>) -> Result<()> {
+        let info = ctx.accounts.global_state.to_account_info();
+        let legacy: GlobalStateV1 = read_legacy::<GlobalState, _>(&info, GlobalStateV1::INIT_SPACE)?;
+        let global_state = GlobalState::from_v1(legacy, Clock::get()?.unix_timestamp);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + GlobalState::INIT_SPACE,
+            &global_state,
+        )?;
+        
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: GlobalState::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("Global state migrated to v{}", GlobalState::VERSION);
+        Ok(())
+    }
+
+    /// Upgrade a v1 User account to the current layout (permissionless)
+    pub fn migrate_user(ctx: Context<MigrateUser>) -> Result<()> {
+        let info = ctx.accounts.user.to_account_info();
+        let legacy: UserV1 = read_legacy::<User, _>(&info, UserV1::INIT_SPACE)?;
+        let now = Clock::get()?.unix_timestamp;
+        let user = User::from_v1(legacy, now);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + User::INIT_SPACE,
+            &user,
+        )?;
+        
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: User::VERSION,
+            timestamp: now,
+        });
+        
+        msg!("User {} migrated to v{}", user.username, User::VERSION);
+        Ok(())
+    }
+
+    /// Upgrade a v1 Pack account to the current layout (permissionless)
+    pub fn migrate_pack(ctx: Context<MigratePack>) -> Result<()> {
+        let info = ctx.accounts.pack.to_account_info();
+        let legacy: PackV1 = read_legacy::<Pack, _>(&info, PackV1::INIT_SPACE)?;
+        let now = Clock::get()?.unix_timestamp;
+        let pack = Pack::from_v1(legacy, now);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + Pack::INIT_SPACE,
+            &pack,
+        )?;
+        
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: Pack::VERSION,
+            timestamp: now,
+        });
+        
+        msg!("Pack {} migrated to v{}", pack.name, Pack::VERSION);
+        Ok(())
+    }
+
+    /// Mirror the pack treasury value reported by pack_manager (treasury PDA signer only)
+    pub fn sync_treasury_value(ctx: Context<SyncTreasuryValue>, treasury_value: u64) -> Result<()> {
+        let pack = &mut ctx.accounts.pack;
+        let (expected_treasury, _) = Pubkey::find_program_address(
+            &[b"treasury", pack.key().as_ref()],
+            &PACK_MANAGER_PROGRAM_ID,
+        );
+        require_keys_eq!(ctx.accounts.treasury.key(), expected_treasury, AlphaPackError::InvalidTreasury);
+        
+        pack.treasury_value = treasury_value;
+        pack.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(TreasuryValueSynced {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            treasury_value,
+            timestamp: pack.updated_at,
+        });
+        
+        msg!("Treasury value synced for pack {}", pack.name);
+        Ok(())
+    }
+
+    /// Change a pack's strategy and risk level (pack_manager governance PDA signer only)
+    pub fn set_pack_strategy(
+        ctx: Context<SetPackStrategy>,
+        strategy_focus: StrategyFocus,
+        risk_level: RiskLevel,
+    ) -> Result<()> {
+        let pack = &mut ctx.accounts.pack;
+        check_governance_signer(&pack.key(), &ctx.accounts.governance.key())?;
+        
+        pack.strategy_focus = strategy_focus.clone();
+        pack.risk_level = risk_level.clone();
+        pack.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(PackStrategyUpdated {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            strategy_focus,
+            risk_level,
+            timestamp: pack.updated_at,
+        });
+        
+        msg!("Pack {} strategy updated", pack.name);
+        Ok(())
+    }
+
+    /// Remove a member by pack vote (pack_manager governance PDA signer only)
+    pub fn governance_remove_member(ctx: Context<GovernanceRemoveMember>) -> Result<()> {
+        let pack = &mut ctx.accounts.pack;
+        let member = &mut ctx.accounts.member;
+        check_governance_signer(&pack.key(), &ctx.accounts.governance.key())?;
+        
+        remove_pack_member(pack, member)?;
+        
+        emit!(MemberRemoved {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            user: member.key(),
+            removed_by: ctx.accounts.governance.key(),
+            member_count: pack.members.len() as u8,
+            timestamp: pack.updated_at,
+        });
+        
+        msg!("User {} removed from pack {} by vote", member.username, pack.name);
+        Ok(())
+    }
+
+    /// Hand pack leadership to another member by pack vote (pack_manager governance PDA signer only)
+    pub fn governance_transfer_leadership(ctx: Context<GovernanceTransferLeadership>) -> Result<()> {
+        let pack = &mut ctx.accounts.pack;
+        let new_leader = &ctx.accounts.new_leader;
+        check_governance_signer(&pack.key(), &ctx.accounts.governance.key())?;
+        
+        let previous_leader = hand_over_leadership(pack, new_leader)?;
+        
+        emit!(LeadershipTransferred {
+            header: EventHeader::now()?,
+            pack: pack.key(),
+            previous_leader,
+            new_leader: pack.leader,
+            timestamp: pack.updated_at,
+        });
+        
+        msg!("Pack {} leadership transferred to {} by vote", pack.name, new_leader.username);
+        Ok(())
+    }
+
+    /// Pause/unpause the program (admin only)
+    pub fn set_pause_state(ctx: Context<SetPauseState>, paused: bool) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let signer = ctx.accounts.authority.key();
+        
+        // The pauser can trip the circuit breaker; only the admin can reset it
+        if !(paused && global_state.pauser == Some(signer)) {
+            require!(signer == global_state.authority, AlphaPackError::UnauthorizedRole);
+            global_state.admin.check_approval(&signer, ctx.remaining_accounts)?;
+        }
+        global_state.paused = paused;
+
+        emit!(PauseStateChanged {
+            header: EventHeader::now()?,
+            paused,
+            authority: signer,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        msg!("Program pause state set to: {}", paused);
+        Ok(())
+    }
+
+    /// Propose a new admin authority; `None` cancels a pending proposal
+    pub fn propose_admin_authority(ctx: Context<ProposeAdminAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let (account, authority) = (global_state.key(), global_state.authority);
+        let now = Clock::get()?.unix_timestamp;
+        global_state.admin.propose(account, authority, ctx.remaining_accounts, new_authority, now)?;
+
+        msg!("Admin authority transfer proposed");
+        Ok(())
+    }
+
+    /// Accept a proposed admin authority transfer (signed by the new authority)
+    pub fn accept_admin_authority(ctx: Context<AcceptAdminAuthority>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let (account, authority) = (global_state.key(), global_state.authority);
+        let now = Clock::get()?.unix_timestamp;
+        global_state.authority = global_state.admin.transfer(account, authority, ctx.accounts.new_authority.key(), now)?;
+
+        msg!("Admin authority transferred to {}", global_state.authority);
+        Ok(())
+    }
+
+    /// Set the M-of-N approvers required for sensitive admin actions; an empty set disables them
+    pub fn configure_admin_approvals(
+        ctx: Context<ConfigureAdminApprovals>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let (account, authority) = (global_state.key(), global_state.authority);
+        let now = Clock::get()?.unix_timestamp;
+        global_state.admin.configure(account, authority, ctx.remaining_accounts, approvers, threshold, now)?;
+
+        msg!("Admin approvals set to {}-of-{}", threshold, global_state.admin.approvers.len());
+        Ok(())
+    }
+
+    /// Change the stats epoch length (admin only); the running epoch ends now and the next one
+    /// starts with the new length
+    pub fn set_epoch_length(ctx: Context<SetEpochLength>, epoch_length: i64) -> Result<()> {
+        require!(epoch_length >= GlobalState::MIN_EPOCH_LENGTH, AlphaPackError::InvalidEpochLength);
+        
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.admin.check_approval(&global_state.authority, ctx.remaining_accounts)?;
+        let now = Clock::get()?.unix_timestamp;
+        global_state.epoch_base_index = global_state.current_epoch(now) + 1;
+        global_state.epoch_base_time = now;
+        global_state.epoch_length = epoch_length;
+        
+        emit!(EpochLengthUpdated {
+            header: EventHeader::now()?,
+            epoch_length,
+            next_epoch: global_state.epoch_base_index,
+            starts_at: now,
+        });
+        
+        msg!("Epoch length set to {}s from epoch {}", epoch_length, global_state.epoch_base_index);
+        Ok(())
+    }
+
+    /// Create a leaderboard for `scope` ranked by `metric` (admin, or the pack leader for a pack scope)
+    pub fn create_leaderboard(
+        ctx: Context<CreateLeaderboard>,
+        scope: LeaderboardScope,
+        metric: RankingMetric,
+    ) -> Result<()> {
+        authorize_leaderboard(
+            &scope,
+            ctx.accounts.authority.key(),
+            &ctx.accounts.global_state,
+            ctx.accounts.pack.as_ref(),
+        )?;
+        
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        leaderboard.scope = scope;
+        leaderboard.metric = metric;
+        leaderboard.entries = Vec::new();
+        leaderboard.updated_at = Clock::get()?.unix_timestamp;
+        leaderboard.bump = *ctx.bumps.get("leaderboard").unwrap();
+        
+        emit!(LeaderboardConfigured {
+            header: EventHeader::now()?,
+            leaderboard: leaderboard.key(),
+            scope,
+            metric,
+            timestamp: leaderboard.updated_at,
+        });
+        
+        msg!("Leaderboard created");
+        Ok(())
+    }
+
+    /// Switch a leaderboard to a new metric; existing entries are cleared and must be re-cranked
+    pub fn set_leaderboard_metric(ctx: Context<SetLeaderboardMetric>, metric: RankingMetric) -> Result<()> {
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        authorize_leaderboard(
+            &leaderboard.scope,
+            ctx.accounts.authority.key(),
+            &ctx.accounts.global_state,
+            ctx.accounts.pack.as_ref(),
+        )?;
+        
+        leaderboard.metric = metric;
+        leaderboard.entries.clear();
+        leaderboard.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(LeaderboardConfigured {
+            header: EventHeader::now()?,
+            leaderboard: leaderboard.key(),
+            scope: leaderboard.scope,
+            metric,
+            timestamp: leaderboard.updated_at,
+        });
+        
+        msg!("Leaderboard metric updated");
+        Ok(())
+    }
+
+    /// Re-score a batch of users (or packs, for the pack ranking) passed as remaining accounts
+    /// (permissionless). On the global leaderboard each cranked user's `rank` is refreshed too.
+    pub fn crank_leaderboard<'info>(ctx: Context<'_, '_, '_, 'info, CrankLeaderboard<'info>>) -> Result<()> {
+        require!(
+            !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() <= Leaderboard::MAX_BATCH,
+            AlphaPackError::InvalidRankingBatch
+        );
+        
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        let metric = leaderboard.metric;
+        let scope = leaderboard.scope;
+        
+        if scope == LeaderboardScope::Packs {
+            for info in ctx.remaining_accounts.iter() {
+                let pack = Account::<Pack>::try_from(info)?;
+                leaderboard.upsert(pack.key(), metric.pack_score(&pack));
+            }
+        } else {
+            let mut users = Vec::with_capacity(ctx.remaining_accounts.len());
+            for info in ctx.remaining_accounts.iter() {
+                let user = Account::<User>::try_from(info)?;
+                if let LeaderboardScope::Pack(pack) = scope {
+                    require!(user.pack_id == Some(pack), AlphaPackError::UserNotInPack);
+                }
+                leaderboard.upsert(user.key(), metric.user_score(&user));
+                users.push(user);
+            }
+            
+            // Ranks are written after the whole batch is placed so they agree with each other.
+            // Users displaced by this batch keep a stale rank until they are cranked themselves.
+            if scope == LeaderboardScope::Global {
+                for user in users.iter_mut() {
+                    user.rank = leaderboard.rank_of(&user.key());
+                    user.exit(&crate::ID)?;
+                }
+            }
+        }
+        leaderboard.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(LeaderboardCranked {
+            header: EventHeader::now()?,
+            leaderboard: leaderboard.key(),
+            processed: ctx.remaining_accounts.len() as u8,
+            ranked: leaderboard.entries.len() as u16,
+            timestamp: leaderboard.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Drop leaderboard entries that no longer qualify (permissionless): closed accounts,
+    /// disbanded packs, and users who left the pack a pack leaderboard ranks
+    pub fn evict_leaderboard_entries<'info>(
+        ctx: Context<'_, '_, '_, 'info, EvictLeaderboardEntries<'info>>,
+    ) -> Result<()> {
+        require!(
+            !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len() <= Leaderboard::MAX_BATCH,
+            AlphaPackError::InvalidRankingBatch
+        );
+        
+        let leaderboard = &mut ctx.accounts.leaderboard;
+        let scope = leaderboard.scope;
+        for info in ctx.remaining_accounts.iter() {
+            require!(leaderboard.rank_of(info.key) != 0, AlphaPackError::NotOnLeaderboard);
+            require!(is_evictable(scope, info)?, AlphaPackError::EntryStillQualifies);
+            leaderboard.remove(info.key);
+        }
+        leaderboard.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(LeaderboardEntriesEvicted {
+            header: EventHeader::now()?,
+            leaderboard: leaderboard.key(),
+            evicted: ctx.remaining_accounts.len() as u8,
+            ranked: leaderboard.entries.len() as u16,
+            timestamp: leaderboard.updated_at,
+        });
+        
+        Ok(())
+    }
+}
+
+// Helper function to decide whether a leaderboard entry's account has stopped qualifying
+fn is_evictable(scope: LeaderboardScope, info: &AccountInfo) -> Result<bool> {
+    // Closed accounts are handed back to the system program
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(true);
+    }
+    Ok(match scope {
+        LeaderboardScope::Packs => Account::<Pack>::try_from(info)?.status == PackStatus::Disbanded,
+        LeaderboardScope::Pack(pack) => Account::<User>::try_from(info)?.pack_id != Some(pack),
+        LeaderboardScope::Global => false,
+    })
+}
+
+// Helper function to normalise a username for the registry: ASCII letters are lowercased,
+// and only letters, digits and underscores are accepted
+pub fn normalize_username(username: &str) -> Result<String> {
+    require!(
+        username.len() >= UsernameRecord::MIN_LENGTH
+            && username.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_'),
+        AlphaPackError::InvalidUsername
+    );
+    Ok(username.to_ascii_lowercase())
+}
+
+// Helper function to derive the registry seed for a username. Handles can exceed the 32-byte
+// seed limit, so the normalised name is hashed.
+pub fn username_seed(username: &str) -> [u8; 32] {
+    anchor_lang::solana_program::hash::hash(username.to_ascii_lowercase().as_bytes()).to_bytes()
+}
+
+// Helper function to check that signer is the user's authority, or a live session delegate
+// allowed to call `permission`. `spend` is charged against the session's spend cap.
+pub fn authorize_signer(
+    user: &Account<User>,
+    signer: Pubkey,
+    session_key: Option<&mut Account<SessionKey>>,
+    permission: u8,
+    spend: u64,
+) -> Result<()> {
+    if signer == user.authority {
+        return Ok(());
+    }
+    
+    let session_key = session_key.ok_or(AlphaPackError::UnauthorizedSigner)?;
+    require!(
+        session_key.user == user.key() && session_key.delegate == signer,
+        AlphaPackError::UnauthorizedSigner
+    );
+    require!(
+        session_key.allowed_instructions & permission != 0,
+        AlphaPackError::InvalidSessionPermissions
+    );
+    require!(
+        Clock::get()?.unix_timestamp < session_key.expires_at,
+        AlphaPackError::SessionExpired
+    );
+    
+    let spent = session_key.spent.checked_add(spend).unwrap();
+    require!(spent <= session_key.spend_cap, AlphaPackError::SessionSpendCapExceeded);
+    session_key.spent = spent;
+    Ok(())
+}
+
+// Helper function to close a program-owned account that is not deserialised by Anchor
+fn close_record<'info>(info: &AccountInfo<'info>, destination: &AccountInfo<'info>) -> Result<()> {
+    let lamports = info.lamports();
+    **destination.try_borrow_mut_lamports()? = destination.lamports().checked_add(lamports).unwrap();
+    **info.try_borrow_mut_lamports()? = 0;
+    info.assign(&anchor_lang::system_program::ID);
+    info.realloc(0, false)?;
+    Ok(())
+}
+
+// Helper function to decode a pre-versioning (v1) account. v1 accounts carry no version
+// byte and are recognised by their fixed allocation size; later versions store the
+// version as the first field after the discriminator.
+pub fn read_legacy<T: Discriminator, L: AnchorDeserialize>(info: &AccountInfo, legacy_space: usize) -> Result<L> {
+    let data = info.try_borrow_data()?;
+    require!(data.len() == 8 + legacy_space, AlphaPackError::AlreadyMigrated);
+    require!(
+        data[..8] == T::discriminator(),
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(L::deserialize(&mut &data[8..])?)
+}
+
+// Helper function to resize a migrated account, top up its rent from payer and write the new layout
+pub fn write_migrated<'info, T: AccountSerialize>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+    account: &T,
+) -> Result<()> {
+    let rent_due = Rent::get()?.minimum_balance(new_len).saturating_sub(info.lamports());
+    if rent_due > 0 {
+        let cpi_accounts = anchor_lang::system_program::Transfer {
+            from: payer.clone(),
+            to: info.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.clone(), cpi_accounts);
+        anchor_lang::system_program::transfer(cpi_ctx, rent_due)?;
+    }
+    
+    info.realloc(new_len, true)?;
+    account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+    Ok(())
+}
+
+// Helper function to check a prospective member against the pack's requirements
+fn check_recruitment_requirements(pack: &Pack, user: &User) -> Result<()> {
+    require!(
+        user.social_score >= pack.min_social_score && user.win_rate >= pack.min_win_rate,
+        AlphaPackError::RequirementsNotMet
+    );
+    Ok(())
+}
+
+// Helper function to add a user to a recruiting pack
+fn add_member(
+    pack: &mut Account<Pack>,
+    user: &mut Account<User>,
+) -> Result<()> {
+    require!(pack.status == PackStatus::Recruiting, AlphaPackError::PackNotRecruiting);
+    require!(pack.members.len() < pack.max_members as usize, AlphaPackError::PackFull);
+    require!(!pack.members.contains(&user.authority), AlphaPackError::AlreadyInPack);
+    require!(user.pack_id.is_none(), AlphaPackError::UserAlreadyInPack);
+    
+    // Add user to pack
+    pack.members.push(user.authority);
+    pack.updated_at = Clock::get()?.unix_timestamp;
+    
+    // Update user's pack_id
+    user.pack_id = Some(pack.key());
+    user.updated_at = Clock::get()?.unix_timestamp;
+    
+    // If pack is full, change status to active
+    if pack.members.len() == pack.max_members as usize {
+        pack.status = PackStatus::Active;
+    }
+    
+    emit!(UserJoinedPack {
+        header: EventHeader::now()?,
+        pack: pack.key(),
+        user: user.key(),
+        member_count: pack.members.len() as u8,
+        timestamp: pack.updated_at,
+    });
+    
+    msg!("User {} joined pack {}", user.username, pack.name);
+    Ok(())
+}
+
+// Helper function to take a member out of a pack; the leader cannot be removed
+fn remove_pack_member(pack: &mut Account<Pack>, member: &mut Account<User>) -> Result<()> {
+    require!(member.authority != pack.leader, AlphaPackError::CannotRemoveLeader);
+    require!(pack.members.contains(&member.authority), AlphaPackError::UserNotInPack);
+    
+    pack.members.retain(|&m| m != member.authority);
+    pack.updated_at = Clock::get()?.unix_timestamp;
+    
+    member.pack_id = None;
+    member.updated_at = pack.updated_at;
+    
+    update_pack_status_after_departure(pack);
+    Ok(())
+}
+
+// Helper function to hand pack leadership to another member, returning the previous leader
+fn hand_over_leadership(pack: &mut Account<Pack>, new_leader: &User) -> Result<Pubkey> {
+    require!(new_leader.authority != pack.leader, AlphaPackError::AlreadyPackLeader);
+    require!(pack.members.contains(&new_leader.authority), AlphaPackError::UserNotInPack);
+    
+    let previous_leader = pack.leader;
+    pack.leader = new_leader.authority;
+    pack.updated_at = Clock::get()?.unix_timestamp;
+    Ok(previous_leader)
+}
+
+// Helper function to check that signer is pack_manager's governance PDA for the pack
+fn check_governance_signer(pack: &Pubkey, signer: &Pubkey) -> Result<()> {
+    let (expected_governance, _) = Pubkey::find_program_address(
+        &[b"governance", pack.as_ref()],
+        &PACK_MANAGER_PROGRAM_ID,
+    );
+    require_keys_eq!(*signer, expected_governance, AlphaPackError::InvalidGovernance);
+    Ok(())
+}
+
+// Helper function to update pack status after a member leaves or is removed
+fn update_pack_status_after_departure(pack: &mut Account<Pack>) {
+    if pack.members.is_empty() {
+        pack.status = PackStatus::Disbanded;
+    } else if pack.status == PackStatus::Active && pack.members.len() < 2 {
+        pack.status = PackStatus::Recruiting;
+    }
+}
+
+// Helper function to build a trade record from the priced legs
+fn build_trade(
+    user: &Account<User>,
+    authority: Pubkey,
+    trade_type: TradeType,
+    from_mint: &Account<Mint>,
+    to_mint: &Account<Mint>,
+    from_price: &Account<PriceFeed>,
+    to_price: &Account<PriceFeed>,
+    from_amount: u64,
+    to_amount: u64,
+    fee: u64,
+    signature: String,
+    verification: TradeVerification,
+) -> Result<Trade> {
+    require!(signature.len() <= 100, AlphaPackError::SignatureTooLong);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now - from_price.updated_at <= PriceFeed::MAX_AGE && now - to_price.updated_at <= PriceFeed::MAX_AGE,
+        AlphaPackError::StalePrice
+    );
+
+    // Value both legs in the quote unit; fees are charged in the from token
+    let from_value = quote_value(from_amount, from_mint.decimals, from_price.price)?;
+    let to_value = quote_value(to_amount, to_mint.decimals, to_price.price)?;
+    let fee_value = quote_value(fee, from_mint.decimals, from_price.price)?;
+    let profit_loss = to_value as i128 - from_value as i128 - fee_value as i128;
+
+    Ok(Trade {
+        authority,
+        user: user.key(),
+        pack: user.pack_id,
+        trade_type,
+        from_mint: from_mint.key(),
+        to_mint: to_mint.key(),
+        from_amount,
+        to_amount,
+        fee,
+        from_value,
+        to_value,
+        fee_value,
+        profit_loss: i64::try_from(profit_loss).map_err(|_| error!(AlphaPackError::ValueOverflow))?,
+        signature,
+        verification,
+        timestamp: now,
+    })
+}
+
+// Helper function to store a trade either in its own Trade account or in the user's
+// TradeLog; exactly one of the two must be supplied. Returns the address that holds it
+fn store_trade<'info>(
+    trade: &Trade,
+    trade_index: u64,
+    trade_account: Option<&mut Account<'info, Trade>>,
+    trade_log: Option<&AccountLoader<'info, TradeLog>>,
+) -> Result<Pubkey> {
+    match (trade_account, trade_log) {
+        (Some(account), None) => {
+            account.set_inner(trade.clone());
+            Ok(account.key())
+        }
+        (None, Some(trade_log)) => {
+            trade_log.load_mut()?.push(TradeLogEntry::new(trade_index, trade));
+            Ok(trade_log.key())
+        }
+        _ => err!(AlphaPackError::InvalidTradeStorage),
+    }
+}
+
+// Helper function to apply a signed score change; penalties floor at zero. Returns the new
+// score and the change actually applied
+fn apply_score_delta(score: u64, delta: i64) -> Result<(u64, i64)> {
+    let new_score = if delta >= 0 {
+        score.checked_add(delta as u64).ok_or(AlphaPackError::ValueOverflow)?
+    } else {
+        score.saturating_sub(delta.unsigned_abs())
+    };
+    Ok((new_score, (new_score as i128 - score as i128) as i64))
+}
+
+// Helper function to check that signer may configure a leaderboard: the admin for any scope,
+// or the pack's leader for that pack's member ranking
+fn authorize_leaderboard(
+    scope: &LeaderboardScope,
+    signer: Pubkey,
+    global_state: &GlobalState,
+    pack: Option<&Account<Pack>>,
+) -> Result<()> {
+    if signer == global_state.authority {
+        return Ok(());
+    }
+    match (scope, pack) {
+        (LeaderboardScope::Pack(key), Some(pack)) if pack.key() == *key && pack.leader == signer => Ok(()),
+        _ => err!(AlphaPackError::Unauthorized),
+    }
+}
+
+// Helper function to value a raw token amount in quote units
+fn quote_value(amount: u64, decimals: u8, price: u64) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(AlphaPackError::ValueOverflow)?
+        / 10u128.pow(decimals as u32);
+    u64::try_from(value).map_err(|_| error!(AlphaPackError::ValueOverflow))
+}
+
+// Helper function to fold a recorded trade into user, pack and global stats
+fn apply_trade<'info>(
+    trade: &Trade,
+    trade_key: Pubkey,
+    user: &mut Account<'info, User>,
+    pack: Option<&mut Account<'info, Pack>>,
+    global_state: &mut Account<'info, GlobalState>,
+    epoch: u64,
+    user_stats: &mut Account<'info, UserEpochStats>,
+    pack_stats: Option<&mut Account<'info, PackEpochStats>>,
+) -> Result<()> {
+    let volume = trade.from_value;
+    let profit_loss = trade.profit_loss;
+    let now = Clock::get()?.unix_timestamp;
+    require!(epoch == global_state.current_epoch(now), AlphaPackError::InvalidEpoch);
+
+    // Update user stats
+    user.total_trades = user.total_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+    user.total_volume = user.total_volume.checked_add(volume).ok_or(AlphaPackError::ValueOverflow)?;
+    user.profit_loss = user.profit_loss.checked_add(profit_loss).ok_or(AlphaPackError::ValueOverflow)?;
+
+    // Fees are summed in quote units; raw amounts of different mints do not add up
+    user.total_fees = user.total_fees.checked_add(trade.fee_value).ok_or(AlphaPackError::ValueOverflow)?;
+
+    // Win/loss counts and streak; break-even trades reset the streak
+    if profit_loss > 0 {
+        user.winning_trades = user.winning_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        user.current_streak = if user.current_streak > 0 { user.current_streak + 1 } else { 1 };
+    } else if profit_loss < 0 {
+        user.losing_trades = user.losing_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        user.current_streak = if user.current_streak < 0 { user.current_streak - 1 } else { -1 };
+    } else {
+        user.current_streak = 0;
+    }
+
+    // Win rate from exact counts; win_rate is kept as a whole percentage for older clients
+    user.win_rate_bps = (user.winning_trades * 10_000 / user.total_trades) as u16;
+    user.win_rate = (user.win_rate_bps / 100) as u8;
+
+    if user.total_trades == 1 {
+        user.best_trade = profit_loss;
+        user.worst_trade = profit_loss;
+    } else {
+        user.best_trade = user.best_trade.max(profit_loss);
+        user.worst_trade = user.worst_trade.min(profit_loss);
+    }
+
+    // Drawdown is measured on cumulative realised P&L
+    user.peak_profit_loss = user.peak_profit_loss.max(user.profit_loss);
+    let drawdown = user
+        .peak_profit_loss
+        .checked_sub(user.profit_loss)
+        .ok_or(AlphaPackError::ValueOverflow)? as u64;
+    user.max_drawdown = user.max_drawdown.max(drawdown);
+
+    user.updated_at = Clock::get()?.unix_timestamp;
+
+    // Update pack stats if user is in a pack; the accounts constraint ties the pack to user.pack_id
+    if user.pack_id.is_some() {
+        let pack = pack.ok_or(AlphaPackError::PackAccountRequired)?;
+
+        pack.total_trades = pack.total_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        if profit_loss > 0 {
+            pack.winning_trades = pack.winning_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        }
+        pack.win_rate = (pack.winning_trades * 100 / pack.total_trades) as u8;
+        pack.profit_loss = pack.profit_loss.checked_add(profit_loss).ok_or(AlphaPackError::ValueOverflow)?;
+        pack.total_volume = pack.total_volume.checked_add(volume).ok_or(AlphaPackError::ValueOverflow)?;
+        pack.score = pack
+            .score
+            .checked_add(if profit_loss > 0 { profit_loss as u64 } else { 0 })
+            .ok_or(AlphaPackError::ValueOverflow)?;
+        pack.updated_at = Clock::get()?.unix_timestamp;
+    }
+
+    // Update global state
+    global_state.total_volume = global_state.total_volume.checked_add(volume).ok_or(AlphaPackError::ValueOverflow)?;
+
+    // Update the current epoch's stats; the accounts are created by the first trade of the epoch
+    let started_at = global_state.epoch_start(epoch);
+    user_stats.open(user.key(), epoch, started_at);
+    user_stats.record_trade(volume, profit_loss, trade.fee_value, now)?;
+    if let Some(pack_id) = user.pack_id {
+        let pack_stats = pack_stats.ok_or(AlphaPackError::PackAccountRequired)?;
+        pack_stats.open(pack_id, epoch, started_at);
+        pack_stats.record_trade(volume, profit_loss, now)?;
+    }
+
+    emit!(TradeRecorded {
+        header: EventHeader::now()?,
+        trade: trade_key,
+        trade_index: user.total_trades - 1,
+        user: user.key(),
+        pack: user.pack_id,
+        from_amount: trade.from_amount,
+        to_amount: trade.to_amount,
+        volume,
+        profit_loss,
+        verification: trade.verification.clone(),
+        timestamp: trade.timestamp,
+    });
+
+    msg!("Trade recorded for user {}", user.username);
+    Ok(())
+}
+
+// Helper function to check that a settlement is closed by record_settled_trade in the
+// same transaction, with only allow-listed swap programs invoked in between
+fn verify_settlement_window(
+    instructions: &AccountInfo,
+    current_index: u16,
+    settlement_programs: &[Pubkey],
+) -> Result<()> {
+    let mut index = current_index as usize + 1;
+    loop {
+        let ix = load_instruction_at_checked(index, instructions)
+            .map_err(|_| error!(AlphaPackError::SettlementNotClosed))?;
+
+        if ix.program_id == crate::ID {
+            require!(
+                ix.data.len() >= 8 && ix.data[..8] == instruction::RecordSettledTrade::DISCRIMINATOR,
+                AlphaPackError::SettlementNotClosed
+            );
+            return Ok(());
+        }
+
+        require!(
+            settlement_programs.contains(&ix.program_id),
+            AlphaPackError::SettlementProgramNotAllowed
+        );
+        index += 1;
+    }
+}
+
+// Account structures
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GlobalState::INIT_SPACE,
+        seeds = [b"global_state"],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(username: String)]
+pub struct CreateUser<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + User::INIT_SPACE,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UsernameRecord::INIT_SPACE,
+        seeds = [b"username", username_seed(&username).as_ref()],
+        bump
+    )]
+    pub username_record: Account<'info, UsernameRecord>,
+    
+    /// CHECK: counters left by a closed user of this authority; absent if there was none
+    #[account(
+        mut,
+        seeds = [b"user_counters", authority.key().as_ref()],
+        bump
+    )]
+    pub user_counters: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(new_username: String)]
+pub struct RenameUser<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump,
+        has_one = authority
+    )]
+    pub user: Account<'info, User>,
+    
+    /// CHECK: registry entry for the current username; absent for users created before the registry
+    #[account(
+        mut,
+        seeds = [b"username", username_seed(&user.username).as_ref()],
+        bump
+    )]
+    pub old_username_record: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UsernameRecord::INIT_SPACE,
+        seeds = [b"username", username_seed(&new_username).as_ref()],
+        bump
+    )]
+    pub new_username_record: Account<'info, UsernameRecord>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateUserProfile<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RotateAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        close = new_authority,
+        seeds = [b"user", old_user.authority.as_ref()],
+        bump,
+        constraint = old_user.pending_authority == Some(new_authority.key()) @ AlphaPackError::NoPendingAuthority,
+        // Pack membership, proposal snapshots and session keys reference the old authority or PDA
+        constraint = old_user.pack_id.is_none() @ AlphaPackError::UserAlreadyInPack,
+        constraint = old_user.session_keys == 0 @ AlphaPackError::SessionKeysOpen,
+        // So do trades and epoch stats, which are only created by trading or scoring
+        constraint = old_user.total_trades == 0 && old_user.social_score == 0 @ AlphaPackError::UserHasHistory
+    )]
+    pub old_user: Account<'info, User>,
+    
+    /// CHECK: only checked to be empty; a trade log cannot follow the user to its new address
+    #[account(
+        seeds = [b"trade_log", old_user.key().as_ref()],
+        bump,
+        constraint = trade_log.data_is_empty() @ AlphaPackError::UserHasHistory
+    )]
+    pub trade_log: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = new_authority,
+        space = 8 + User::INIT_SPACE,
+        seeds = [b"user", new_authority.key().as_ref()],
+        bump
+    )]
+    pub new_user: Account<'info, User>,
+    
+    /// CHECK: registry entry for the username; absent for users created before the registry
+    #[account(
+        mut,
+        seeds = [b"username", username_seed(&old_user.username).as_ref()],
+        bump
+    )]
+    pub username_record: UncheckedAccount<'info>,
+    
+    /// CHECK: the global leaderboard; absent until it is created
+    #[account(
+        mut,
+        seeds = [b"leaderboard", LeaderboardScope::Global.seed().as_ref()],
+        bump
+    )]
+    pub global_leaderboard: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUser<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"user", authority.key().as_ref()],
+        bump,
+        constraint = user.pack_id.is_none() @ AlphaPackError::UserAlreadyInPack,
+        constraint = user.session_keys == 0 @ AlphaPackError::SessionKeysOpen
+    )]
+    pub user: Account<'info, User>,
+    
+    // Trade and pack PDAs are seeded by the user's counters, so they outlive it
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UserCounters::INIT_SPACE,
+        seeds = [b"user_counters", authority.key().as_ref()],
+        bump
+    )]
+    pub user_counters: Account<'info, UserCounters>,
+    
+    /// CHECK: registry entry for the username; absent for users created before the registry
+    #[account(
+        mut,
+        seeds = [b"username", username_seed(&user.username).as_ref()],
+        bump
+    )]
+    pub username_record: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate: Pubkey)]
+pub struct CreateSessionKey<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + SessionKey::INIT_SPACE,
+        seeds = [b"session", user.key().as_ref(), delegate.as_ref()],
+        bump
+    )]
+    pub session_key: Account<'info, SessionKey>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"session", user.key().as_ref(), session_key.delegate.as_ref()],
+        bump = session_key.bump
+    )]
+    pub session_key: Account<'info, SessionKey>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(pack_index: u64)]
+pub struct CreatePack<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Pack::INIT_SPACE,
+        seeds = [b"pack", authority.key().as_ref(), &pack_index.to_le_bytes()],
+        bump
+    )]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump,
+        constraint = user.packs_created == pack_index @ AlphaPackError::InvalidCounter
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinPack<'info> {
+    #[account(mut)]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecruitmentPolicy<'info> {
+    #[account(
+        mut,
+        has_one = leader @ AlphaPackError::NotPackLeader
+    )]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub leader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InviteMember<'info> {
+    #[account(
+        init,
+        payer = leader,
+        space = 8 + PackInvite::INIT_SPACE,
+        seeds = [b"invite", pack.key().as_ref(), invitee.authority.as_ref()],
+        bump
+    )]
+    pub invite: Account<'info, PackInvite>,
+    
+    #[account(has_one = leader @ AlphaPackError::NotPackLeader)]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"user", invitee.authority.as_ref()],
+        bump
+    )]
+    pub invitee: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub leader: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptInvite<'info> {
+    #[account(
+        mut,
+        seeds = [b"invite", pack.key().as_ref(), authority.key().as_ref()],
+        bump = invite.bump,
+        has_one = pack,
+        has_one = inviter,
+        close = inviter
+    )]
+    pub invite: Account<'info, PackInvite>,
+    
+    #[account(mut)]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// CHECK: receives the invite rent; matched against invite.inviter
+    #[account(mut)]
+    pub inviter: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyToPack<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PackApplication::INIT_SPACE,
+        seeds = [b"application", pack.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub application: Account<'info, PackApplication>,
+    
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveApplication<'info> {
+    #[account(
+        mut,
+        seeds = [b"application", pack.key().as_ref(), applicant.key().as_ref()],
+        bump = application.bump,
+        has_one = pack,
+        has_one = applicant,
+        close = applicant
+    )]
+    pub application: Account<'info, PackApplication>,
+    
+    #[account(
+        mut,
+        has_one = leader @ AlphaPackError::NotPackLeader
+    )]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", applicant.key().as_ref()],
+        bump
+    )]
+    pub applicant_user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// CHECK: receives the application rent; matched against application.applicant
+    #[account(mut)]
+    pub applicant: UncheckedAccount<'info>,
+    
+    pub leader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RejectApplication<'info> {
+    #[account(
+        mut,
+        seeds = [b"application", pack.key().as_ref(), applicant.key().as_ref()],
+        bump = application.bump,
+        has_one = pack,
+        has_one = applicant,
+        close = applicant
+    )]
+    pub application: Account<'info, PackApplication>,
+    
+    #[account(has_one = leader @ AlphaPackError::NotPackLeader)]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// CHECK: receives the application rent; matched against application.applicant
+    #[account(mut)]
+    pub applicant: UncheckedAccount<'info>,
+    
+    pub leader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelInvite<'info> {
+    #[account(
+        mut,
+        seeds = [b"invite", pack.key().as_ref(), invite.invitee.as_ref()],
+        bump = invite.bump,
+        has_one = pack,
+        has_one = inviter,
+        close = inviter
+    )]
+    pub invite: Account<'info, PackInvite>,
+    
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// CHECK: receives the invite rent; matched against invite.inviter
+    #[account(mut)]
+    pub inviter: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredInvite<'info> {
+    #[account(
+        mut,
+        seeds = [b"invite", invite.pack.as_ref(), invite.invitee.as_ref()],
+        bump = invite.bump,
+        has_one = inviter,
+        close = inviter
+    )]
+    pub invite: Account<'info, PackInvite>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// CHECK: receives the invite rent; matched against invite.inviter
+    #[account(mut)]
+    pub inviter: UncheckedAccount<'info>,
+    
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawApplication<'info> {
+    #[account(
+        mut,
+        seeds = [b"application", application.pack.as_ref(), applicant.key().as_ref()],
+        bump = application.bump,
+        has_one = applicant,
+        close = applicant
+    )]
+    pub application: Account<'info, PackApplication>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub applicant: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseExpiredApplication<'info> {
+    #[account(
+        mut,
+        seeds = [b"application", application.pack.as_ref(), applicant.key().as_ref()],
+        bump = application.bump,
+        has_one = applicant,
+        close = applicant
+    )]
+    pub application: Account<'info, PackApplication>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// CHECK: receives the application rent; matched against application.applicant
+    #[account(mut)]
+    pub applicant: UncheckedAccount<'info>,
+    
+    pub closer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct LeavePack<'info> {
+    #[account(mut)]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
 
 #[derive(Accounts)]
-pub struct CreateUser<'info> {
+pub struct RemoveMember<'info> {
+    #[account(
+        mut,
+        has_one = leader @ AlphaPackError::NotPackLeader
+    )]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", member.authority.as_ref()],
+        bump,
+        constraint = member.pack_id == Some(pack.key()) @ AlphaPackError::UserNotInPack
+    )]
+    pub member: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub leader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct TransferLeadership<'info> {
+    #[account(
+        mut,
+        has_one = leader @ AlphaPackError::NotPackLeader
+    )]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"user", new_leader.authority.as_ref()],
+        bump,
+        constraint = new_leader.pack_id == Some(pack.key()) @ AlphaPackError::UserNotInPack
+    )]
+    pub new_leader: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub leader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DisbandPack<'info> {
+    #[account(
+        mut,
+        has_one = leader @ AlphaPackError::NotPackLeader,
+        close = leader
+    )]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", leader.key().as_ref()],
+        bump,
+        constraint = leader_user.pack_id == Some(pack.key()) @ AlphaPackError::UserNotInPack
+    )]
+    pub leader_user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub leader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_index: u64, epoch: u64)]
+pub struct RecordTrade<'info> {
+    // Exactly one of trade and trade_log must be supplied
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Trade::INIT_SPACE,
+        seeds = [b"trade", user.authority.as_ref(), &trade_index.to_le_bytes()],
+        bump
+    )]
+    pub trade: Option<Account<'info, Trade>>,
+    
+    #[account(mut, has_one = user)]
+    pub trade_log: Option<AccountLoader<'info, TradeLog>>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", user.authority.as_ref()],
+        bump,
+        constraint = user.total_trades == trade_index @ AlphaPackError::InvalidCounter
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        constraint = user.pack_id == Some(pack.key()) @ AlphaPackError::PackMismatch
+    )]
+    pub pack: Option<Account<'info, Pack>>,
+    
+    #[account(mut)]
+    pub session_key: Option<Account<'info, SessionKey>>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + UserEpochStats::INIT_SPACE,
+        seeds = [b"user_epoch", user.key().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub user_epoch_stats: Box<Account<'info, UserEpochStats>>,
+    
+    // Required when the user is in a pack
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PackEpochStats::INIT_SPACE,
+        seeds = [b"pack_epoch", user.pack_id.unwrap_or_default().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub pack_epoch_stats: Option<Box<Account<'info, PackEpochStats>>>,
+    
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub from_mint: Box<Account<'info, Mint>>,
+    
+    pub to_mint: Box<Account<'info, Mint>>,
+    
+    #[account(
+        seeds = [b"price_feed", from_mint.key().as_ref()],
+        bump = from_price.bump
+    )]
+    pub from_price: Box<Account<'info, PriceFeed>>,
+    
+    #[account(
+        seeds = [b"price_feed", to_mint.key().as_ref()],
+        bump = to_price.bump
+    )]
+    pub to_price: Box<Account<'info, PriceFeed>>,
+    
+    /// The user authority or one of its session delegates
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_index: u64, epoch: u64)]
+pub struct RecordAttestedTrade<'info> {
+    // Exactly one of trade and trade_log must be supplied
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Trade::INIT_SPACE,
+        seeds = [b"trade", user.authority.as_ref(), &trade_index.to_le_bytes()],
+        bump
+    )]
+    pub trade: Option<Account<'info, Trade>>,
+    
+    #[account(mut, has_one = user)]
+    pub trade_log: Option<AccountLoader<'info, TradeLog>>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", user.authority.as_ref()],
+        bump,
+        constraint = user.total_trades == trade_index @ AlphaPackError::InvalidCounter
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        constraint = user.pack_id == Some(pack.key()) @ AlphaPackError::PackMismatch
+    )]
+    pub pack: Option<Account<'info, Pack>>,
+    
+    #[account(mut)]
+    pub session_key: Option<Account<'info, SessionKey>>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + UserEpochStats::INIT_SPACE,
+        seeds = [b"user_epoch", user.key().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub user_epoch_stats: Box<Account<'info, UserEpochStats>>,
+    
+    // Required when the user is in a pack
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PackEpochStats::INIT_SPACE,
+        seeds = [b"pack_epoch", user.pack_id.unwrap_or_default().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub pack_epoch_stats: Option<Box<Account<'info, PackEpochStats>>>,
+    
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub from_mint: Box<Account<'info, Mint>>,
+    
+    pub to_mint: Box<Account<'info, Mint>>,
+    
+    #[account(
+        seeds = [b"price_feed", from_mint.key().as_ref()],
+        bump = from_price.bump
+    )]
+    pub from_price: Box<Account<'info, PriceFeed>>,
+    
+    #[account(
+        seeds = [b"price_feed", to_mint.key().as_ref()],
+        bump = to_price.bump
+    )]
+    pub to_price: Box<Account<'info, PriceFeed>>,
+    
+    #[account(
+        constraint = global_state.trade_attestor == Some(attestor.key()) @ AlphaPackError::InvalidAttestor
+    )]
+    pub attestor: Signer<'info>,
+    
+    /// The user authority or one of its session delegates
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BeginTradeSettlement<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TradeSettlement::INIT_SPACE,
+        seeds = [b"settlement", authority.key().as_ref()],
+        bump
+    )]
+    pub settlement: Account<'info, TradeSettlement>,
+    
+    #[account(
+        seeds = [b"user", authority.key().as_ref()],
+        bump
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(token::authority = authority)]
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    #[account(token::authority = authority)]
+    pub to_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: validated against the instructions sysvar address
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(trade_index: u64, epoch: u64)]
+pub struct RecordSettledTrade<'info> {
+    // Exactly one of trade and trade_log must be supplied
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Trade::INIT_SPACE,
+        seeds = [b"trade", authority.key().as_ref(), &trade_index.to_le_bytes()],
+        bump
+    )]
+    pub trade: Option<Account<'info, Trade>>,
+    
+    #[account(mut, has_one = user)]
+    pub trade_log: Option<AccountLoader<'info, TradeLog>>,
+    
+    #[account(
+        mut,
+        seeds = [b"settlement", authority.key().as_ref()],
+        bump = settlement.bump,
+        has_one = authority,
+        has_one = from_token_account,
+        has_one = to_token_account,
+        close = authority
+    )]
+    pub settlement: Account<'info, TradeSettlement>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", authority.key().as_ref()],
+        bump,
+        constraint = user.total_trades == trade_index @ AlphaPackError::InvalidCounter
+    )]
+    pub user: Account<'info, User>,
+    
+    #[account(
+        mut,
+        constraint = user.pack_id == Some(pack.key()) @ AlphaPackError::PackMismatch
+    )]
+    pub pack: Option<Account<'info, Pack>>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + UserEpochStats::INIT_SPACE,
+        seeds = [b"user_epoch", user.key().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub user_epoch_stats: Box<Account<'info, UserEpochStats>>,
+    
+    // Required when the user is in a pack
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + PackEpochStats::INIT_SPACE,
+        seeds = [b"pack_epoch", user.pack_id.unwrap_or_default().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub pack_epoch_stats: Option<Box<Account<'info, PackEpochStats>>>,
+    
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub from_token_account: Account<'info, TokenAccount>,
+    
+    pub to_token_account: Account<'info, TokenAccount>,
+    
+    #[account(address = from_token_account.mint)]
+    pub from_mint: Box<Account<'info, Mint>>,
+    
+    #[account(address = to_token_account.mint)]
+    pub to_mint: Box<Account<'info, Mint>>,
+    
+    #[account(
+        seeds = [b"price_feed", from_mint.key().as_ref()],
+        bump = from_price.bump
+    )]
+    pub from_price: Box<Account<'info, PriceFeed>>,
+    
+    #[account(
+        seeds = [b"price_feed", to_mint.key().as_ref()],
+        bump = to_price.bump
+    )]
+    pub to_price: Box<Account<'info, PriceFeed>>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateTradeLog<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + TradeLog::INIT_SPACE,
+        seeds = [b"trade_log", user.key().as_ref()],
+        bump
+    )]
+    pub trade_log: AccountLoader<'info, TradeLog>,
+    
     #[account(
-        init,
-        payer = authority,
-        space = 8 + User::INIT_SPACE,
         seeds = [b"user", authority.key().as_ref()],
         bump
     )]
     pub user: Account<'info, User>,
     
-    #[account(mut)]
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
     pub global_state: Account<'info, GlobalState>,
     
     #[account(mut)]
@@ -340,120 +3022,1091 @@ pub struct CreateUser<'info> {
 }
 
 #[derive(Accounts)]
-pub struct CreatePack<'info> {
+#[instruction(trade_index: u64)]
+pub struct CloseTrade<'info> {
+    #[account(
+        mut,
+        seeds = [b"trade", authority.key().as_ref(), &trade_index.to_le_bytes()],
+        bump,
+        has_one = authority,
+        close = authority
+    )]
+    pub trade: Account<'info, Trade>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureTradeVerification<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePriceFeed<'info> {
     #[account(
         init,
-        payer = authority,
-        space = 8 + Pack::INIT_SPACE,
-        seeds = [b"pack", authority.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        payer = price_oracle,
+        space = 8 + PriceFeed::INIT_SPACE,
+        seeds = [b"price_feed", mint.key().as_ref()],
         bump
     )]
-    pub pack: Account<'info, Pack>,
+    pub price_feed: Account<'info, PriceFeed>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.price_oracle == Some(price_oracle.key()) @ AlphaPackError::InvalidPriceOracle
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub price_oracle: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePriceFeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"price_feed", price_feed.mint.as_ref()],
+        bump = price_feed.bump
+    )]
+    pub price_feed: Account<'info, PriceFeed>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = global_state.price_oracle == Some(price_oracle.key()) @ AlphaPackError::InvalidPriceOracle
+    )]
+    pub global_state: Account<'info, GlobalState>,
     
+    pub price_oracle: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct UpdateSocialScore<'info> {
     #[account(
         mut,
-        seeds = [b"user", authority.key().as_ref()],
+        seeds = [b"user", user.authority.as_ref()],
         bump
     )]
     pub user: Account<'info, User>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user.pack_id == Some(pack.key()) @ AlphaPackError::PackMismatch
+    )]
+    pub pack: Option<Account<'info, Pack>>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserEpochStats::INIT_SPACE,
+        seeds = [b"user_epoch", user.key().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub user_epoch_stats: Box<Account<'info, UserEpochStats>>,
+    
+    // Required when the user is in a pack
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + PackEpochStats::INIT_SPACE,
+        seeds = [b"pack_epoch", user.pack_id.unwrap_or_default().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub pack_epoch_stats: Option<Box<Account<'info, PackEpochStats>>>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
     pub global_state: Account<'info, GlobalState>,
     
+    /// The score oracle, or the social_rewards rewards_system PDA via CPI
+    pub scorer: Signer<'info>,
+    
+    /// Funds the epoch stats accounts the first time they are touched in an epoch
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub payer: Signer<'info>,
     
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct JoinPack<'info> {
+pub struct MigrateGlobalState<'info> {
+    /// CHECK: still holds a legacy layout; checked by size and discriminator in the handler
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump,
+        owner = crate::ID
+    )]
+    pub global_state: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUser<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(mut, owner = crate::ID)]
+    pub user: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePack<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(mut, owner = crate::ID)]
+    pub pack: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SyncTreasuryValue<'info> {
     #[account(mut)]
     pub pack: Account<'info, Pack>,
     
     #[account(
-        mut,
-        seeds = [b"user", authority.key().as_ref()],
-        bump
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
     )]
-    pub user: Account<'info, User>,
+    pub global_state: Account<'info, GlobalState>,
     
+    /// pack_manager treasury PDA for this pack, checked in the handler
+    pub treasury: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPackStrategy<'info> {
     #[account(mut)]
-    pub authority: Signer<'info>,
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// pack_manager governance PDA for this pack, checked in the handler
+    pub governance: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct LeavePack<'info> {
+pub struct GovernanceRemoveMember<'info> {
     #[account(mut)]
     pub pack: Account<'info, Pack>,
     
     #[account(
         mut,
-        seeds = [b"user", authority.key().as_ref()],
+        seeds = [b"user", member.authority.as_ref()],
+        bump,
+        constraint = member.pack_id == Some(pack.key()) @ AlphaPackError::UserNotInPack
+    )]
+    pub member: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// pack_manager governance PDA for this pack, checked in the handler
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GovernanceTransferLeadership<'info> {
+    #[account(mut)]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"user", new_leader.authority.as_ref()],
+        bump,
+        constraint = new_leader.pack_id == Some(pack.key()) @ AlphaPackError::UserNotInPack
+    )]
+    pub new_leader: Account<'info, User>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    /// pack_manager governance PDA for this pack, checked in the handler
+    pub governance: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetPauseState<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetEpochLength<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(scope: LeaderboardScope)]
+pub struct CreateLeaderboard<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Leaderboard::INIT_SPACE,
+        seeds = [b"leaderboard", scope.seed().as_ref()],
         bump
     )]
-    pub user: Account<'info, User>,
+    pub leaderboard: Account<'info, Leaderboard>,
+    
+    pub pack: Option<Account<'info, Pack>>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
     
     #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetLeaderboardMetric<'info> {
+    #[account(
+        mut,
+        seeds = [b"leaderboard", leaderboard.scope.seed().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+    
+    pub pack: Option<Account<'info, Pack>>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CrankLeaderboard<'info> {
+    #[account(
+        mut,
+        seeds = [b"leaderboard", leaderboard.scope.seed().as_ref()],
+        bump = leaderboard.bump
+    )]
+    pub leaderboard: Account<'info, Leaderboard>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
+    )]
+    pub global_state: Account<'info, GlobalState>,
+    
+    pub cranker: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct RecordTrade<'info> {
+pub struct EvictLeaderboardEntries<'info> {
     #[account(
-        init,
-        payer = authority,
-        space = 8 + Trade::INIT_SPACE,
-        seeds = [b"trade", authority.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
-        bump
+        mut,
+        seeds = [b"leaderboard", leaderboard.scope.seed().as_ref()],
+        bump = leaderboard.bump
     )]
-    pub trade: Account<'info, Trade>,
+    pub leaderboard: Account<'info, Leaderboard>,
     
     #[account(
-        mut,
-        seeds = [b"user", authority.key().as_ref()],
-        bump
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        constraint = !global_state.paused @ AlphaPackError::ProgramPaused
     )]
-    pub user: Account<'info, User>,
+    pub global_state: Account<'info, GlobalState>,
     
-    #[account(mut)]
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureAdminApprovals<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump = global_state.bump,
+        has_one = authority
+    )]
     pub global_state: Account<'info, GlobalState>,
     
-    #[account(mut)]
     pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct UpdateSocialScore<'info> {
-    #[account(
-        mut,
-        seeds = [b"user", authority.key().as_ref()],
-        bump
-    )]
-    pub user: Account<'info, User>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
+// Data structures
+#[account]
+pub struct GlobalState {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub total_packs: u64,
+    pub total_users: u64,
+    pub total_volume: u64,
+    pub bump: u8,
+    pub paused: bool,
+    pub trade_attestor: Option<Pubkey>,
+    pub verified_trades_only: bool,
+    pub settlement_programs: Vec<Pubkey>,
+    pub price_oracle: Option<Pubkey>,
+    pub score_oracle: Option<Pubkey>,
+    pub pauser: Option<Pubkey>,
+    pub admin: AdminConfig,
+    // Stats epochs: epoch_base_index starts at epoch_base_time, then one per epoch_length seconds
+    pub epoch_length: i64,
+    pub epoch_base_index: u64,
+    pub epoch_base_time: i64,
+}
+
+impl GlobalState {
+    pub const VERSION: u8 = 2;
+    pub const MAX_SETTLEMENT_PROGRAMS: usize = 8;
+    pub const DEFAULT_EPOCH_LENGTH: i64 = 7 * 24 * 60 * 60;
+    pub const MIN_EPOCH_LENGTH: i64 = 60 * 60;
+    pub const INIT_SPACE: usize = 1 + 32 + 8 + 8 + 8 + 1 + 1 + (1 + 32) + 1
+        + (4 + Self::MAX_SETTLEMENT_PROGRAMS * 32) + (1 + 32) + (1 + 32) + (1 + 32) + AdminConfig::SPACE + 8 + 8 + 8;
+
+    pub fn from_v1(v1: GlobalStateV1, now: i64) -> Self {
+        Self {
+            version: Self::VERSION,
+            authority: v1.authority,
+            total_packs: v1.total_packs,
+            total_users: v1.total_users,
+            total_volume: v1.total_volume,
+            bump: v1.bump,
+            paused: v1.paused,
+            trade_attestor: None,
+            verified_trades_only: false,
+            settlement_programs: Vec::new(),
+            price_oracle: None,
+            score_oracle: None,
+            pauser: None,
+            admin: AdminConfig::default(),
+            epoch_length: Self::DEFAULT_EPOCH_LENGTH,
+            epoch_base_index: 0,
+            epoch_base_time: now,
+        }
+    }
+
+    /// Index of the stats epoch containing `now`
+    pub fn current_epoch(&self, now: i64) -> u64 {
+        self.epoch_base_index + ((now - self.epoch_base_time).max(0) / self.epoch_length) as u64
+    }
+
+    /// Start time of `epoch`; only meaningful for epochs since the last length change
+    pub fn epoch_start(&self, epoch: u64) -> i64 {
+        self.epoch_base_time + epoch.saturating_sub(self.epoch_base_index) as i64 * self.epoch_length
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct AdminConfig {
+    pub pending_authority: Option<Pubkey>,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+impl AdminConfig {
+    pub const MAX_APPROVERS: usize = 5;
+    pub const SPACE: usize = (1 + 32) + (4 + Self::MAX_APPROVERS * 32) + 1;
+
+    /// Require `threshold` approvers among the authority and the signed `co_signers`
+    pub fn check_approval(&self, authority: &Pubkey, co_signers: &[AccountInfo]) -> Result<()> {
+        if self.approvers.is_empty() {
+            return Ok(());
+        }
+        
+        let approvals = self
+            .approvers
+            .iter()
+            .filter(|approver| {
+                *approver == authority
+                    || co_signers.iter().any(|info| info.is_signer && info.key == *approver)
+            })
+            .count();
+        require!(approvals >= self.threshold as usize, AlphaPackError::InsufficientApprovals);
+        Ok(())
+    }
+
+    /// Replace the approver set; an empty set with threshold 0 turns approvals off
+    pub fn set_approvers(&mut self, approvers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(approvers.len() <= Self::MAX_APPROVERS, AlphaPackError::InvalidAdminConfig);
+        for (i, approver) in approvers.iter().enumerate() {
+            require!(!approvers[..i].contains(approver), AlphaPackError::InvalidAdminConfig);
+        }
+        if approvers.is_empty() {
+            require!(threshold == 0, AlphaPackError::InvalidAdminConfig);
+        } else {
+            require!(
+                threshold >= 1 && threshold as usize <= approvers.len(),
+                AlphaPackError::InvalidAdminConfig
+            );
+        }
+        
+        self.approvers = approvers;
+        self.threshold = threshold;
+        Ok(())
+    }
+
+    /// Complete a proposed transfer, returning the key that becomes the authority
+    pub fn accept(&mut self, signer: Pubkey) -> Result<Pubkey> {
+        require!(self.pending_authority == Some(signer), AlphaPackError::NoPendingAuthority);
+        self.pending_authority = None;
+        Ok(signer)
+    }
+
+    /// Approval-gated nomination of the next authority of `account`; `None` cancels it
+    pub fn propose(
+        &mut self,
+        account: Pubkey,
+        authority: Pubkey,
+        co_signers: &[AccountInfo],
+        new_authority: Option<Pubkey>,
+        timestamp: i64,
+    ) -> Result<()> {
+        self.check_approval(&authority, co_signers)?;
+        self.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            header: EventHeader::now()?,
+            account,
+            authority,
+            pending_authority: new_authority,
+            timestamp,
+        });
+        Ok(())
+    }
+
+    /// Complete a proposed transfer of `account` away from `authority`, returning the new authority
+    pub fn transfer(&mut self, account: Pubkey, authority: Pubkey, signer: Pubkey, timestamp: i64) -> Result<Pubkey> {
+        let new_authority = self.accept(signer)?;
+
+        emit!(AuthorityTransferred {
+            header: EventHeader::now()?,
+            account,
+            previous_authority: authority,
+            new_authority,
+            timestamp,
+        });
+        Ok(new_authority)
+    }
+
+    /// Approval-gated replacement of the approver set of `account`
+    pub fn configure(
+        &mut self,
+        account: Pubkey,
+        authority: Pubkey,
+        co_signers: &[AccountInfo],
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+        timestamp: i64,
+    ) -> Result<()> {
+        self.check_approval(&authority, co_signers)?;
+        self.set_approvers(approvers.clone(), threshold)?;
+
+        emit!(AdminApprovalsConfigured {
+            header: EventHeader::now()?,
+            account,
+            approvers,
+            threshold,
+            timestamp,
+        });
+        Ok(())
+    }
+}
+
+#[account]
+pub struct User {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub username: String,
+    pub wallet_address: Pubkey,
+    pub pack_id: Option<Pubkey>,
+    pub total_trades: u64,
+    pub total_volume: u64,
+    pub win_rate: u8,
+    pub profit_loss: i64,
+    pub social_score: u64,
+    pub rank: u64,
+    pub packs_created: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    // Performance statistics
+    pub winning_trades: u64,
+    pub losing_trades: u64,
+    pub win_rate_bps: u16,
+    pub peak_profit_loss: i64,
+    pub max_drawdown: u64,
+    pub best_trade: i64,
+    pub worst_trade: i64,
+    pub current_streak: i32,
+    pub total_fees: u64,
+    pub metadata_uri: String,
+    pub pending_authority: Option<Pubkey>,
+    // Open SessionKey accounts seeded by this user's address
+    pub session_keys: u8,
+}
+
+impl User {
+    pub const VERSION: u8 = 2;
+    pub const MAX_METADATA_URI_LENGTH: usize = 200;
+    pub const INIT_SPACE: usize = 1 + 32 + (4 + 50) + 32 + (1 + 32) + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8
+        + 8 + 8 + 2 + 8 + 8 + 8 + 8 + 4 + 8 + (4 + Self::MAX_METADATA_URI_LENGTH) + (1 + 32) + 1;
+
+    // Per-trade history is not on-chain, so only the aggregate statistics can be recovered
+    pub fn from_v1(v1: UserV1, now: i64) -> Self {
+        let winning_trades = v1.total_trades * v1.win_rate as u64 / 100;
+        Self {
+            version: Self::VERSION,
+            authority: v1.authority,
+            username: v1.username,
+            wallet_address: v1.wallet_address,
+            pack_id: v1.pack_id,
+            total_trades: v1.total_trades,
+            total_volume: v1.total_volume,
+            win_rate: v1.win_rate,
+            profit_loss: v1.profit_loss,
+            social_score: v1.social_score,
+            rank: v1.rank,
+            packs_created: 0,
+            created_at: v1.created_at,
+            updated_at: now,
+            winning_trades,
+            losing_trades: v1.total_trades - winning_trades,
+            win_rate_bps: v1.win_rate as u16 * 100,
+            peak_profit_loss: v1.profit_loss.max(0),
+            max_drawdown: 0,
+            best_trade: 0,
+            worst_trade: 0,
+            current_streak: 0,
+            total_fees: 0,
+            metadata_uri: String::new(),
+            pending_authority: None,
+            session_keys: 0,
+        }
+    }
+}
+
+#[account]
+pub struct Pack {
+    pub version: u8,
+    pub authority: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub leader: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub max_members: u8,
+    pub status: PackStatus,
+    pub score: u64,
+    pub total_volume: u64,
+    pub win_rate: u8,
+    pub social_score: u64,
+    pub treasury_value: u64,
+    pub total_trades: u64,
+    pub winning_trades: u64,
+    pub profit_loss: i64,
+    pub strategy_focus: StrategyFocus,
+    pub risk_level: RiskLevel,
+    pub recruitment_mode: RecruitmentMode,
+    pub min_social_score: u64,
+    pub min_win_rate: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Pack {
+    pub const VERSION: u8 = 2;
+    pub const INIT_SPACE: usize = 1 + 32 + (4 + 100) + (4 + 500) + 32 + (4 + 20 * 32) + 1 + 1 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 1 + 8 + 1 + 8 + 8;
+
+    pub fn from_v1(v1: PackV1, now: i64) -> Self {
+        Self {
+            version: Self::VERSION,
+            authority: v1.authority,
+            name: v1.name,
+            description: v1.description,
+            leader: v1.leader,
+            members: v1.members,
+            max_members: v1.max_members,
+            status: v1.status,
+            score: v1.score,
+            total_volume: v1.total_volume,
+            win_rate: v1.win_rate,
+            social_score: v1.social_score,
+            treasury_value: v1.treasury_value,
+            total_trades: 0,
+            winning_trades: 0,
+            profit_loss: 0,
+            strategy_focus: v1.strategy_focus,
+            risk_level: v1.risk_level,
+            recruitment_mode: RecruitmentMode::Open,
+            min_social_score: 0,
+            min_win_rate: 0,
+            created_at: v1.created_at,
+            updated_at: now,
+        }
+    }
+}
+
+#[account]
+pub struct Trade {
+    pub authority: Pubkey,
+    pub user: Pubkey,
+    pub pack: Option<Pubkey>,
+    pub trade_type: TradeType,
+    pub from_mint: Pubkey,
+    pub to_mint: Pubkey,
+    pub from_amount: u64,
+    pub to_amount: u64,
+    pub fee: u64,
+    // Quote-currency valuations, see PriceFeed
+    pub from_value: u64,
+    pub to_value: u64,
+    pub fee_value: u64,
+    pub profit_loss: i64,
+    pub signature: String,
+    pub verification: TradeVerification,
+    pub timestamp: i64,
+}
+
+impl Trade {
+    pub const INIT_SPACE: usize = 32 + 32 + (1 + 32) + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + (4 + 100) + 1 + 8;
+}
+
+/// Oracle price for a mint, in quote units (QUOTE_DECIMALS) per whole token
+#[account]
+pub struct PriceFeed {
+    pub mint: Pubkey,
+    pub price: u64,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl PriceFeed {
+    pub const INIT_SPACE: usize = 32 + 8 + 8 + 1;
+    pub const QUOTE_DECIMALS: u8 = 6;
+    pub const MAX_AGE: i64 = 300;
+}
+
+#[account]
+pub struct TradeSettlement {
+    pub authority: Pubkey,
+    pub from_token_account: Pubkey,
+    pub to_token_account: Pubkey,
+    pub from_balance_before: u64,
+    pub to_balance_before: u64,
+    pub instruction_index: u16,
+    pub slot: u64,
+    pub bump: u8,
+}
+
+impl TradeSettlement {
+    pub const INIT_SPACE: usize = 32 + 32 + 32 + 8 + 8 + 2 + 8 + 1;
+}
+
+/// Per-user ring buffer of the last CAPACITY trades, an alternative to one Trade account per trade
+#[account(zero_copy)]
+pub struct TradeLog {
+    pub user: Pubkey,
+    /// Trades ever appended; the next entry goes to slot total_logged % CAPACITY
+    pub total_logged: u64,
+    pub created_at: i64,
+    pub bump: u8,
+    pub _padding: [u8; 7],
+    pub entries: [TradeLogEntry; 64],
+}
+
+impl TradeLog {
+    pub const CAPACITY: usize = 64;
+    pub const INIT_SPACE: usize = 32 + 8 + 8 + 1 + 7 + Self::CAPACITY * TradeLogEntry::SPACE;
+
+    pub fn push(&mut self, entry: TradeLogEntry) {
+        let slot = (self.total_logged % Self::CAPACITY as u64) as usize;
+        self.entries[slot] = entry;
+        self.total_logged = self.total_logged.checked_add(1).unwrap();
+    }
+
+    /// Logged entries, oldest first
+    pub fn recent(&self) -> impl Iterator<Item = &TradeLogEntry> {
+        let len = (self.total_logged as usize).min(Self::CAPACITY);
+        let start = (self.total_logged as usize - len) % Self::CAPACITY;
+        (0..len).map(move |i| &self.entries[(start + i) % Self::CAPACITY])
+    }
+}
+
+/// Fixed-size copy of a Trade; the signature is not kept, it is in the TradeRecorded transaction
+#[zero_copy]
+pub struct TradeLogEntry {
+    pub trade_index: u64,
+    pub timestamp: i64,
+    pub from_mint: Pubkey,
+    pub to_mint: Pubkey,
+    pub from_amount: u64,
+    pub to_amount: u64,
+    pub fee: u64,
+    pub from_value: u64,
+    pub to_value: u64,
+    pub fee_value: u64,
+    pub profit_loss: i64,
+    /// TradeType discriminant
+    pub trade_type: u8,
+    /// TradeVerification discriminant
+    pub verification: u8,
+    pub _padding: [u8; 6],
+}
+
+impl TradeLogEntry {
+    pub const SPACE: usize = 8 + 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1 + 6;
+
+    pub fn new(trade_index: u64, trade: &Trade) -> Self {
+        Self {
+            trade_index,
+            timestamp: trade.timestamp,
+            from_mint: trade.from_mint,
+            to_mint: trade.to_mint,
+            from_amount: trade.from_amount,
+            to_amount: trade.to_amount,
+            fee: trade.fee,
+            from_value: trade.from_value,
+            to_value: trade.to_value,
+            fee_value: trade.fee_value,
+            profit_loss: trade.profit_loss,
+            trade_type: trade.trade_type.clone() as u8,
+            verification: trade.verification.clone() as u8,
+            _padding: [0; 6],
+        }
+    }
+}
+
+#[account]
+pub struct PackInvite {
+    pub pack: Pubkey,
+    pub invitee: Pubkey,
+    pub inviter: Pubkey,
+    pub expires_at: i64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl PackInvite {
+    pub const INIT_SPACE: usize = 32 + 32 + 32 + 8 + 8 + 1;
+    pub const MAX_DURATION: i64 = 30 * 24 * 60 * 60;
+}
+
+#[account]
+pub struct PackApplication {
+    pub pack: Pubkey,
+    pub applicant: Pubkey,
+    pub expires_at: i64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl PackApplication {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 1;
+    pub const MAX_DURATION: i64 = 30 * 24 * 60 * 60;
+}
+
+#[account]
+pub struct UsernameRecord {
+    pub username: String,
+    pub user: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl UsernameRecord {
+    pub const MIN_LENGTH: usize = 3;
+    pub const INIT_SPACE: usize = (4 + 50) + 32 + 8 + 1;
+}
+
+/// Trade and pack counters of a closed user, resumed when its authority creates a user again
+#[account]
+pub struct UserCounters {
+    pub authority: Pubkey,
+    pub total_trades: u64,
+    pub packs_created: u64,
+    pub bump: u8,
+}
+
+impl UserCounters {
+    pub const INIT_SPACE: usize = 32 + 8 + 8 + 1;
+}
+
+#[account]
+pub struct UserEpochStats {
+    pub user: Pubkey,
+    pub epoch: u64,
+    pub started_at: i64,
+    pub total_trades: u64,
+    pub winning_trades: u64,
+    pub total_volume: u64,
+    pub profit_loss: i64,
+    pub total_fees: u64,
+    pub social_score_delta: i64,
+    pub updated_at: i64,
+}
+
+impl UserEpochStats {
+    pub const INIT_SPACE: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+
+    /// Stamp the key fields; the account is created lazily so this runs on every update
+    pub fn open(&mut self, user: Pubkey, epoch: u64, started_at: i64) {
+        self.user = user;
+        self.epoch = epoch;
+        self.started_at = started_at;
+    }
+
+    pub fn record_trade(&mut self, volume: u64, profit_loss: i64, fee: u64, now: i64) -> Result<()> {
+        self.total_trades = self.total_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        if profit_loss > 0 {
+            self.winning_trades = self.winning_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        }
+        self.total_volume = self.total_volume.checked_add(volume).ok_or(AlphaPackError::ValueOverflow)?;
+        self.profit_loss = self.profit_loss.checked_add(profit_loss).ok_or(AlphaPackError::ValueOverflow)?;
+        self.total_fees = self.total_fees.checked_add(fee).ok_or(AlphaPackError::ValueOverflow)?;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct PackEpochStats {
+    pub pack: Pubkey,
+    pub epoch: u64,
+    pub started_at: i64,
+    pub total_trades: u64,
+    pub winning_trades: u64,
+    pub total_volume: u64,
+    pub profit_loss: i64,
+    pub social_score_delta: i64,
+    pub updated_at: i64,
+}
+
+impl PackEpochStats {
+    pub const INIT_SPACE: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+
+    /// Stamp the key fields; the account is created lazily so this runs on every update
+    pub fn open(&mut self, pack: Pubkey, epoch: u64, started_at: i64) {
+        self.pack = pack;
+        self.epoch = epoch;
+        self.started_at = started_at;
+    }
+
+    pub fn record_trade(&mut self, volume: u64, profit_loss: i64, now: i64) -> Result<()> {
+        self.total_trades = self.total_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        if profit_loss > 0 {
+            self.winning_trades = self.winning_trades.checked_add(1).ok_or(AlphaPackError::ValueOverflow)?;
+        }
+        self.total_volume = self.total_volume.checked_add(volume).ok_or(AlphaPackError::ValueOverflow)?;
+        self.profit_loss = self.profit_loss.checked_add(profit_loss).ok_or(AlphaPackError::ValueOverflow)?;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+#[account]
+pub struct Leaderboard {
+    pub scope: LeaderboardScope,
+    pub metric: RankingMetric,
+    // Sorted best first; ties keep the entry that was ranked first
+    pub entries: Vec<RankEntry>,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl Leaderboard {
+    pub const MAX_ENTRIES: usize = 100;
+    pub const MAX_BATCH: usize = 10;
+    pub const INIT_SPACE: usize = (1 + 32) + 1 + (4 + Self::MAX_ENTRIES * RankEntry::SPACE) + 8 + 1;
+
+    /// Move `key` to its sorted position for `score`, dropping whatever falls off the end
+    pub fn upsert(&mut self, key: Pubkey, score: i128) {
+        self.entries.retain(|entry| entry.key != key);
+        let index = self.entries.partition_point(|entry| entry.score >= score);
+        if index < Self::MAX_ENTRIES {
+            self.entries.insert(index, RankEntry { key, score });
+            self.entries.truncate(Self::MAX_ENTRIES);
+        }
+    }
+
+    /// Remove `key`; the entries below it move up one place
+    pub fn remove(&mut self, key: &Pubkey) {
+        self.entries.retain(|entry| entry.key != *key);
+    }
+
+    /// 1-based position of `key`, or 0 when it is not on the leaderboard
+    pub fn rank_of(&self, key: &Pubkey) -> u64 {
+        self.entries
+            .iter()
+            .position(|entry| entry.key == *key)
+            .map_or(0, |index| index as u64 + 1)
+    }
 }
 
-#[derive(Accounts)]
-pub struct SetPauseState<'info> {
-    #[account(
-        mut,
-        seeds = [b"global_state"],
-        bump = global_state.bump,
-        has_one = authority
-    )]
-    pub global_state: Account<'info, GlobalState>,
-    
-    pub authority: Signer<'info>,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RankEntry {
+    pub key: Pubkey,
+    pub score: i128,
+}
+
+impl RankEntry {
+    pub const SPACE: usize = 32 + 16;
 }
 
-// Data structures
 #[account]
-pub struct GlobalState {
+pub struct SessionKey {
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+    pub allowed_instructions: u8,
+    pub expires_at: i64,
+    pub spend_cap: u64,
+    pub spent: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl SessionKey {
+    // Bits of `allowed_instructions`. Retired bits stay reserved so keys already granted keep
+    // their meaning; create_session_key rejects them
+    pub const RECORD_TRADE: u8 = 1 << 0;
+    /// Reserved: update_social_score is restricted to the score oracle and no longer delegable
+    pub const RESERVED_UPDATE_SOCIAL_SCORE: u8 = 1 << 1;
+    pub const VOTE_ON_PROPOSAL: u8 = 1 << 2;
+    pub const ALL: u8 = Self::RECORD_TRADE | Self::VOTE_ON_PROPOSAL;
+
+    pub const INIT_SPACE: usize = 32 + 32 + 1 + 8 + 8 + 8 + 8 + 1;
+    pub const MAX_DURATION: i64 = 30 * 24 * 60 * 60;
+}
+
+/// Leading field of every event emitted by the Alpha Pack programs
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EventHeader {
+    /// Layout version of the event that follows, bumped whenever any event's fields change
+    pub schema_version: u8,
+    /// Slot the event was emitted in. Events carry no sequence number, so no shared counter
+    /// account is write-locked to number them; consumers order them by
+    /// `(slot, block index, log index)`, the transaction's position in the slot's block and the
+    /// event's position among the transaction's `Program data:` lines, and identify them by
+    /// `(signature, log index)`.
+    pub slot: u64,
+}
+
+impl EventHeader {
+    pub const SCHEMA_VERSION: u8 = 1;
+
+    /// Header for an event emitted in the current slot
+    pub fn now() -> Result<Self> {
+        Ok(Self {
+            schema_version: Self::SCHEMA_VERSION,
+            slot: Clock::get()?.slot,
+        })
+    }
+}
+
+// Legacy account layouts, kept so migrate_* instructions can decode deployed accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GlobalStateV1 {
     pub authority: Pubkey,
     pub total_packs: u64,
     pub total_users: u64,
@@ -462,12 +4115,14 @@ pub struct GlobalState {
     pub paused: bool,
 }
 
-impl GlobalState {
+impl GlobalStateV1 {
     pub const INIT_SPACE: usize = 32 + 8 + 8 + 8 + 1 + 1;
 }
 
-#[account]
-pub struct User {
+/// User as originally deployed: no version byte, and `packs_created` does not exist yet, so
+/// `created_at` directly follows `rank`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UserV1 {
     pub authority: Pubkey,
     pub username: String,
     pub wallet_address: Pubkey,
@@ -482,12 +4137,12 @@ pub struct User {
     pub updated_at: i64,
 }
 
-impl User {
+impl UserV1 {
     pub const INIT_SPACE: usize = 32 + (4 + 50) + 32 + (1 + 32) + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 8;
 }
 
-#[account]
-pub struct Pack {
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PackV1 {
     pub authority: Pubkey,
     pub name: String,
     pub description: String,
@@ -506,30 +4161,10 @@ pub struct Pack {
     pub updated_at: i64,
 }
 
-impl Pack {
+impl PackV1 {
     pub const INIT_SPACE: usize = 32 + (4 + 100) + (4 + 500) + 32 + (4 + 20 * 32) + 1 + 1 + 8 + 8 + 1 + 8 + 8 + 1 + 1 + 8 + 8;
 }
 
-#[account]
-pub struct Trade {
-    pub authority: Pubkey,
-    pub user: Pubkey,
-    pub pack: Option<Pubkey>,
-    pub trade_type: TradeType,
-    pub from_token: String,
-    pub to_token: String,
-    pub from_amount: u64,
-    pub to_amount: u64,
-    pub fee: u64,
-    pub profit_loss: i64,
-    pub signature: String,
-    pub timestamp: i64,
-}
-
-impl Trade {
-    pub const INIT_SPACE: usize = 32 + 32 + (1 + 32) + 1 + (4 + 10) + (4 + 10) + 8 + 8 + 8 + 8 + (4 + 100) + 8;
-}
-
 // Enums
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum PackStatus {
@@ -554,6 +4189,13 @@ pub enum RiskLevel {
     High,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum RecruitmentMode {
+    Open,
+    InviteOnly,
+    Application,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum TradeType {
     Spot,
@@ -562,17 +4204,144 @@ pub enum TradeType {
     YieldFarming,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Role {
+    ScoreOracle,
+    TradeAttestor,
+    PriceOracle,
+    Pauser,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RankingMetric {
+    ProfitLoss,
+    Volume,
+    SocialScore,
+}
+
+impl RankingMetric {
+    pub fn user_score(&self, user: &User) -> i128 {
+        match self {
+            RankingMetric::ProfitLoss => user.profit_loss as i128,
+            RankingMetric::Volume => user.total_volume as i128,
+            RankingMetric::SocialScore => user.social_score as i128,
+        }
+    }
+
+    pub fn pack_score(&self, pack: &Pack) -> i128 {
+        match self {
+            RankingMetric::ProfitLoss => pack.profit_loss as i128,
+            RankingMetric::Volume => pack.total_volume as i128,
+            RankingMetric::SocialScore => pack.social_score as i128,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaderboardScope {
+    // All users; the only scope mirrored into User.rank
+    Global,
+    // Members of one pack
+    Pack(Pubkey),
+    // Packs ranked against each other
+    Packs,
+}
+
+impl LeaderboardScope {
+    /// Seed distinguishing the leaderboard PDA for this scope
+    pub fn seed(&self) -> Pubkey {
+        match self {
+            LeaderboardScope::Global => Pubkey::default(),
+            LeaderboardScope::Pack(pack) => *pack,
+            LeaderboardScope::Packs => crate::ID,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TradeVerification {
+    Unverified,
+    Attested,
+    Settled,
+}
+
 // Events
 #[event]
 pub struct UserCreated {
+    pub header: EventHeader,
     pub user: Pubkey,
     pub authority: Pubkey,
     pub username: String,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct UserRenamed {
+    pub header: EventHeader,
+    pub user: Pubkey,
+    pub old_username: String,
+    pub new_username: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserProfileUpdated {
+    pub header: EventHeader,
+    pub user: Pubkey,
+    pub wallet_address: Pubkey,
+    pub metadata_uri: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityRotationProposed {
+    pub header: EventHeader,
+    pub user: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityRotated {
+    pub header: EventHeader,
+    pub old_user: Pubkey,
+    pub new_user: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserClosed {
+    pub header: EventHeader,
+    pub user: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SessionKeyCreated {
+    pub header: EventHeader,
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+    pub allowed_instructions: u8,
+    pub expires_at: i64,
+    pub spend_cap: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SessionKeyRevoked {
+    pub header: EventHeader,
+    pub user: Pubkey,
+    pub delegate: Pubkey,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct PackCreated {
+    pub header: EventHeader,
     pub pack: Pubkey,
     pub leader: Pubkey,
     pub name: String,
@@ -582,6 +4351,7 @@ pub struct PackCreated {
 
 #[event]
 pub struct UserJoinedPack {
+    pub header: EventHeader,
     pub pack: Pubkey,
     pub user: Pubkey,
     pub member_count: u8,
@@ -590,28 +4360,255 @@ pub struct UserJoinedPack {
 
 #[event]
 pub struct UserLeftPack {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub user: Pubkey,
+    pub member_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecruitmentPolicyUpdated {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub recruitment_mode: RecruitmentMode,
+    pub min_social_score: u64,
+    pub min_win_rate: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MemberInvited {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub invitee: Pubkey,
+    pub inviter: Pubkey,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationSubmitted {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub applicant: Pubkey,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationResolved {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub applicant: Pubkey,
+    pub approved: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InviteClosed {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub invitee: Pubkey,
+    pub closed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ApplicationClosed {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub applicant: Pubkey,
+    pub closed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MemberRemoved {
+    pub header: EventHeader,
     pub pack: Pubkey,
     pub user: Pubkey,
+    pub removed_by: Pubkey,
     pub member_count: u8,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct LeadershipTransferred {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub previous_leader: Pubkey,
+    pub new_leader: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PackDisbanded {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub leader: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PackStrategyUpdated {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub strategy_focus: StrategyFocus,
+    pub risk_level: RiskLevel,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TradeRecorded {
+    pub header: EventHeader,
+    /// The Trade account, or the TradeLog the trade was appended to
     pub trade: Pubkey,
+    pub trade_index: u64,
     pub user: Pubkey,
     pub pack: Option<Pubkey>,
     pub from_amount: u64,
     pub to_amount: u64,
+    pub volume: u64,
     pub profit_loss: i64,
+    pub verification: TradeVerification,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub header: EventHeader,
+    pub role: Role,
+    pub account: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeVerificationConfigured {
+    pub header: EventHeader,
+    pub verified_trades_only: bool,
+    pub settlement_programs: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriceUpdated {
+    pub header: EventHeader,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityProposed {
+    pub header: EventHeader,
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Option<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub header: EventHeader,
+    pub account: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AdminApprovalsConfigured {
+    pub header: EventHeader,
+    pub account: Pubkey,
+    pub approvers: Vec<Pubkey>,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseStateChanged {
+    pub header: EventHeader,
+    pub paused: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeLogCreated {
+    pub header: EventHeader,
+    pub trade_log: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeClosed {
+    pub header: EventHeader,
+    pub trade: Pubkey,
+    pub trade_index: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EpochLengthUpdated {
+    pub header: EventHeader,
+    pub epoch_length: i64,
+    pub next_epoch: u64,
+    pub starts_at: i64,
+}
+
+#[event]
+pub struct LeaderboardConfigured {
+    pub header: EventHeader,
+    pub leaderboard: Pubkey,
+    pub scope: LeaderboardScope,
+    pub metric: RankingMetric,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LeaderboardCranked {
+    pub header: EventHeader,
+    pub leaderboard: Pubkey,
+    pub processed: u8,
+    pub ranked: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LeaderboardEntriesEvicted {
+    pub header: EventHeader,
+    pub leaderboard: Pubkey,
+    pub evicted: u8,
+    pub ranked: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub header: EventHeader,
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryValueSynced {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub treasury_value: u64,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct SocialScoreUpdated {
+    pub header: EventHeader,
     pub user: Pubkey,
     pub pack: Option<Pubkey>,
-    pub score_increase: u64,
+    pub score_delta: i64,
     pub new_score: u64,
     pub timestamp: i64,
 }
@@ -643,4 +4640,108 @@ pub enum AlphaPackError {
     ProgramPaused,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Only verified trades can be recorded")]
+    VerifiedTradeRequired,
+    #[msg("Signer is not the registered trade attestor")]
+    InvalidAttestor,
+    #[msg("Too many settlement programs")]
+    TooManySettlementPrograms,
+    #[msg("Settlement token accounts must differ")]
+    InvalidSettlementAccounts,
+    #[msg("Settlement must be closed by record_settled_trade in the same transaction")]
+    SettlementNotClosed,
+    #[msg("Program is not allowed inside a trade settlement")]
+    SettlementProgramNotAllowed,
+    #[msg("Trade settlement has expired")]
+    SettlementExpired,
+    #[msg("Token balance deltas do not describe a trade")]
+    InvalidSettlementDelta,
+    #[msg("Trade signature is too long")]
+    SignatureTooLong,
+    #[msg("Counter does not match on-chain state")]
+    InvalidCounter,
+    #[msg("Only the pack leader can do this")]
+    NotPackLeader,
+    #[msg("Pack leader cannot be removed")]
+    CannotRemoveLeader,
+    #[msg("User is already the pack leader")]
+    AlreadyPackLeader,
+    #[msg("Pack still has members besides the leader")]
+    PackNotEmpty,
+    #[msg("Pack does not accept members this way")]
+    RecruitmentModeMismatch,
+    #[msg("User does not meet the pack's requirements")]
+    RequirementsNotMet,
+    #[msg("Invalid win rate")]
+    InvalidWinRate,
+    #[msg("Invalid expiry duration")]
+    InvalidExpiry,
+    #[msg("Invite has expired")]
+    InviteExpired,
+    #[msg("Application has expired")]
+    ApplicationExpired,
+    #[msg("Pack account does not match the user's pack")]
+    PackMismatch,
+    #[msg("User's pack account must be provided")]
+    PackAccountRequired,
+    #[msg("Signer is not the pack's treasury")]
+    InvalidTreasury,
+    #[msg("Account already uses the current layout")]
+    AlreadyMigrated,
+    #[msg("Signer is not the registered price oracle")]
+    InvalidPriceOracle,
+    #[msg("Invalid price")]
+    InvalidPrice,
+    #[msg("Price feed is stale")]
+    StalePrice,
+    #[msg("Quote value overflow")]
+    ValueOverflow,
+    #[msg("Username must be at least 3 characters of letters, digits or underscores")]
+    InvalidUsername,
+    #[msg("Metadata URI is too long")]
+    MetadataUriTooLong,
+    #[msg("Invalid authority")]
+    InvalidAuthority,
+    #[msg("No pending authority rotation for this signer")]
+    NoPendingAuthority,
+    #[msg("Signer is neither the user authority nor a session delegate")]
+    UnauthorizedSigner,
+    #[msg("Session key does not allow this instruction")]
+    InvalidSessionPermissions,
+    #[msg("Session key has expired")]
+    SessionExpired,
+    #[msg("Session key spend cap exceeded")]
+    SessionSpendCapExceeded,
+    #[msg("Signer does not hold the required role")]
+    UnauthorizedRole,
+    #[msg("Social score can only be changed by the score oracle or social rewards program")]
+    UnauthorizedScorer,
+    #[msg("Not enough admin approvers signed")]
+    InsufficientApprovals,
+    #[msg("Invalid admin approver set or threshold")]
+    InvalidAdminConfig,
+    #[msg("Ranking batch must hold between 1 and 10 accounts")]
+    InvalidRankingBatch,
+    #[msg("Epoch does not match the current stats epoch")]
+    InvalidEpoch,
+    #[msg("Epoch length is below the minimum")]
+    InvalidEpochLength,
+    #[msg("Provide exactly one of a Trade account or a TradeLog")]
+    InvalidTradeStorage,
+    #[msg("Signer is not the pack's governance authority")]
+    InvalidGovernance,
+    #[msg("Invite or application has not expired yet")]
+    NotExpired,
+    #[msg("Signer is not the inviter, invitee or pack leader")]
+    NotInviteParty,
+    #[msg("User has trades, a trade log or a social score tied to its address")]
+    UserHasHistory,
+    #[msg("Revoke the user's session keys first")]
+    SessionKeysOpen,
+    #[msg("User has too many session keys")]
+    TooManySessionKeys,
+    #[msg("Account is not on the leaderboard")]
+    NotOnLeaderboard,
+    #[msg("Leaderboard entry still qualifies")]
+    EntryStillQualifies,
 }
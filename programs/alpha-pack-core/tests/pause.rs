@@ -0,0 +1,592 @@
+use alpha_pack_core::{
+    accounts, instruction, AlphaPackError, LeaderboardScope, RankingMetric, RecruitmentMode, RiskLevel, Role,
+    SessionKey, StrategyFocus, TradeType,
+};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use solana_program_test::{tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+fn global_state_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"global_state"], &alpha_pack_core::ID)
+}
+
+fn user_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user", authority.as_ref()], &alpha_pack_core::ID).0
+}
+
+fn user_counters_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user_counters", authority.as_ref()], &alpha_pack_core::ID).0
+}
+
+fn username_pda(username: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"username", alpha_pack_core::username_seed(username).as_ref()],
+        &alpha_pack_core::ID,
+    )
+    .0
+}
+
+fn leaderboard_pda(scope: &LeaderboardScope) -> Pubkey {
+    Pubkey::find_program_address(&[b"leaderboard", scope.seed().as_ref()], &alpha_pack_core::ID).0
+}
+
+fn price_feed_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"price_feed", mint.as_ref()], &alpha_pack_core::ID).0
+}
+
+async fn send(
+    ctx: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let blockhash = ctx.banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&ctx.payer.pubkey()), &all_signers, blockhash);
+    ctx.banks_client.process_transaction(tx).await
+}
+
+async fn fund(ctx: &mut ProgramTestContext, to: &Pubkey) {
+    let ix = system_instruction::transfer(&ctx.payer.pubkey(), to, 1_000_000_000);
+    send(ctx, ix, &[]).await.unwrap();
+}
+
+async fn create_mint(ctx: &mut ProgramTestContext) -> Pubkey {
+    let mint = Keypair::new();
+    let rent = ctx.banks_client.get_rent().await.unwrap();
+    let ix = system_instruction::create_account(
+        &ctx.payer.pubkey(),
+        &mint.pubkey(),
+        rent.minimum_balance(spl_token::state::Mint::LEN),
+        spl_token::state::Mint::LEN as u64,
+        &spl_token::ID,
+    );
+    send(ctx, ix, &[&mint]).await.unwrap();
+    let ix = spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &ctx.payer.pubkey(), None, 6)
+        .unwrap();
+    send(ctx, ix, &[]).await.unwrap();
+    mint.pubkey()
+}
+
+fn create_price_feed_ix(price_oracle: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::CreatePriceFeed {
+            price_feed: price_feed_pda(mint),
+            mint: *mint,
+            global_state: global_state_pda().0,
+            price_oracle: *price_oracle,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreatePriceFeed { price: 1_000_000 }.data(),
+    }
+}
+
+fn create_user_ix(authority: &Pubkey, username: &str) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::CreateUser {
+            user: user_pda(authority),
+            username_record: username_pda(username),
+            user_counters: user_counters_pda(authority),
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateUser {
+            username: username.to_string(),
+            wallet_address: *authority,
+        }
+        .data(),
+    }
+}
+
+fn rename_user_ix(authority: &Pubkey, old_username: &str, new_username: &str) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::RenameUser {
+            user: user_pda(authority),
+            old_username_record: username_pda(old_username),
+            new_username_record: username_pda(new_username),
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::RenameUser {
+            new_username: new_username.to_string(),
+        }
+        .data(),
+    }
+}
+
+fn update_user_profile_ix(authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::UpdateUserProfile {
+            user: user_pda(authority),
+            global_state: global_state_pda().0,
+            authority: *authority,
+        }
+        .to_account_metas(None),
+        data: instruction::UpdateUserProfile {
+            wallet_address: Some(Pubkey::new_unique()),
+            metadata_uri: None,
+        }
+        .data(),
+    }
+}
+
+fn rotate_authority_ix(authority: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::RotateAuthority {
+            user: user_pda(authority),
+            global_state: global_state_pda().0,
+            authority: *authority,
+        }
+        .to_account_metas(None),
+        data: instruction::RotateAuthority {
+            new_authority: Some(*new_authority),
+        }
+        .data(),
+    }
+}
+
+fn close_user_ix(authority: &Pubkey, username: &str) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::CloseUser {
+            user: user_pda(authority),
+            user_counters: user_counters_pda(authority),
+            username_record: username_pda(username),
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CloseUser {}.data(),
+    }
+}
+
+fn create_session_key_ix(authority: &Pubkey, delegate: &Pubkey) -> Instruction {
+    let user = user_pda(authority);
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::CreateSessionKey {
+            session_key: Pubkey::find_program_address(
+                &[b"session", user.as_ref(), delegate.as_ref()],
+                &alpha_pack_core::ID,
+            )
+            .0,
+            user,
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateSessionKey {
+            delegate: *delegate,
+            allowed_instructions: SessionKey::ALL,
+            duration_seconds: 3600,
+            spend_cap: 1_000_000,
+        }
+        .data(),
+    }
+}
+
+fn create_pack_ix(authority: &Pubkey, pack_index: u64) -> (Pubkey, Instruction) {
+    let pack = Pubkey::find_program_address(
+        &[b"pack", authority.as_ref(), &pack_index.to_le_bytes()],
+        &alpha_pack_core::ID,
+    )
+    .0;
+    let ix = Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::CreatePack {
+            pack,
+            user: user_pda(authority),
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreatePack {
+            pack_index,
+            name: "Wolves".to_string(),
+            description: "Pause test pack".to_string(),
+            max_members: 5,
+            strategy_focus: StrategyFocus::Balanced,
+            risk_level: RiskLevel::Medium,
+        }
+        .data(),
+    };
+    (pack, ix)
+}
+
+fn join_pack_ix(pack: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::JoinPack {
+            pack: *pack,
+            user: user_pda(authority),
+            global_state: global_state_pda().0,
+            authority: *authority,
+        }
+        .to_account_metas(None),
+        data: instruction::JoinPack {}.data(),
+    }
+}
+
+fn set_recruitment_policy_ix(pack: &Pubkey, leader: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::SetRecruitmentPolicy {
+            pack: *pack,
+            global_state: global_state_pda().0,
+            leader: *leader,
+        }
+        .to_account_metas(None),
+        data: instruction::SetRecruitmentPolicy {
+            recruitment_mode: RecruitmentMode::Application,
+            min_social_score: 0,
+            min_win_rate: 0,
+        }
+        .data(),
+    }
+}
+
+fn invite_member_ix(pack: &Pubkey, leader: &Pubkey, invitee: &Pubkey) -> Instruction {
+    let invite = Pubkey::find_program_address(&[b"invite", pack.as_ref(), invitee.as_ref()], &alpha_pack_core::ID).0;
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::InviteMember {
+            invite,
+            pack: *pack,
+            invitee: user_pda(invitee),
+            global_state: global_state_pda().0,
+            leader: *leader,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InviteMember { duration_seconds: 3_600 }.data(),
+    }
+}
+
+fn apply_to_pack_ix(pack: &Pubkey, authority: &Pubkey) -> Instruction {
+    let application =
+        Pubkey::find_program_address(&[b"application", pack.as_ref(), authority.as_ref()], &alpha_pack_core::ID).0;
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::ApplyToPack {
+            application,
+            pack: *pack,
+            user: user_pda(authority),
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::ApplyToPack { duration_seconds: 3_600 }.data(),
+    }
+}
+
+fn leave_pack_ix(pack: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::LeavePack {
+            pack: *pack,
+            user: user_pda(authority),
+            global_state: global_state_pda().0,
+            authority: *authority,
+        }
+        .to_account_metas(None),
+        data: instruction::LeavePack {}.data(),
+    }
+}
+
+fn remove_member_ix(pack: &Pubkey, leader: &Pubkey, member: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::RemoveMember {
+            pack: *pack,
+            member: user_pda(member),
+            global_state: global_state_pda().0,
+            leader: *leader,
+        }
+        .to_account_metas(None),
+        data: instruction::RemoveMember {}.data(),
+    }
+}
+
+fn transfer_leadership_ix(pack: &Pubkey, leader: &Pubkey, new_leader: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::TransferLeadership {
+            pack: *pack,
+            new_leader: user_pda(new_leader),
+            global_state: global_state_pda().0,
+            leader: *leader,
+        }
+        .to_account_metas(None),
+        data: instruction::TransferLeadership {}.data(),
+    }
+}
+
+fn disband_pack_ix(pack: &Pubkey, leader: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::DisbandPack {
+            pack: *pack,
+            leader_user: user_pda(leader),
+            global_state: global_state_pda().0,
+            leader: *leader,
+        }
+        .to_account_metas(None),
+        data: instruction::DisbandPack {}.data(),
+    }
+}
+
+fn user_epoch_stats_pda(authority: &Pubkey, epoch: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"user_epoch", user_pda(authority).as_ref(), &epoch.to_le_bytes()],
+        &alpha_pack_core::ID,
+    )
+    .0
+}
+
+fn pack_epoch_stats_pda(pack: &Pubkey, epoch: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"pack_epoch", pack.as_ref(), &epoch.to_le_bytes()], &alpha_pack_core::ID).0
+}
+
+fn record_trade_ix(
+    authority: &Pubkey,
+    pack: &Pubkey,
+    trade_index: u64,
+    from_mint: &Pubkey,
+    to_mint: &Pubkey,
+) -> Instruction {
+    let trade = Pubkey::find_program_address(
+        &[b"trade", authority.as_ref(), &trade_index.to_le_bytes()],
+        &alpha_pack_core::ID,
+    )
+    .0;
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::RecordTrade {
+            trade: Some(trade),
+            trade_log: None,
+            user: user_pda(authority),
+            pack: Some(*pack),
+            session_key: None,
+            user_epoch_stats: user_epoch_stats_pda(authority, 0),
+            pack_epoch_stats: Some(pack_epoch_stats_pda(pack, 0)),
+            global_state: global_state_pda().0,
+            from_mint: *from_mint,
+            to_mint: *to_mint,
+            from_price: price_feed_pda(from_mint),
+            to_price: price_feed_pda(to_mint),
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::RecordTrade {
+            trade_index,
+            epoch: 0,
+            trade_type: TradeType::Spot,
+            from_amount: 1_000,
+            to_amount: 1_100,
+            fee: 5,
+            signature: "sig".to_string(),
+        }
+        .data(),
+    }
+}
+
+fn create_trade_log_ix(authority: &Pubkey) -> Instruction {
+    let user = user_pda(authority);
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::CreateTradeLog {
+            trade_log: Pubkey::find_program_address(&[b"trade_log", user.as_ref()], &alpha_pack_core::ID).0,
+            user,
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateTradeLog {}.data(),
+    }
+}
+
+fn update_social_score_ix(scorer: &Pubkey, authority: &Pubkey, pack: &Pubkey, score_delta: i64) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::UpdateSocialScore {
+            user: user_pda(authority),
+            pack: Some(*pack),
+            user_epoch_stats: user_epoch_stats_pda(authority, 0),
+            pack_epoch_stats: Some(pack_epoch_stats_pda(pack, 0)),
+            global_state: global_state_pda().0,
+            scorer: *scorer,
+            payer: *scorer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::UpdateSocialScore { epoch: 0, score_delta }.data(),
+    }
+}
+
+fn set_role_ix(authority: &Pubkey, role: Role, account: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::SetRole {
+            global_state: global_state_pda().0,
+            authority: *authority,
+        }
+        .to_account_metas(None),
+        data: instruction::SetRole { role, account }.data(),
+    }
+}
+
+fn create_leaderboard_ix(authority: &Pubkey, scope: LeaderboardScope) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::CreateLeaderboard {
+            leaderboard: leaderboard_pda(&scope),
+            pack: None,
+            global_state: global_state_pda().0,
+            authority: *authority,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::CreateLeaderboard {
+            scope,
+            metric: RankingMetric::ProfitLoss,
+        }
+        .data(),
+    }
+}
+
+fn crank_leaderboard_ix(cranker: &Pubkey, scope: LeaderboardScope, user: &Pubkey) -> Instruction {
+    let mut accounts = accounts::CrankLeaderboard {
+        leaderboard: leaderboard_pda(&scope),
+        global_state: global_state_pda().0,
+        cranker: *cranker,
+    }
+    .to_account_metas(None);
+    accounts.push(AccountMeta::new(user_pda(user), false));
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts,
+        data: instruction::CrankLeaderboard {}.data(),
+    }
+}
+
+fn set_pause_state_ix(authority: &Pubkey, paused: bool) -> Instruction {
+    Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::SetPauseState {
+            global_state: global_state_pda().0,
+            authority: *authority,
+        }
+        .to_account_metas(None),
+        data: instruction::SetPauseState { paused }.data(),
+    }
+}
+
+fn assert_paused(result: Result<(), BanksClientError>) {
+    let err = result.expect_err("instruction should fail while paused").unwrap();
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(AlphaPackError::ProgramPaused.into()))
+    );
+}
+
+#[tokio::test]
+async fn every_entrypoint_rejects_while_paused() {
+    let mut ctx = ProgramTest::new("alpha_pack_core", alpha_pack_core::ID, None)
+        .start_with_context()
+        .await;
+    let admin = ctx.payer.pubkey();
+
+    let (global_state, _) = global_state_pda();
+    let init_ix = Instruction {
+        program_id: alpha_pack_core::ID,
+        accounts: accounts::Initialize {
+            global_state,
+            authority: admin,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::Initialize {}.data(),
+    };
+    send(&mut ctx, init_ix, &[]).await.unwrap();
+
+    let member = Keypair::new();
+    let outsider = Keypair::new();
+    let newcomer = Keypair::new();
+    for kp in [&member, &outsider, &newcomer] {
+        fund(&mut ctx, &kp.pubkey()).await;
+    }
+
+    send(&mut ctx, create_user_ix(&admin, "leader"), &[]).await.unwrap();
+    send(&mut ctx, create_user_ix(&member.pubkey(), "member"), &[&member]).await.unwrap();
+    send(&mut ctx, create_user_ix(&outsider.pubkey(), "outsider"), &[&outsider]).await.unwrap();
+
+    let (pack, ix) = create_pack_ix(&admin, 0);
+    send(&mut ctx, ix, &[]).await.unwrap();
+    send(&mut ctx, join_pack_ix(&pack, &member.pubkey()), &[&member]).await.unwrap();
+    send(&mut ctx, set_role_ix(&admin, Role::ScoreOracle, Some(admin)), &[]).await.unwrap();
+    send(&mut ctx, create_leaderboard_ix(&admin, LeaderboardScope::Global), &[]).await.unwrap();
+
+    // Every account record_trade loads must exist, so only the pause check can reject it
+    send(&mut ctx, set_role_ix(&admin, Role::PriceOracle, Some(admin)), &[]).await.unwrap();
+    let from_mint = create_mint(&mut ctx).await;
+    let to_mint = create_mint(&mut ctx).await;
+    for mint in [&from_mint, &to_mint] {
+        send(&mut ctx, create_price_feed_ix(&admin, mint), &[]).await.unwrap();
+    }
+
+    send(&mut ctx, set_pause_state_ix(&admin, true), &[]).await.unwrap();
+
+    assert_paused(send(&mut ctx, create_user_ix(&newcomer.pubkey(), "newcomer"), &[&newcomer]).await);
+    assert_paused(send(&mut ctx, rename_user_ix(&member.pubkey(), "member", "renamed"), &[&member]).await);
+    assert_paused(send(&mut ctx, update_user_profile_ix(&outsider.pubkey()), &[&outsider]).await);
+    assert_paused(send(&mut ctx, rotate_authority_ix(&outsider.pubkey(), &newcomer.pubkey()), &[&outsider]).await);
+    assert_paused(send(&mut ctx, close_user_ix(&outsider.pubkey(), "outsider"), &[&outsider]).await);
+    assert_paused(send(&mut ctx, create_session_key_ix(&outsider.pubkey(), &newcomer.pubkey()), &[&outsider]).await);
+
+    let (_, ix) = create_pack_ix(&outsider.pubkey(), 0);
+    assert_paused(send(&mut ctx, ix, &[&outsider]).await);
+
+    assert_paused(send(&mut ctx, join_pack_ix(&pack, &outsider.pubkey()), &[&outsider]).await);
+    assert_paused(send(&mut ctx, leave_pack_ix(&pack, &member.pubkey()), &[&member]).await);
+    assert_paused(send(&mut ctx, set_recruitment_policy_ix(&pack, &admin), &[]).await);
+    assert_paused(send(&mut ctx, invite_member_ix(&pack, &admin, &outsider.pubkey()), &[]).await);
+    assert_paused(send(&mut ctx, apply_to_pack_ix(&pack, &outsider.pubkey()), &[&outsider]).await);
+    assert_paused(send(&mut ctx, remove_member_ix(&pack, &admin, &member.pubkey()), &[]).await);
+    assert_paused(send(&mut ctx, transfer_leadership_ix(&pack, &admin, &member.pubkey()), &[]).await);
+    assert_paused(send(&mut ctx, disband_pack_ix(&pack, &admin), &[]).await);
+
+    assert_paused(send(&mut ctx, record_trade_ix(&admin, &pack, 0, &from_mint, &to_mint), &[]).await);
+    assert_paused(send(&mut ctx, create_trade_log_ix(&admin), &[]).await);
+
+    assert_paused(send(&mut ctx, update_social_score_ix(&admin, &admin, &pack, 10), &[]).await);
+
+    assert_paused(send(&mut ctx, create_leaderboard_ix(&admin, LeaderboardScope::Packs), &[]).await);
+    assert_paused(send(&mut ctx, crank_leaderboard_ix(&admin, LeaderboardScope::Global, &member.pubkey()), &[]).await);
+
+    // Unpausing lifts the circuit breaker
+    send(&mut ctx, set_pause_state_ix(&admin, false), &[]).await.unwrap();
+    send(&mut ctx, update_social_score_ix(&admin, &admin, &pack, -5), &[]).await.unwrap();
+}
@@ -1,6 +1,11 @@
+// Anchor handlers take one argument per instruction field and return its large Error type
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
-use alpha_pack_core::{Pack, User, Trade, TradeType};
+use alpha_pack_core::{
+    GlobalState, Pack, Trade, TradeType, TradeVerification, AdminConfig, AccountMigrated, EventHeader,
+    read_legacy, write_migrated,
+};
 
 declare_id!("ArbitrageExecutor1111111111111111111111111");
 
@@ -8,6 +13,24 @@ declare_id!("ArbitrageExecutor1111111111111111111111111");
 pub mod arbitrage_executor {
     use super::*;
 
+    /// Initialize the arbitrage executor global state (protocol admin only)
+    pub fn initialize_arbitrage_state(ctx: Context<InitializeArbitrageState>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.version = GlobalArbitrageState::VERSION;
+        global_state.authority = ctx.accounts.authority.key();
+        global_state.emergency_stop = false;
+        global_state.total_opportunities = 0;
+        global_state.total_executions = 0;
+        global_state.total_volume = 0;
+        global_state.total_profit = 0;
+        global_state.created_at = Clock::get()?.unix_timestamp;
+        global_state.updated_at = Clock::get()?.unix_timestamp;
+        global_state.admin = AdminConfig::default();
+
+        msg!("Arbitrage executor state initialized");
+        Ok(())
+    }
+
     /// Initialize arbitrage bot for a pack
     pub fn initialize_arbitrage_bot(
         ctx: Context<InitializeArbitrageBot>,
@@ -37,6 +60,7 @@ pub mod arbitrage_executor {
         bot.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(ArbitrageBotInitialized {
+            header: EventHeader::now()?,
             bot: bot.key(),
             pack: bot.pack,
             authority: bot.authority,
@@ -59,6 +83,7 @@ pub mod arbitrage_executor {
         bot.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(BotStatusChanged {
+            header: EventHeader::now()?,
             bot: bot.key(),
             is_active,
             timestamp: bot.updated_at,
@@ -71,6 +96,7 @@ pub mod arbitrage_executor {
     /// Create arbitrage opportunity
     pub fn create_opportunity(
         ctx: Context<CreateOpportunity>,
+        opportunity_index: u64,
         token_a: Pubkey,
         token_b: Pubkey,
         exchange_a: ExchangeType,
@@ -119,7 +145,12 @@ pub mod arbitrage_executor {
         opportunity.expires_at = current_time + 300; // 5 minutes expiry
         opportunity.updated_at = current_time;
 
+        let global_state = &mut ctx.accounts.global_state;
+        global_state.total_opportunities = global_state.total_opportunities.checked_add(1).unwrap();
+        global_state.updated_at = current_time;
+
         emit!(OpportunityCreated {
+            header: EventHeader::now()?,
             opportunity: opportunity.key(),
             creator: opportunity.creator,
             token_a,
@@ -130,7 +161,7 @@ pub mod arbitrage_executor {
             timestamp: current_time,
         });
 
-        msg!("Arbitrage opportunity created");
+        msg!("Arbitrage opportunity {} created", opportunity_index);
         Ok(())
     }
 
@@ -177,7 +208,6 @@ pub mod arbitrage_executor {
 
         // Execute the arbitrage trade
         let execution_result = execute_cross_exchange_trade(
-            &ctx,
             amount,
             opportunity.buy_price,
             opportunity.sell_price,
@@ -205,16 +235,22 @@ pub mod arbitrage_executor {
         trade_record.user = ctx.accounts.executor.key();
         trade_record.pack = Some(bot.pack);
         trade_record.trade_type = TradeType::Arbitrage;
-        trade_record.from_token = opportunity.token_a.to_string();
-        trade_record.to_token = opportunity.token_b.to_string();
+        trade_record.from_mint = opportunity.token_a;
+        trade_record.to_mint = opportunity.token_b;
         trade_record.from_amount = amount;
         trade_record.to_amount = execution_result.output_amount;
         trade_record.fee = execution_result.total_fees;
+        // Arbitrage legs are not priced against a quote feed
+        trade_record.from_value = 0;
+        trade_record.to_value = 0;
+        trade_record.fee_value = 0;
         trade_record.profit_loss = execution_result.actual_profit as i64;
         trade_record.signature = execution_result.signature;
+        trade_record.verification = TradeVerification::Unverified;
         trade_record.timestamp = current_time;
 
         emit!(ArbitrageExecuted {
+            header: EventHeader::now()?,
             opportunity: opportunity.key(),
             executor: ctx.accounts.executor.key(),
             bot: bot.key(),
@@ -260,6 +296,7 @@ pub mod arbitrage_executor {
         bot.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(BotSettingsUpdated {
+            header: EventHeader::now()?,
             bot: bot.key(),
             timestamp: bot.updated_at,
         });
@@ -283,6 +320,7 @@ pub mod arbitrage_executor {
         opportunity.updated_at = current_time;
 
         emit!(OpportunityClosed {
+            header: EventHeader::now()?,
             opportunity: opportunity.key(),
             total_executions: opportunity.executions,
             total_volume: opportunity.total_volume_executed,
@@ -297,10 +335,12 @@ pub mod arbitrage_executor {
     /// Emergency stop all bots (admin only)
     pub fn emergency_stop(ctx: Context<EmergencyStop>) -> Result<()> {
         let global_state = &mut ctx.accounts.global_state;
+        global_state.admin.check_approval(&global_state.authority, ctx.remaining_accounts)?;
         global_state.emergency_stop = true;
         global_state.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(EmergencyStopActivated {
+            header: EventHeader::now()?,
             authority: ctx.accounts.authority.key(),
             timestamp: global_state.updated_at,
         });
@@ -308,11 +348,76 @@ pub mod arbitrage_executor {
         msg!("Emergency stop activated - all arbitrage bots disabled");
         Ok(())
     }
+
+    /// Propose a new arbitrage admin; `None` cancels a pending proposal
+    pub fn propose_admin_authority(ctx: Context<ProposeAdminAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let (account, authority) = (global_state.key(), global_state.authority);
+        let now = Clock::get()?.unix_timestamp;
+        global_state.admin.propose(account, authority, ctx.remaining_accounts, new_authority, now)?;
+        global_state.updated_at = now;
+
+        msg!("Arbitrage authority transfer proposed");
+        Ok(())
+    }
+
+    /// Accept a proposed arbitrage admin transfer (signed by the new authority)
+    pub fn accept_admin_authority(ctx: Context<AcceptAdminAuthority>) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let (account, authority) = (global_state.key(), global_state.authority);
+        let now = Clock::get()?.unix_timestamp;
+        global_state.authority = global_state.admin.transfer(account, authority, ctx.accounts.new_authority.key(), now)?;
+        global_state.updated_at = now;
+
+        msg!("Arbitrage authority transferred to {}", global_state.authority);
+        Ok(())
+    }
+
+    /// Set the M-of-N approvers required for arbitrage admin actions; an empty set disables them
+    pub fn configure_admin_approvals(
+        ctx: Context<ConfigureAdminApprovals>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let global_state = &mut ctx.accounts.global_state;
+        let (account, authority) = (global_state.key(), global_state.authority);
+        let now = Clock::get()?.unix_timestamp;
+        global_state.admin.configure(account, authority, ctx.remaining_accounts, approvers, threshold, now)?;
+        global_state.updated_at = now;
+
+        msg!("Arbitrage admin approvals set to {}-of-{}", threshold, global_state.admin.approvers.len());
+        Ok(())
+    }
+
+    /// Upgrade the v1 arbitrage global state to the current layout (permissionless)
+    pub fn migrate_arbitrage_state(ctx: Context<MigrateArbitrageState>) -> Result<()> {
+        let info = ctx.accounts.global_state.to_account_info();
+        let legacy: GlobalArbitrageStateV1 =
+            read_legacy::<GlobalArbitrageState, _>(&info, GlobalArbitrageStateV1::INIT_SPACE)?;
+        let global_state = GlobalArbitrageState::from_v1(legacy);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + GlobalArbitrageState::INIT_SPACE,
+            &global_state,
+        )?;
+
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: GlobalArbitrageState::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Arbitrage state migrated to v{}", GlobalArbitrageState::VERSION);
+        Ok(())
+    }
 }
 
 // Helper function to execute cross-exchange trade
 fn execute_cross_exchange_trade(
-    ctx: &Context<ExecuteArbitrage>,
     amount: u64,
     buy_price: u64,
     sell_price: u64,
@@ -347,6 +452,32 @@ fn execute_cross_exchange_trade(
 }
 
 // Account structures
+#[derive(Accounts)]
+pub struct InitializeArbitrageState<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + GlobalArbitrageState::INIT_SPACE,
+        seeds = [b"global_state"],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalArbitrageState>,
+    
+    // Only the protocol admin may create the singleton, so it cannot be front-run at deploy
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        has_one = authority @ ArbitrageError::Unauthorized
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct InitializeArbitrageBot<'info> {
     #[account(
@@ -360,6 +491,14 @@ pub struct InitializeArbitrageBot<'info> {
     
     pub pack: Account<'info, Pack>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ ArbitrageError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub pack_leader: Signer<'info>,
     
@@ -382,16 +521,33 @@ pub struct SetBotStatus<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(opportunity_index: u64)]
 pub struct CreateOpportunity<'info> {
     #[account(
         init,
         payer = creator,
         space = 8 + ArbitrageOpportunity::INIT_SPACE,
-        seeds = [b"opportunity", creator.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"opportunity".as_ref(), &opportunity_index.to_le_bytes()],
         bump
     )]
     pub opportunity: Account<'info, ArbitrageOpportunity>,
     
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump,
+        constraint = global_state.total_opportunities == opportunity_index @ ArbitrageError::InvalidCounter
+    )]
+    pub global_state: Account<'info, GlobalArbitrageState>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ ArbitrageError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub creator: Signer<'info>,
     
@@ -410,11 +566,19 @@ pub struct ExecuteArbitrage<'info> {
         init,
         payer = executor,
         space = 8 + Trade::INIT_SPACE,
-        seeds = [b"trade", executor.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"trade", arbitrage_bot.key().as_ref(), &arbitrage_bot.total_trades.to_le_bytes()],
         bump
     )]
     pub trade_record: Account<'info, Trade>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ ArbitrageError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub executor: Signer<'info>,
     
@@ -429,6 +593,14 @@ pub struct UpdateBotSettings<'info> {
     )]
     pub arbitrage_bot: Account<'info, ArbitrageBot>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ ArbitrageError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     pub authority: Signer<'info>,
 }
 
@@ -453,6 +625,61 @@ pub struct EmergencyStop<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct ProposeAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalArbitrageState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalArbitrageState>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureAdminApprovals<'info> {
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump,
+        has_one = authority
+    )]
+    pub global_state: Account<'info, GlobalArbitrageState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateArbitrageState<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(
+        mut,
+        seeds = [b"global_state"],
+        bump,
+        owner = crate::ID
+    )]
+    pub global_state: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 // Data structures
 #[account]
 pub struct ArbitrageBot {
@@ -504,6 +731,7 @@ impl ArbitrageOpportunity {
 
 #[account]
 pub struct GlobalArbitrageState {
+    pub version: u8,
     pub authority: Pubkey,
     pub emergency_stop: bool,
     pub total_opportunities: u64,
@@ -512,10 +740,27 @@ pub struct GlobalArbitrageState {
     pub total_profit: u64,
     pub created_at: i64,
     pub updated_at: i64,
+    pub admin: AdminConfig,
 }
 
 impl GlobalArbitrageState {
-    pub const INIT_SPACE: usize = 32 + 1 + 8 + 8 + 8 + 8 + 8 + 8;
+    pub const VERSION: u8 = 2;
+    pub const INIT_SPACE: usize = 1 + GlobalArbitrageStateV1::INIT_SPACE + AdminConfig::SPACE;
+
+    pub fn from_v1(v1: GlobalArbitrageStateV1) -> Self {
+        Self {
+            version: Self::VERSION,
+            authority: v1.authority,
+            emergency_stop: v1.emergency_stop,
+            total_opportunities: v1.total_opportunities,
+            total_executions: v1.total_executions,
+            total_volume: v1.total_volume,
+            total_profit: v1.total_profit,
+            created_at: v1.created_at,
+            updated_at: v1.updated_at,
+            admin: AdminConfig::default(),
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -538,6 +783,23 @@ pub struct ExecutionResult {
     pub signature: String,
 }
 
+// Legacy account layouts, kept so migrate_arbitrage_state can decode deployed accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GlobalArbitrageStateV1 {
+    pub authority: Pubkey,
+    pub emergency_stop: bool,
+    pub total_opportunities: u64,
+    pub total_executions: u64,
+    pub total_volume: u64,
+    pub total_profit: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl GlobalArbitrageStateV1 {
+    pub const INIT_SPACE: usize = 32 + 1 + 8 + 8 + 8 + 8 + 8 + 8;
+}
+
 // Enums
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum ExchangeType {
@@ -562,6 +824,7 @@ pub enum OpportunityStatus {
 // Events
 #[event]
 pub struct ArbitrageBotInitialized {
+    pub header: EventHeader,
     pub bot: Pubkey,
     pub pack: Pubkey,
     pub authority: Pubkey,
@@ -572,6 +835,7 @@ pub struct ArbitrageBotInitialized {
 
 #[event]
 pub struct BotStatusChanged {
+    pub header: EventHeader,
     pub bot: Pubkey,
     pub is_active: bool,
     pub timestamp: i64,
@@ -579,6 +843,7 @@ pub struct BotStatusChanged {
 
 #[event]
 pub struct OpportunityCreated {
+    pub header: EventHeader,
     pub opportunity: Pubkey,
     pub creator: Pubkey,
     pub token_a: Pubkey,
@@ -591,6 +856,7 @@ pub struct OpportunityCreated {
 
 #[event]
 pub struct ArbitrageExecuted {
+    pub header: EventHeader,
     pub opportunity: Pubkey,
     pub executor: Pubkey,
     pub bot: Pubkey,
@@ -602,12 +868,14 @@ pub struct ArbitrageExecuted {
 
 #[event]
 pub struct BotSettingsUpdated {
+    pub header: EventHeader,
     pub bot: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct OpportunityClosed {
+    pub header: EventHeader,
     pub opportunity: Pubkey,
     pub total_executions: u32,
     pub total_volume: u64,
@@ -617,6 +885,7 @@ pub struct OpportunityClosed {
 
 #[event]
 pub struct EmergencyStopActivated {
+    pub header: EventHeader,
     pub authority: Pubkey,
     pub timestamp: i64,
 }
@@ -652,4 +921,8 @@ pub enum ArbitrageError {
     EmergencyStopActive,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Alpha Pack protocol is paused")]
+    ProtocolPaused,
+    #[msg("Counter does not match on-chain state")]
+    InvalidCounter,
 }
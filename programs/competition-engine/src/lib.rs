@@ -1,6 +1,9 @@
+// Anchor handlers take one argument per instruction field and return its large Error type
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
-use alpha_pack_core::{Pack, User, PackStatus};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use alpha_pack_core::{GlobalState, Pack, PackStatus, AccountMigrated, EventHeader, read_legacy, write_migrated};
 
 declare_id!("CompetitionEngine11111111111111111111111111");
 
@@ -8,9 +11,23 @@ declare_id!("CompetitionEngine11111111111111111111111111");
 pub mod competition_engine {
     use super::*;
 
+    /// Initialize the competition engine global state (protocol admin only)
+    pub fn initialize_competition_state(ctx: Context<InitializeCompetitionState>) -> Result<()> {
+        let competition_state = &mut ctx.accounts.competition_state;
+        competition_state.authority = ctx.accounts.authority.key();
+        competition_state.total_competitions = 0;
+        competition_state.total_tournaments = 0;
+        competition_state.bump = *ctx.bumps.get("competition_state").unwrap();
+        competition_state.created_at = Clock::get()?.unix_timestamp;
+
+        msg!("Competition engine state initialized");
+        Ok(())
+    }
+
     /// Create a new competition
     pub fn create_competition(
         ctx: Context<CreateCompetition>,
+        competition_index: u64,
         name: String,
         description: String,
         competition_type: CompetitionType,
@@ -22,11 +39,12 @@ pub mod competition_engine {
         require!(name.len() <= 100, CompetitionError::NameTooLong);
         require!(description.len() <= 1000, CompetitionError::DescriptionTooLong);
         require!(max_participants >= 2, CompetitionError::InvalidParticipantCount);
-        require!(duration_hours >= 1 && duration_hours <= 168, CompetitionError::InvalidDuration); // 1 hour to 1 week
+        require!((1..=168).contains(&duration_hours), CompetitionError::InvalidDuration); // 1 hour to 1 week
 
         let competition = &mut ctx.accounts.competition;
         let current_time = Clock::get()?.unix_timestamp;
 
+        competition.version = Competition::VERSION;
         competition.authority = ctx.accounts.authority.key();
         competition.name = name;
         competition.description = description;
@@ -44,7 +62,11 @@ pub mod competition_engine {
         competition.created_at = current_time;
         competition.updated_at = current_time;
 
+        let competition_state = &mut ctx.accounts.competition_state;
+        competition_state.total_competitions = competition_state.total_competitions.checked_add(1).unwrap();
+
         emit!(CompetitionCreated {
+            header: EventHeader::now()?,
             competition: competition.key(),
             name: competition.name.clone(),
             competition_type: competition.competition_type.clone(),
@@ -56,7 +78,7 @@ pub mod competition_engine {
             timestamp: current_time,
         });
 
-        msg!("Competition created: {}", competition.name);
+        msg!("Competition {} created: {}", competition_index, competition.name);
         Ok(())
     }
 
@@ -128,6 +150,7 @@ pub mod competition_engine {
         competition.updated_at = current_time;
 
         emit!(PackRegistered {
+            header: EventHeader::now()?,
             competition: competition.key(),
             pack: pack.key(),
             pack_name: pack.name.clone(),
@@ -164,18 +187,19 @@ pub mod competition_engine {
         competition.updated_at = current_time;
 
         // Initialize leaderboard
-        for participant in &competition.participants {
-            let leaderboard_entry = LeaderboardEntry {
+        let entries: Vec<LeaderboardEntry> = competition.participants.iter()
+            .map(|participant| LeaderboardEntry {
                 pack: participant.pack,
                 pack_name: participant.pack_name.clone(),
                 score: 0,
                 rank: 0,
                 change: 0,
-            };
-            competition.leaderboard.push(leaderboard_entry);
-        }
+            })
+            .collect();
+        competition.leaderboard.extend(entries);
 
         emit!(CompetitionStarted {
+            header: EventHeader::now()?,
             competition: competition.key(),
             participants: competition.current_participants,
             timestamp: current_time,
@@ -213,6 +237,7 @@ pub mod competition_engine {
             .ok_or(CompetitionError::PackNotInCompetition)?;
 
         // Update participant stats
+        let competition_type = competition.competition_type.clone();
         let participant = &mut competition.participants[participant_index];
         participant.score = (participant.score as i64 + score_delta).max(0) as u64;
         participant.trades_count += 1;
@@ -221,7 +246,7 @@ pub mod competition_engine {
         participant.social_score += social_score_delta;
 
         // Calculate composite score based on competition type
-        let composite_score = match competition.competition_type {
+        let composite_score = match competition_type {
             CompetitionType::TradingVolume => participant.volume,
             CompetitionType::ProfitPercentage => {
                 if participant.volume > 0 {
@@ -254,8 +279,10 @@ pub mod competition_engine {
         update_leaderboard(competition)?;
 
         competition.updated_at = current_time;
+        let participant = &competition.participants[participant_index];
 
         emit!(PackScoreUpdated {
+            header: EventHeader::now()?,
             competition: competition.key(),
             pack,
             new_score: participant.score,
@@ -291,7 +318,7 @@ pub mod competition_engine {
         update_leaderboard(competition)?;
 
         // Distribute prizes (simplified - top 3 get rewards)
-        let prize_distribution = vec![
+        let prize_distribution = [
             (0.5, "1st Place"), // 50% to winner
             (0.3, "2nd Place"), // 30% to second
             (0.2, "3rd Place"), // 20% to third
@@ -303,6 +330,7 @@ pub mod competition_engine {
                 let winner = &competition.leaderboard[i];
                 
                 emit!(PrizeAwarded {
+                    header: EventHeader::now()?,
                     competition: competition.key(),
                     pack: winner.pack,
                     pack_name: winner.pack_name.clone(),
@@ -314,6 +342,7 @@ pub mod competition_engine {
         }
 
         emit!(CompetitionEnded {
+            header: EventHeader::now()?,
             competition: competition.key(),
             winner: if !competition.leaderboard.is_empty() {
                 Some(competition.leaderboard[0].pack)
@@ -332,6 +361,7 @@ pub mod competition_engine {
     /// Create tournament (multiple competitions)
     pub fn create_tournament(
         ctx: Context<CreateTournament>,
+        tournament_index: u64,
         name: String,
         description: String,
         tournament_type: TournamentType,
@@ -340,7 +370,7 @@ pub mod competition_engine {
     ) -> Result<()> {
         require!(name.len() <= 100, CompetitionError::NameTooLong);
         require!(description.len() <= 1000, CompetitionError::DescriptionTooLong);
-        require!(rounds >= 1 && rounds <= 10, CompetitionError::InvalidRounds);
+        require!((1..=10).contains(&rounds), CompetitionError::InvalidRounds);
 
         let tournament = &mut ctx.accounts.tournament;
         let current_time = Clock::get()?.unix_timestamp;
@@ -360,7 +390,11 @@ pub mod competition_engine {
         tournament.created_at = current_time;
         tournament.updated_at = current_time;
 
+        let competition_state = &mut ctx.accounts.competition_state;
+        competition_state.total_tournaments = competition_state.total_tournaments.checked_add(1).unwrap();
+
         emit!(TournamentCreated {
+            header: EventHeader::now()?,
             tournament: tournament.key(),
             name: tournament.name.clone(),
             tournament_type: tournament.tournament_type.clone(),
@@ -369,7 +403,7 @@ pub mod competition_engine {
             timestamp: current_time,
         });
 
-        msg!("Tournament created: {}", tournament.name);
+        msg!("Tournament {} created: {}", tournament_index, tournament.name);
         Ok(())
     }
 
@@ -397,6 +431,7 @@ pub mod competition_engine {
         }
 
         emit!(TournamentRoundAdvanced {
+            header: EventHeader::now()?,
             tournament: tournament.key(),
             round: tournament.current_round,
             completed: tournament.status == TournamentStatus::Completed,
@@ -406,22 +441,49 @@ pub mod competition_engine {
         msg!("Tournament advanced to round {}", tournament.current_round);
         Ok(())
     }
+
+    /// Upgrade a v1 Competition account to the current layout (permissionless)
+    pub fn migrate_competition(ctx: Context<MigrateCompetition>) -> Result<()> {
+        let info = ctx.accounts.competition.to_account_info();
+        let legacy: CompetitionV1 = read_legacy::<Competition, _>(&info, CompetitionV1::INIT_SPACE)?;
+        let now = Clock::get()?.unix_timestamp;
+        let competition = Competition::from_v1(legacy, now);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + Competition::INIT_SPACE,
+            &competition,
+        )?;
+
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: Competition::VERSION,
+            timestamp: now,
+        });
+
+        msg!("Competition {} migrated to v{}", competition.name, Competition::VERSION);
+        Ok(())
+    }
 }
 
 // Helper function to update leaderboard
 fn update_leaderboard(competition: &mut Competition) -> Result<()> {
     // Sort participants by score (descending)
-    let mut sorted_participants: Vec<_> = competition.participants.iter().enumerate().collect();
-    sorted_participants.sort_by(|a, b| b.1.score.cmp(&a.1.score));
+    let mut sorted_participants: Vec<usize> = (0..competition.participants.len()).collect();
+    sorted_participants.sort_by(|a, b| competition.participants[*b].score.cmp(&competition.participants[*a].score));
 
     // Update ranks and leaderboard
     competition.leaderboard.clear();
-    for (new_rank, (original_index, participant)) in sorted_participants.iter().enumerate() {
-        let old_rank = competition.participants[*original_index].rank;
+    for (new_rank, original_index) in sorted_participants.into_iter().enumerate() {
+        let participant = &mut competition.participants[original_index];
+        let old_rank = participant.rank;
         let rank_change = if old_rank == 0 { 0 } else { old_rank as i32 - (new_rank + 1) as i32 };
 
         // Update participant rank
-        competition.participants[*original_index].rank = (new_rank + 1) as u32;
+        participant.rank = (new_rank + 1) as u32;
 
         // Add to leaderboard
         let leaderboard_entry = LeaderboardEntry {
@@ -439,16 +501,59 @@ fn update_leaderboard(competition: &mut Competition) -> Result<()> {
 
 // Account structures
 #[derive(Accounts)]
+pub struct InitializeCompetitionState<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CompetitionState::INIT_SPACE,
+        seeds = [b"competition_state"],
+        bump
+    )]
+    pub competition_state: Account<'info, CompetitionState>,
+    
+    // Only the protocol admin may create the singleton, so it cannot be front-run at deploy
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        has_one = authority @ CompetitionError::Unauthorized
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(competition_index: u64)]
 pub struct CreateCompetition<'info> {
     #[account(
         init,
         payer = authority,
         space = 8 + Competition::INIT_SPACE,
-        seeds = [b"competition", authority.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"competition".as_ref(), &competition_index.to_le_bytes()],
         bump
     )]
     pub competition: Account<'info, Competition>,
     
+    #[account(
+        mut,
+        seeds = [b"competition_state"],
+        bump = competition_state.bump,
+        constraint = competition_state.total_competitions == competition_index @ CompetitionError::InvalidCounter
+    )]
+    pub competition_state: Account<'info, CompetitionState>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ CompetitionError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -468,6 +573,14 @@ pub struct RegisterForCompetition<'info> {
     #[account(mut)]
     pub competition_treasury: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ CompetitionError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub pack_leader: Signer<'info>,
     
@@ -482,6 +595,14 @@ pub struct StartCompetition<'info> {
     )]
     pub competition: Account<'info, Competition>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ CompetitionError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     pub authority: Signer<'info>,
 }
 
@@ -490,6 +611,14 @@ pub struct UpdatePackScore<'info> {
     #[account(mut)]
     pub competition: Account<'info, Competition>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ CompetitionError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     pub score_oracle: Signer<'info>, // Authorized score updater
 }
 
@@ -501,20 +630,45 @@ pub struct EndCompetition<'info> {
     )]
     pub competition: Account<'info, Competition>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ CompetitionError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
+#[instruction(tournament_index: u64)]
 pub struct CreateTournament<'info> {
     #[account(
         init,
         payer = authority,
         space = 8 + Tournament::INIT_SPACE,
-        seeds = [b"tournament", authority.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"tournament".as_ref(), &tournament_index.to_le_bytes()],
         bump
     )]
     pub tournament: Account<'info, Tournament>,
     
+    #[account(
+        mut,
+        seeds = [b"competition_state"],
+        bump = competition_state.bump,
+        constraint = competition_state.total_tournaments == tournament_index @ CompetitionError::InvalidCounter
+    )]
+    pub competition_state: Account<'info, CompetitionState>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ CompetitionError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -529,12 +683,46 @@ pub struct AdvanceTournamentRound<'info> {
     )]
     pub tournament: Account<'info, Tournament>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ CompetitionError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateCompetition<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(mut, owner = crate::ID)]
+    pub competition: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 // Data structures
+#[account]
+pub struct CompetitionState {
+    pub authority: Pubkey,
+    pub total_competitions: u64,
+    pub total_tournaments: u64,
+    pub bump: u8,
+    pub created_at: i64,
+}
+
+impl CompetitionState {
+    pub const INIT_SPACE: usize = 32 + 8 + 8 + 1 + 8;
+}
+
 #[account]
 pub struct Competition {
+    pub version: u8,
     pub authority: Pubkey,
     pub name: String,
     pub description: String,
@@ -554,8 +742,31 @@ pub struct Competition {
 }
 
 impl Competition {
-    pub const INIT_SPACE: usize = 32 + (4 + 100) + (4 + 1000) + 1 + 1 + 8 + 8 + 4 + 4 + 
+    pub const VERSION: u8 = 2;
+    pub const INIT_SPACE: usize = 1 + 32 + (4 + 100) + (4 + 1000) + 1 + 1 + 8 + 8 + 4 + 4 + 
         (4 + 50 * CompetitionParticipant::SPACE) + (4 + 50 * LeaderboardEntry::SPACE) + 8 + 8 + 8 + 8 + 8;
+
+    pub fn from_v1(v1: CompetitionV1, now: i64) -> Self {
+        Self {
+            version: Self::VERSION,
+            authority: v1.authority,
+            name: v1.name,
+            description: v1.description,
+            competition_type: v1.competition_type,
+            status: v1.status,
+            entry_fee: v1.entry_fee,
+            prize_pool: v1.prize_pool,
+            max_participants: v1.max_participants,
+            current_participants: v1.current_participants,
+            participants: v1.participants,
+            leaderboard: v1.leaderboard,
+            start_time: v1.start_time,
+            end_time: v1.end_time,
+            registration_deadline: v1.registration_deadline,
+            created_at: v1.created_at,
+            updated_at: now,
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -625,8 +836,34 @@ impl TournamentWinner {
     pub const SPACE: usize = 1 + 32 + (4 + 100) + 8;
 }
 
+// Legacy account layouts, kept so migrate_competition can decode deployed accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompetitionV1 {
+    pub authority: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub competition_type: CompetitionType,
+    pub status: CompetitionStatus,
+    pub entry_fee: u64,
+    pub prize_pool: u64,
+    pub max_participants: u32,
+    pub current_participants: u32,
+    pub participants: Vec<CompetitionParticipant>,
+    pub leaderboard: Vec<LeaderboardEntry>,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub registration_deadline: i64,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl CompetitionV1 {
+    pub const INIT_SPACE: usize = 32 + (4 + 100) + (4 + 1000) + 1 + 1 + 8 + 8 + 4 + 4 + 
+        (4 + 50 * CompetitionParticipant::SPACE) + (4 + 50 * LeaderboardEntry::SPACE) + 8 + 8 + 8 + 8 + 8;
+}
+
 // Enums
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum CompetitionType {
     TradingVolume,
     ProfitPercentage,
@@ -662,6 +899,7 @@ pub enum TournamentStatus {
 // Events
 #[event]
 pub struct CompetitionCreated {
+    pub header: EventHeader,
     pub competition: Pubkey,
     pub name: String,
     pub competition_type: CompetitionType,
@@ -675,6 +913,7 @@ pub struct CompetitionCreated {
 
 #[event]
 pub struct PackRegistered {
+    pub header: EventHeader,
     pub competition: Pubkey,
     pub pack: Pubkey,
     pub pack_name: String,
@@ -685,6 +924,7 @@ pub struct PackRegistered {
 
 #[event]
 pub struct CompetitionStarted {
+    pub header: EventHeader,
     pub competition: Pubkey,
     pub participants: u32,
     pub timestamp: i64,
@@ -692,6 +932,7 @@ pub struct CompetitionStarted {
 
 #[event]
 pub struct PackScoreUpdated {
+    pub header: EventHeader,
     pub competition: Pubkey,
     pub pack: Pubkey,
     pub new_score: u64,
@@ -703,6 +944,7 @@ pub struct PackScoreUpdated {
 
 #[event]
 pub struct CompetitionEnded {
+    pub header: EventHeader,
     pub competition: Pubkey,
     pub winner: Option<Pubkey>,
     pub total_participants: u32,
@@ -712,6 +954,7 @@ pub struct CompetitionEnded {
 
 #[event]
 pub struct PrizeAwarded {
+    pub header: EventHeader,
     pub competition: Pubkey,
     pub pack: Pubkey,
     pub pack_name: String,
@@ -722,6 +965,7 @@ pub struct PrizeAwarded {
 
 #[event]
 pub struct TournamentCreated {
+    pub header: EventHeader,
     pub tournament: Pubkey,
     pub name: String,
     pub tournament_type: TournamentType,
@@ -732,6 +976,7 @@ pub struct TournamentCreated {
 
 #[event]
 pub struct TournamentRoundAdvanced {
+    pub header: EventHeader,
     pub tournament: Pubkey,
     pub round: u8,
     pub completed: bool,
@@ -781,4 +1026,8 @@ pub enum CompetitionError {
     TournamentCompleted,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Alpha Pack protocol is paused")]
+    ProtocolPaused,
+    #[msg("Counter does not match on-chain state")]
+    InvalidCounter,
 }
@@ -1,6 +1,12 @@
+// Anchor handlers take one argument per instruction field and return its large Error type
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, Burn};
-use alpha_pack_core::{Pack, User, Trade, TradeType};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use alpha_pack_core::{
+    GlobalState, User, Trade, TradeType, TradeVerification, AdminConfig, AccountMigrated, EventHeader,
+    read_legacy, write_migrated,
+};
 
 declare_id!("CrossChainBridge111111111111111111111111111");
 
@@ -22,6 +28,7 @@ pub mod cross_chain_bridge {
         require!(!supported_chains.is_empty(), BridgeError::NoChainsSupported);
 
         let bridge = &mut ctx.accounts.bridge;
+        bridge.version = CrossChainBridge::VERSION;
         bridge.authority = ctx.accounts.authority.key();
         bridge.supported_chains = supported_chains;
         bridge.bridge_fee_bps = bridge_fee_bps;
@@ -33,8 +40,10 @@ pub mod cross_chain_bridge {
         bridge.is_active = true;
         bridge.created_at = Clock::get()?.unix_timestamp;
         bridge.updated_at = Clock::get()?.unix_timestamp;
+        bridge.admin = AdminConfig::default();
 
         emit!(BridgeInitialized {
+            header: EventHeader::now()?,
             bridge: bridge.key(),
             authority: bridge.authority,
             supported_chains: bridge.supported_chains.len() as u8,
@@ -49,6 +58,7 @@ pub mod cross_chain_bridge {
     /// Initiate cross-chain transfer
     pub fn initiate_transfer(
         ctx: Context<InitiateTransfer>,
+        transfer_index: u64,
         target_chain: TargetChain,
         target_address: String,
         amount: u64,
@@ -63,8 +73,9 @@ pub mod cross_chain_bridge {
         require!(token_symbol.len() <= 20, BridgeError::SymbolTooLong);
 
         // Validate target chain is supported
+        let chain_id = target_chain.clone() as u16;
         let chain_supported = bridge.supported_chains.iter()
-            .any(|chain| chain.chain_id == target_chain as u16);
+            .any(|chain| chain.chain_id == chain_id);
         require!(chain_supported, BridgeError::ChainNotSupported);
 
         let transfer = &mut ctx.accounts.transfer;
@@ -84,7 +95,7 @@ pub mod cross_chain_bridge {
         transfer.bridge_fee = bridge_fee;
         transfer.transfer_amount = transfer_amount;
         transfer.status = TransferStatus::Initiated;
-        transfer.nonce = generate_nonce(current_time, ctx.accounts.initiator.key());
+        transfer.nonce = transfer_index;
         transfer.initiated_at = current_time;
         transfer.completed_at = None;
         transfer.tx_hash = None;
@@ -107,9 +118,10 @@ pub mod cross_chain_bridge {
         bridge.updated_at = current_time;
 
         emit!(TransferInitiated {
+            header: EventHeader::now()?,
             transfer: transfer.key(),
             initiator: transfer.initiator,
-            target_chain: transfer.target_chain,
+            target_chain: transfer.target_chain.clone(),
             target_address: transfer.target_address.clone(),
             amount,
             bridge_fee,
@@ -147,23 +159,26 @@ pub mod cross_chain_bridge {
             trade_record.user = transfer.initiator;
             trade_record.pack = transfer.pack;
             trade_record.trade_type = TradeType::Arbitrage; // Cross-chain transfers are arbitrage-like
-            trade_record.from_token = "SOL".to_string();
-            trade_record.to_token = transfer.token_symbol.clone();
+            trade_record.from_mint = ctx.accounts.user_token_account.mint;
+            trade_record.to_mint = ctx.accounts.user_token_account.mint;
             trade_record.from_amount = transfer.amount;
             trade_record.to_amount = transfer.transfer_amount;
             trade_record.fee = transfer.bridge_fee;
+            // Bridge transfers move a single asset and are not priced
+            trade_record.from_value = 0;
+            trade_record.to_value = 0;
+            trade_record.fee_value = 0;
             trade_record.profit_loss = 0; // Neutral for bridge transfers
             trade_record.signature = tx_hash.clone();
+            trade_record.verification = TradeVerification::Unverified;
             trade_record.timestamp = current_time;
 
         } else {
             transfer.status = TransferStatus::Failed;
             
             // Refund tokens to user
-            let bridge_seeds = &[
-                b"bridge",
-                &[ctx.bumps.bridge],
-            ];
+            let bump = *ctx.bumps.get("bridge").unwrap();
+            let bridge_seeds = &[b"bridge".as_ref(), &[bump]];
             let signer = &[&bridge_seeds[..]];
 
             let cpi_accounts = Transfer {
@@ -179,6 +194,7 @@ pub mod cross_chain_bridge {
         transfer.completed_at = Some(current_time);
 
         emit!(TransferCompleted {
+            header: EventHeader::now()?,
             transfer: transfer.key(),
             success,
             tx_hash: transfer.tx_hash.clone(),
@@ -216,10 +232,8 @@ pub mod cross_chain_bridge {
         incoming_transfer.created_at = current_time;
 
         // Mint tokens to recipient (simplified - in production would use proper token mapping)
-        let bridge_seeds = &[
-            b"bridge",
-            &[ctx.bumps.bridge],
-        ];
+        let bump = *ctx.bumps.get("bridge").unwrap();
+        let bridge_seeds = &[b"bridge".as_ref(), &[bump]];
         let signer = &[&bridge_seeds[..]];
 
         let cpi_accounts = Transfer {
@@ -235,8 +249,9 @@ pub mod cross_chain_bridge {
         incoming_transfer.processed_at = Some(current_time);
 
         emit!(IncomingTransferProcessed {
+            header: EventHeader::now()?,
             transfer: incoming_transfer.key(),
-            source_chain: incoming_transfer.source_chain,
+            source_chain: incoming_transfer.source_chain.clone(),
             recipient,
             amount,
             nonce,
@@ -253,6 +268,7 @@ pub mod cross_chain_bridge {
         chain_info: ChainInfo,
     ) -> Result<()> {
         let bridge = &mut ctx.accounts.bridge;
+        bridge.admin.check_approval(&bridge.authority, ctx.remaining_accounts)?;
         
         // Check if chain already exists
         let chain_exists = bridge.supported_chains.iter()
@@ -263,9 +279,10 @@ pub mod cross_chain_bridge {
         bridge.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(ChainAdded {
+            header: EventHeader::now()?,
             bridge: bridge.key(),
             chain_id: chain_info.chain_id,
-            chain_name: chain_info.name,
+            chain_name: chain_info.name.clone(),
             timestamp: bridge.updated_at,
         });
 
@@ -282,6 +299,7 @@ pub mod cross_chain_bridge {
         is_active: Option<bool>,
     ) -> Result<()> {
         let bridge = &mut ctx.accounts.bridge;
+        bridge.admin.check_approval(&bridge.authority, ctx.remaining_accounts)?;
 
         if let Some(fee) = bridge_fee_bps {
             require!(fee <= 1000, BridgeError::InvalidFee);
@@ -305,6 +323,7 @@ pub mod cross_chain_bridge {
         bridge.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(BridgeSettingsUpdated {
+            header: EventHeader::now()?,
             bridge: bridge.key(),
             timestamp: bridge.updated_at,
         });
@@ -316,10 +335,12 @@ pub mod cross_chain_bridge {
     /// Emergency pause bridge
     pub fn emergency_pause(ctx: Context<EmergencyPause>) -> Result<()> {
         let bridge = &mut ctx.accounts.bridge;
+        bridge.admin.check_approval(&bridge.authority, ctx.remaining_accounts)?;
         bridge.is_active = false;
         bridge.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(BridgeEmergencyPaused {
+            header: EventHeader::now()?,
             bridge: bridge.key(),
             authority: ctx.accounts.authority.key(),
             timestamp: bridge.updated_at,
@@ -328,17 +349,71 @@ pub mod cross_chain_bridge {
         msg!("Bridge emergency paused");
         Ok(())
     }
-}
 
-// Helper function to generate unique nonce
-fn generate_nonce(timestamp: i64, initiator: Pubkey) -> u64 {
-    let mut hasher = std::collections::hash_map::DefaultHasher::new();
-    use std::hash::{Hash, Hasher};
-    
-    timestamp.hash(&mut hasher);
-    initiator.hash(&mut hasher);
-    
-    hasher.finish()
+    /// Propose a new bridge authority; `None` cancels a pending proposal
+    pub fn propose_admin_authority(ctx: Context<ProposeAdminAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+        let bridge = &mut ctx.accounts.bridge;
+        let (account, authority) = (bridge.key(), bridge.authority);
+        let now = Clock::get()?.unix_timestamp;
+        bridge.admin.propose(account, authority, ctx.remaining_accounts, new_authority, now)?;
+        bridge.updated_at = now;
+
+        msg!("Bridge authority transfer proposed");
+        Ok(())
+    }
+
+    /// Accept a proposed bridge authority transfer (signed by the new authority)
+    pub fn accept_admin_authority(ctx: Context<AcceptAdminAuthority>) -> Result<()> {
+        let bridge = &mut ctx.accounts.bridge;
+        let (account, authority) = (bridge.key(), bridge.authority);
+        let now = Clock::get()?.unix_timestamp;
+        bridge.authority = bridge.admin.transfer(account, authority, ctx.accounts.new_authority.key(), now)?;
+        bridge.updated_at = now;
+
+        msg!("Bridge authority transferred to {}", bridge.authority);
+        Ok(())
+    }
+
+    /// Set the M-of-N approvers required for bridge admin actions; an empty set disables them
+    pub fn configure_admin_approvals(
+        ctx: Context<ConfigureAdminApprovals>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let bridge = &mut ctx.accounts.bridge;
+        let (account, authority) = (bridge.key(), bridge.authority);
+        let now = Clock::get()?.unix_timestamp;
+        bridge.admin.configure(account, authority, ctx.remaining_accounts, approvers, threshold, now)?;
+        bridge.updated_at = now;
+
+        msg!("Bridge admin approvals set to {}-of-{}", threshold, bridge.admin.approvers.len());
+        Ok(())
+    }
+
+    /// Upgrade the v1 bridge account to the current layout (permissionless)
+    pub fn migrate_bridge(ctx: Context<MigrateBridge>) -> Result<()> {
+        let info = ctx.accounts.bridge.to_account_info();
+        let legacy: CrossChainBridgeV1 = read_legacy::<CrossChainBridge, _>(&info, CrossChainBridgeV1::INIT_SPACE)?;
+        let bridge = CrossChainBridge::from_v1(legacy);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + CrossChainBridge::INIT_SPACE,
+            &bridge,
+        )?;
+
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: CrossChainBridge::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Bridge migrated to v{}", CrossChainBridge::VERSION);
+        Ok(())
+    }
 }
 
 // Account structures
@@ -360,17 +435,23 @@ pub struct InitializeBridge<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(transfer_index: u64)]
 pub struct InitiateTransfer<'info> {
     #[account(
         init,
         payer = initiator,
         space = 8 + CrossChainTransfer::INIT_SPACE,
-        seeds = [b"transfer", initiator.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"transfer", &transfer_index.to_le_bytes()],
         bump
     )]
     pub transfer: Account<'info, CrossChainTransfer>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"bridge"],
+        bump,
+        constraint = bridge.total_transfers == transfer_index @ BridgeError::InvalidCounter
+    )]
     pub bridge: Account<'info, CrossChainBridge>,
     
     pub user: Account<'info, User>,
@@ -381,6 +462,14 @@ pub struct InitiateTransfer<'info> {
     #[account(mut)]
     pub bridge_vault: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ BridgeError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub initiator: Signer<'info>,
     
@@ -404,7 +493,7 @@ pub struct CompleteTransfer<'info> {
         init,
         payer = relayer,
         space = 8 + Trade::INIT_SPACE,
-        seeds = [b"trade", transfer.initiator.as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"trade", transfer.key().as_ref()],
         bump
     )]
     pub trade_record: Account<'info, Trade>,
@@ -415,6 +504,14 @@ pub struct CompleteTransfer<'info> {
     #[account(mut)]
     pub bridge_vault: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ BridgeError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub relayer: Signer<'info>,
     
@@ -423,12 +520,20 @@ pub struct CompleteTransfer<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(
+    source_chain: SourceChain,
+    source_tx_hash: String,
+    recipient: Pubkey,
+    amount: u64,
+    token_symbol: String,
+    nonce: u64
+)]
 pub struct ProcessIncomingTransfer<'info> {
     #[account(
         init,
         payer = relayer,
         space = 8 + IncomingTransfer::INIT_SPACE,
-        seeds = [b"incoming_transfer", &nonce.to_le_bytes()],
+        seeds = [b"incoming_transfer".as_ref(), &nonce.to_le_bytes()],
         bump
     )]
     pub incoming_transfer: Account<'info, IncomingTransfer>,
@@ -446,6 +551,14 @@ pub struct ProcessIncomingTransfer<'info> {
     #[account(mut)]
     pub bridge_vault: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ BridgeError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub relayer: Signer<'info>,
     
@@ -492,9 +605,65 @@ pub struct EmergencyPause<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct ProposeAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"bridge"],
+        bump,
+        has_one = authority
+    )]
+    pub bridge: Account<'info, CrossChainBridge>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"bridge"],
+        bump
+    )]
+    pub bridge: Account<'info, CrossChainBridge>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureAdminApprovals<'info> {
+    #[account(
+        mut,
+        seeds = [b"bridge"],
+        bump,
+        has_one = authority
+    )]
+    pub bridge: Account<'info, CrossChainBridge>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateBridge<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(
+        mut,
+        seeds = [b"bridge"],
+        bump,
+        owner = crate::ID
+    )]
+    pub bridge: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 // Data structures
 #[account]
 pub struct CrossChainBridge {
+    pub version: u8,
     pub authority: Pubkey,
     pub supported_chains: Vec<ChainInfo>,
     pub bridge_fee_bps: u16,
@@ -506,10 +675,30 @@ pub struct CrossChainBridge {
     pub is_active: bool,
     pub created_at: i64,
     pub updated_at: i64,
+    pub admin: AdminConfig,
 }
 
 impl CrossChainBridge {
-    pub const INIT_SPACE: usize = 32 + (4 + 10 * ChainInfo::SPACE) + 2 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+    pub const VERSION: u8 = 2;
+    pub const INIT_SPACE: usize = 1 + CrossChainBridgeV1::INIT_SPACE + AdminConfig::SPACE;
+
+    pub fn from_v1(v1: CrossChainBridgeV1) -> Self {
+        Self {
+            version: Self::VERSION,
+            authority: v1.authority,
+            supported_chains: v1.supported_chains,
+            bridge_fee_bps: v1.bridge_fee_bps,
+            min_transfer_amount: v1.min_transfer_amount,
+            max_transfer_amount: v1.max_transfer_amount,
+            total_volume: v1.total_volume,
+            total_transfers: v1.total_transfers,
+            total_fees_collected: v1.total_fees_collected,
+            is_active: v1.is_active,
+            created_at: v1.created_at,
+            updated_at: v1.updated_at,
+            admin: AdminConfig::default(),
+        }
+    }
 }
 
 #[account]
@@ -563,8 +752,28 @@ impl ChainInfo {
     pub const SPACE: usize = 2 + (4 + 50) + (4 + 200) + 1;
 }
 
+// Legacy account layouts, kept so migrate_bridge can decode deployed accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CrossChainBridgeV1 {
+    pub authority: Pubkey,
+    pub supported_chains: Vec<ChainInfo>,
+    pub bridge_fee_bps: u16,
+    pub min_transfer_amount: u64,
+    pub max_transfer_amount: u64,
+    pub total_volume: u64,
+    pub total_transfers: u64,
+    pub total_fees_collected: u64,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl CrossChainBridgeV1 {
+    pub const INIT_SPACE: usize = 32 + (4 + 10 * ChainInfo::SPACE) + 2 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+}
+
 // Enums
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum SourceChain {
     Solana,
     Ethereum,
@@ -574,7 +783,7 @@ pub enum SourceChain {
     BSC,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum TargetChain {
     Ethereum = 1,
     Base = 8453,
@@ -601,6 +810,7 @@ pub enum IncomingTransferStatus {
 // Events
 #[event]
 pub struct BridgeInitialized {
+    pub header: EventHeader,
     pub bridge: Pubkey,
     pub authority: Pubkey,
     pub supported_chains: u8,
@@ -610,6 +820,7 @@ pub struct BridgeInitialized {
 
 #[event]
 pub struct TransferInitiated {
+    pub header: EventHeader,
     pub transfer: Pubkey,
     pub initiator: Pubkey,
     pub target_chain: TargetChain,
@@ -622,6 +833,7 @@ pub struct TransferInitiated {
 
 #[event]
 pub struct TransferCompleted {
+    pub header: EventHeader,
     pub transfer: Pubkey,
     pub success: bool,
     pub tx_hash: Option<String>,
@@ -630,6 +842,7 @@ pub struct TransferCompleted {
 
 #[event]
 pub struct IncomingTransferProcessed {
+    pub header: EventHeader,
     pub transfer: Pubkey,
     pub source_chain: SourceChain,
     pub recipient: Pubkey,
@@ -640,6 +853,7 @@ pub struct IncomingTransferProcessed {
 
 #[event]
 pub struct ChainAdded {
+    pub header: EventHeader,
     pub bridge: Pubkey,
     pub chain_id: u16,
     pub chain_name: String,
@@ -648,12 +862,14 @@ pub struct ChainAdded {
 
 #[event]
 pub struct BridgeSettingsUpdated {
+    pub header: EventHeader,
     pub bridge: Pubkey,
     pub timestamp: i64,
 }
 
 #[event]
 pub struct BridgeEmergencyPaused {
+    pub header: EventHeader,
     pub bridge: Pubkey,
     pub authority: Pubkey,
     pub timestamp: i64,
@@ -690,4 +906,8 @@ pub enum BridgeError {
     ChainAlreadySupported,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Alpha Pack protocol is paused")]
+    ProtocolPaused,
+    #[msg("Counter does not match on-chain state")]
+    InvalidCounter,
 }
@@ -1,7 +1,11 @@
+// Anchor handlers take one argument per instruction field and return its large Error type
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer, MintTo};
+use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
 use anchor_spl::associated_token::AssociatedToken;
-use alpha_pack_core::{Pack, User, PackStatus, StrategyFocus, RiskLevel};
+use alpha_pack_core::{GlobalState, Pack, User, StrategyFocus, RiskLevel, AccountMigrated, EventHeader, SessionKey, authorize_signer, read_legacy, write_migrated};
+use alpha_pack_core::program::AlphaPackCore;
 
 declare_id!("PackManager1111111111111111111111111111111");
 
@@ -9,34 +13,45 @@ declare_id!("PackManager1111111111111111111111111111111");
 pub mod pack_manager {
     use super::*;
 
+    /// Initialize the pack manager global state (protocol admin only)
+    pub fn initialize_manager_state(ctx: Context<InitializeManagerState>) -> Result<()> {
+        let manager_state = &mut ctx.accounts.manager_state;
+        manager_state.authority = ctx.accounts.authority.key();
+        manager_state.total_proposals = 0;
+        manager_state.bump = *ctx.bumps.get("manager_state").unwrap();
+        manager_state.created_at = Clock::get()?.unix_timestamp;
+
+        msg!("Pack manager state initialized");
+        Ok(())
+    }
+
     /// Initialize pack treasury with multi-token support
-    pub fn initialize_treasury(
-        ctx: Context<InitializeTreasury>,
-        treasury_bump: u8,
-    ) -> Result<()> {
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
         let treasury = &mut ctx.accounts.treasury;
+        treasury.version = PackTreasury::VERSION;
         treasury.pack = ctx.accounts.pack.key();
         treasury.authority = ctx.accounts.pack_leader.key();
         treasury.total_value_usd = 0;
         treasury.token_count = 0;
-        treasury.bump = treasury_bump;
+        treasury.bump = *ctx.bumps.get("treasury").unwrap();
         treasury.created_at = Clock::get()?.unix_timestamp;
         treasury.updated_at = Clock::get()?.unix_timestamp;
+        treasury.total_shares = 0;
 
         msg!("Treasury initialized for pack: {}", ctx.accounts.pack.key());
         Ok(())
     }
 
-    /// Add token to pack treasury
+    /// Add token to pack treasury (pack leader only)
     pub fn add_treasury_token(
         ctx: Context<AddTreasuryToken>,
-        mint: Pubkey,
         allocation_percentage: u8,
     ) -> Result<()> {
         require!(allocation_percentage <= 100, PackManagerError::InvalidAllocation);
         
         let treasury = &mut ctx.accounts.treasury;
         let token_account = &ctx.accounts.token_account;
+        let mint = ctx.accounts.mint.key();
         
         // Check if token already exists
         let existing_token = treasury.tokens.iter().find(|t| t.mint == mint);
@@ -58,6 +73,7 @@ pub mod pack_manager {
         treasury.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(TreasuryTokenAdded {
+            header: EventHeader::now()?,
             treasury: treasury.key(),
             mint,
             allocation_percentage,
@@ -68,19 +84,37 @@ pub mod pack_manager {
         Ok(())
     }
 
-    /// Deposit tokens to pack treasury
+    /// Deposit tokens to pack treasury (members only) and issue shares against them.
+    /// Shares are priced off `total_value_usd`, so every held token needs a fresh price.
     pub fn deposit_to_treasury(
         ctx: Context<DepositToTreasury>,
         amount: u64,
     ) -> Result<()> {
+        let depositor = ctx.accounts.depositor.key();
+        require!(
+            ctx.accounts.pack.members.contains(&depositor),
+            PackManagerError::NotPackMember
+        );
+
         let treasury = &mut ctx.accounts.treasury;
         let mint = ctx.accounts.mint.key();
+        let current_time = Clock::get()?.unix_timestamp;
         
         // Find the token in treasury
         let token_index = treasury.tokens.iter()
             .position(|t| t.mint == mint)
             .ok_or(PackManagerError::TokenNotInTreasury)?;
         
+        // Price the deposit and the shares it buys before any balance moves
+        require!(
+            treasury.tokens[token_index].is_priced(current_time),
+            PackManagerError::StalePrice
+        );
+        treasury.require_fresh_prices(current_time)?;
+        let deposit_value = treasury.tokens[token_index].value_of(amount)?;
+        let shares = treasury.shares_for(deposit_value)?;
+        require!(shares > 0, PackManagerError::DepositTooSmall);
+        
         // Transfer tokens from user to treasury
         let cpi_accounts = Transfer {
             from: ctx.accounts.user_token_account.to_account_info(),
@@ -91,71 +125,134 @@ pub mod pack_manager {
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         token::transfer(cpi_ctx, amount)?;
 
-        // Update treasury token balance
-        treasury.tokens[token_index].balance += amount;
-        treasury.updated_at = Clock::get()?.unix_timestamp;
+        // Update treasury token balance and value
+        let treasury_token = &mut treasury.tokens[token_index];
+        treasury_token.balance = treasury_token.balance.checked_add(amount).ok_or(PackManagerError::MathOverflow)?;
+        treasury_token.value_usd = treasury_token.value_of(treasury_token.balance)?;
+        treasury.total_value_usd = treasury.tokens_value()?;
+        treasury.total_shares = treasury.total_shares.checked_add(shares).ok_or(PackManagerError::MathOverflow)?;
+        treasury.updated_at = current_time;
+
+        // First deposit creates the member's share account
+        let member_share = &mut ctx.accounts.member_share;
+        if member_share.owner == Pubkey::default() {
+            member_share.treasury = treasury.key();
+            member_share.owner = depositor;
+            member_share.bump = *ctx.bumps.get("member_share").unwrap();
+            member_share.created_at = current_time;
+        }
+        member_share.shares = member_share.shares.checked_add(shares).ok_or(PackManagerError::MathOverflow)?;
+        member_share.deposited_value_usd = member_share.deposited_value_usd.saturating_add(deposit_value);
+        member_share.updated_at = current_time;
 
         emit!(TreasuryDeposit {
+            header: EventHeader::now()?,
             treasury: treasury.key(),
-            depositor: ctx.accounts.depositor.key(),
+            depositor,
             mint,
             amount,
-            timestamp: treasury.updated_at,
+            timestamp: current_time,
+        });
+
+        emit!(SharesIssued {
+            header: EventHeader::now()?,
+            treasury: treasury.key(),
+            member: depositor,
+            shares,
+            value_usd: deposit_value,
+            total_shares: treasury.total_shares,
+            treasury_value: treasury.total_value_usd,
+            timestamp: current_time,
         });
 
-        msg!("Deposited {} tokens to treasury", amount);
+        msg!("Deposited {} tokens to treasury for {} shares", amount, shares);
         Ok(())
     }
 
-    /// Withdraw tokens from pack treasury (leader only)
-    pub fn withdraw_from_treasury(
-        ctx: Context<WithdrawFromTreasury>,
-        amount: u64,
+    /// Redeem `shares` for a pro-rata cut of every treasury token. Remaining accounts are a
+    /// (treasury token account, owner token account) pair per treasury token, in treasury order.
+    pub fn withdraw_from_treasury<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawFromTreasury<'info>>,
+        shares: u64,
     ) -> Result<()> {
-        let treasury = &mut ctx.accounts.treasury;
-        let mint = ctx.accounts.mint.key();
-        
-        // Find the token in treasury
-        let token_index = treasury.tokens.iter()
-            .position(|t| t.mint == mint)
-            .ok_or(PackManagerError::TokenNotInTreasury)?;
-        
         require!(
-            treasury.tokens[token_index].balance >= amount,
-            PackManagerError::InsufficientBalance
+            shares > 0 && shares <= ctx.accounts.member_share.shares,
+            PackManagerError::InsufficientShares
+        );
+        require!(
+            ctx.remaining_accounts.len() == 2 * ctx.accounts.treasury.tokens.len(),
+            PackManagerError::InvalidTokenAccounts
         );
 
-        // Transfer tokens from treasury to leader
+        let owner = ctx.accounts.owner.key();
+        let current_time = Clock::get()?.unix_timestamp;
+        let treasury_info = ctx.accounts.treasury.to_account_info();
+        let token_program = ctx.accounts.token_program.to_account_info();
+        let treasury = &mut ctx.accounts.treasury;
         let treasury_key = treasury.key();
+        let total_shares = treasury.total_shares;
+        let pack = treasury.pack;
         let seeds = &[
             b"treasury",
-            treasury.pack.as_ref(),
+            pack.as_ref(),
             &[treasury.bump],
         ];
         let signer = &[&seeds[..]];
 
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.treasury_token_account.to_account_info(),
-            to: ctx.accounts.leader_token_account.to_account_info(),
-            authority: treasury.to_account_info(),
-        };
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        token::transfer(cpi_ctx, amount)?;
+        for (treasury_token, pair) in treasury.tokens.iter_mut().zip(ctx.remaining_accounts.chunks(2)) {
+            let (vault, destination) = (&pair[0], &pair[1]);
+            require_keys_eq!(vault.key(), treasury_token.token_account, PackManagerError::InvalidTokenAccounts);
+            let destination_account = Account::<TokenAccount>::try_from(destination)?;
+            require!(
+                destination_account.mint == treasury_token.mint && destination_account.owner == owner,
+                PackManagerError::InvalidTokenAccounts
+            );
+
+            let amount = (treasury_token.balance as u128 * shares as u128 / total_shares as u128) as u64;
+            if amount == 0 {
+                continue;
+            }
 
-        // Update treasury token balance
-        treasury.tokens[token_index].balance -= amount;
-        treasury.updated_at = Clock::get()?.unix_timestamp;
+            let cpi_accounts = Transfer {
+                from: vault.clone(),
+                to: destination.clone(),
+                authority: treasury_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer);
+            token::transfer(cpi_ctx, amount)?;
+
+            treasury_token.balance -= amount;
+            treasury_token.value_usd = treasury_token.value_of(treasury_token.balance)?;
+
+            emit!(TreasuryWithdrawal {
+                header: EventHeader::now()?,
+                treasury: treasury_key,
+                recipient: owner,
+                mint: treasury_token.mint,
+                amount,
+                timestamp: current_time,
+            });
+        }
 
-        emit!(TreasuryWithdrawal {
-            treasury: treasury.key(),
-            leader: ctx.accounts.pack_leader.key(),
-            mint,
-            amount,
-            timestamp: treasury.updated_at,
+        treasury.total_value_usd = treasury.tokens_value()?;
+        treasury.total_shares -= shares;
+        treasury.updated_at = current_time;
+
+        let member_share = &mut ctx.accounts.member_share;
+        member_share.shares -= shares;
+        member_share.updated_at = current_time;
+
+        emit!(SharesRedeemed {
+            header: EventHeader::now()?,
+            treasury: treasury_key,
+            member: owner,
+            shares,
+            total_shares: treasury.total_shares,
+            treasury_value: treasury.total_value_usd,
+            timestamp: current_time,
         });
 
-        msg!("Withdrew {} tokens from treasury", amount);
+        msg!("Redeemed {} treasury shares", shares);
         Ok(())
     }
 
@@ -164,12 +261,10 @@ pub mod pack_manager {
         let treasury = &mut ctx.accounts.treasury;
         
         // Calculate total value and target allocations
-        let total_value = treasury.tokens.iter()
-            .map(|t| t.value_usd)
-            .sum::<u64>();
+        let total_value = treasury.tokens_value()?;
 
-        for (i, token) in treasury.tokens.iter_mut().enumerate() {
-            let target_value = (total_value * token.allocation_percentage as u64) / 100;
+        for token in treasury.tokens.iter_mut() {
+            let target_value = (total_value as u128 * token.allocation_percentage as u128 / 100) as u64;
             let current_value = token.value_usd;
             
             if current_value < target_value {
@@ -186,6 +281,7 @@ pub mod pack_manager {
         treasury.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(TreasuryRebalanced {
+            header: EventHeader::now()?,
             treasury: treasury.key(),
             total_value,
             timestamp: treasury.updated_at,
@@ -195,7 +291,7 @@ pub mod pack_manager {
         Ok(())
     }
 
-    /// Update token prices in treasury
+    /// Update token prices in treasury (registered core price oracle only)
     pub fn update_token_prices(
         ctx: Context<UpdateTokenPrices>,
         prices: Vec<TokenPrice>,
@@ -208,19 +304,35 @@ pub mod pack_manager {
                 .find(|t| t.mint == price_update.mint) {
                 
                 token.last_price = price_update.price_usd;
-                token.value_usd = (token.balance * price_update.price_usd) / 1_000_000; // Assuming 6 decimals
+                token.value_usd = token.value_of(token.balance)?;
                 token.price_updated_at = current_time;
             }
         }
 
         // Recalculate total treasury value
-        treasury.total_value_usd = treasury.tokens.iter()
-            .map(|t| t.value_usd)
-            .sum();
+        treasury.total_value_usd = treasury.tokens_value()?;
         
         treasury.updated_at = current_time;
 
+        // Mirror the new value onto the core Pack account
+        let seeds = &[
+            b"treasury",
+            treasury.pack.as_ref(),
+            &[treasury.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = alpha_pack_core::cpi::accounts::SyncTreasuryValue {
+            pack: ctx.accounts.pack.to_account_info(),
+            global_state: ctx.accounts.core_state.to_account_info(),
+            treasury: treasury.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.core_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        alpha_pack_core::cpi::sync_treasury_value(cpi_ctx, treasury.total_value_usd)?;
+
         emit!(TokenPricesUpdated {
+            header: EventHeader::now()?,
             treasury: treasury.key(),
             total_value: treasury.total_value_usd,
             timestamp: current_time,
@@ -230,9 +342,13 @@ pub mod pack_manager {
         Ok(())
     }
 
-    /// Create pack governance proposal
-    pub fn create_proposal(
-        ctx: Context<CreateProposal>,
+    /// Create pack governance proposal, snapshotting each member's voting weight under the pack's
+    /// voting mode. Unless the mode is Equal, remaining accounts are one per pack member, in
+    /// member order: their MemberShare PDA (which need not exist) for deposit-based modes, their
+    /// User account for ReputationWeighted.
+    pub fn create_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateProposal<'info>>,
+        proposal_index: u64,
         title: String,
         description: String,
         proposal_type: ProposalType,
@@ -240,9 +356,44 @@ pub mod pack_manager {
     ) -> Result<()> {
         require!(title.len() <= 100, PackManagerError::TitleTooLong);
         require!(description.len() <= 1000, PackManagerError::DescriptionTooLong);
+        if let ProposalAction::GovernanceChange(change) = ProposalAction::decode(&proposal_type, &execution_data)? {
+            GovernanceConfig::validate(change.quorum_bps, change.threshold_bps, change.voting_period)?;
+        }
+
+        let pack = &ctx.accounts.pack;
+        // Packs that never initialized a config vote under the defaults
+        let config_info = &ctx.accounts.governance_config;
+        let config = if config_info.data_is_empty() {
+            GovernanceConfig::defaults(pack.key())
+        } else {
+            GovernanceConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?
+        };
+        let now = Clock::get()?.unix_timestamp;
+
+        // Members who join later cannot vote on it; members who leave keep their vote.
+        // Members without weight are left out.
+        let treasury = Pubkey::find_program_address(&[b"treasury", pack.key().as_ref()], &crate::ID).0;
+        let mut eligible_voters = Vec::with_capacity(pack.members.len());
+        for (index, member) in pack.members.iter().enumerate() {
+            let weight = match config.voting_mode {
+                VotingMode::Equal => 1,
+                _ => {
+                    let info = ctx.remaining_accounts.get(index).ok_or(PackManagerError::InvalidVoterAccounts)?;
+                    voter_weight(&config.voting_mode, *member, treasury, info)?
+                }
+            };
+            if weight > 0 {
+                eligible_voters.push(EligibleVoter { voter: *member, weight });
+            }
+        }
+        let total_weight = eligible_voters.iter()
+            .try_fold(0u64, |total, v| total.checked_add(v.weight))
+            .ok_or(PackManagerError::MathOverflow)?;
+        require!(total_weight > 0, PackManagerError::NoVotingWeight);
 
         let proposal = &mut ctx.accounts.proposal;
-        proposal.pack = ctx.accounts.pack.key();
+        proposal.version = Proposal::VERSION;
+        proposal.pack = pack.key();
         proposal.proposer = ctx.accounts.proposer.key();
         proposal.title = title;
         proposal.description = description;
@@ -251,11 +402,23 @@ pub mod pack_manager {
         proposal.status = ProposalStatus::Active;
         proposal.votes_for = 0;
         proposal.votes_against = 0;
+        proposal.votes_abstain = 0;
         proposal.total_voters = 0;
-        proposal.created_at = Clock::get()?.unix_timestamp;
-        proposal.voting_ends_at = Clock::get()?.unix_timestamp + 7 * 24 * 60 * 60; // 7 days
+        proposal.eligible_voters = eligible_voters;
+        proposal.total_weight = total_weight;
+        proposal.voting_mode = config.voting_mode;
+        proposal.quorum_bps = config.quorum_bps;
+        proposal.threshold_bps = config.threshold_bps;
+        proposal.created_at = now;
+        proposal.voting_ends_at = now + config.voting_period;
+        proposal.executed_at = None;
+        proposal.executable_at = 0;
+
+        let manager_state = &mut ctx.accounts.manager_state;
+        manager_state.total_proposals = manager_state.total_proposals.checked_add(1).unwrap();
 
         emit!(ProposalCreated {
+            header: EventHeader::now()?,
             proposal: proposal.key(),
             pack: proposal.pack,
             proposer: proposal.proposer,
@@ -264,118 +427,498 @@ pub mod pack_manager {
             timestamp: proposal.created_at,
         });
 
-        msg!("Proposal created: {}", proposal.title);
+        msg!("Proposal {} created: {}", proposal_index, proposal.title);
         Ok(())
     }
 
-    /// Vote on pack governance proposal
+    /// Vote on pack governance proposal. Only members in the proposal's snapshot may vote, and
+    /// only once: the vote receipt PDA cannot be created twice. Under the deposit-based modes a
+    /// vote weighs the lesser of the snapshot and the voter's current shares.
     pub fn vote_on_proposal(
         ctx: Context<VoteOnProposal>,
-        vote: bool, // true = for, false = against
+        choice: VoteChoice,
     ) -> Result<()> {
+        authorize_signer(
+            &ctx.accounts.voter,
+            ctx.accounts.authority.key(),
+            ctx.accounts.session_key.as_mut(),
+            SessionKey::VOTE_ON_PROPOSAL,
+            0,
+        )?;
+
+        let current_time = Clock::get()?.unix_timestamp;
         let proposal = &mut ctx.accounts.proposal;
-        let voter = &ctx.accounts.voter;
-        
+        let voter = ctx.accounts.voter.authority;
+
         require!(
             proposal.status == ProposalStatus::Active,
             PackManagerError::ProposalNotActive
         );
-        
+
         require!(
-            Clock::get()?.unix_timestamp < proposal.voting_ends_at,
+            current_time < proposal.voting_ends_at,
             PackManagerError::VotingPeriodEnded
         );
 
-        // Check if user already voted
-        let existing_vote = proposal.voters.iter()
-            .find(|v| v.voter == voter.key());
-        require!(existing_vote.is_none(), PackManagerError::AlreadyVoted);
-
-        // Add vote
-        let vote_record = Vote {
-            voter: voter.key(),
-            vote,
-            timestamp: Clock::get()?.unix_timestamp,
+        let snapshot_weight = proposal.eligible_voters.iter()
+            .find(|v| v.voter == voter)
+            .map(|v| v.weight)
+            .ok_or(PackManagerError::NotEligibleToVote)?;
+        // Shares are not locked, so shares redeemed since the snapshot no longer count
+        let weight = match proposal.voting_mode {
+            VotingMode::DepositWeighted | VotingMode::Quadratic => {
+                let info = ctx.accounts.member_share.as_ref().ok_or(PackManagerError::InvalidVoterAccounts)?;
+                let treasury = Pubkey::find_program_address(&[b"treasury", proposal.pack.as_ref()], &crate::ID).0;
+                snapshot_weight.min(voter_weight(&proposal.voting_mode, voter, treasury, info)?)
+            }
+            _ => snapshot_weight,
         };
-        
-        proposal.voters.push(vote_record);
-        proposal.total_voters += 1;
-        
-        if vote {
-            proposal.votes_for += 1;
-        } else {
-            proposal.votes_against += 1;
+        require!(weight > 0, PackManagerError::NoVotingWeight);
+        match choice {
+            VoteChoice::For => proposal.votes_for += weight,
+            VoteChoice::Against => proposal.votes_against += weight,
+            VoteChoice::Abstain => proposal.votes_abstain += weight,
         }
+        proposal.total_voters += 1;
 
-        // Check if proposal should be executed (simple majority)
-        let pack = &ctx.accounts.pack;
-        let required_votes = (pack.members.len() / 2) + 1;
-        
-        if proposal.votes_for >= required_votes as u32 {
+        let receipt = &mut ctx.accounts.vote_receipt;
+        receipt.proposal = proposal.key();
+        receipt.voter = voter;
+        receipt.choice = choice;
+        receipt.weight = weight;
+        receipt.bump = *ctx.bumps.get("vote_receipt").unwrap();
+        receipt.timestamp = current_time;
+
+        // Resolve early once the remaining votes can no longer change the outcome
+        if proposal.is_decided_for() {
             proposal.status = ProposalStatus::Passed;
-        } else if proposal.votes_against >= required_votes as u32 {
+            proposal.executable_at = current_time + Proposal::EXECUTION_DELAY;
+        } else if proposal.is_decided_against() {
             proposal.status = ProposalStatus::Rejected;
         }
 
-        emit!(VoteCast {
+        emit!(VoteRecorded {
+            header: EventHeader::now()?,
             proposal: proposal.key(),
-            voter: voter.key(),
-            vote,
+            voter,
+            choice,
+            weight,
             votes_for: proposal.votes_for,
             votes_against: proposal.votes_against,
-            timestamp: Clock::get()?.unix_timestamp,
+            votes_abstain: proposal.votes_abstain,
+            timestamp: current_time,
         });
 
         msg!("Vote cast on proposal: {}", proposal.title);
         Ok(())
     }
 
-    /// Execute passed proposal
+    /// Create the pack's governance config with the default voting rules (permissionless).
+    /// Packs without one vote under the defaults; changing the rules is a GovernanceChange
+    /// proposal, which needs the account to exist.
+    pub fn initialize_governance_config(ctx: Context<InitializeGovernanceConfig>) -> Result<()> {
+        let config = &mut ctx.accounts.governance_config;
+        config.set_inner(GovernanceConfig::defaults(ctx.accounts.pack.key()));
+        config.bump = *ctx.bumps.get("governance_config").unwrap();
+        config.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(GovernanceConfigUpdated {
+            header: EventHeader::now()?,
+            pack: config.pack,
+            quorum_bps: config.quorum_bps,
+            threshold_bps: config.threshold_bps,
+            voting_mode: config.voting_mode,
+            voting_period: config.voting_period,
+            timestamp: config.updated_at,
+        });
+
+        msg!("Governance config created for pack: {}", config.pack);
+        Ok(())
+    }
+
+    /// Execute a passed proposal once its timelock has elapsed and before its execution window
+    /// lapses (permissionless). Treasury
+    /// withdrawals are signed by the treasury PDA; pack changes are CPIs into alpha_pack_core
+    /// signed by the pack's governance PDA; governance changes rewrite the pack's GovernanceConfig.
     pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
-        let proposal = &mut ctx.accounts.proposal;
+        let current_time = Clock::get()?.unix_timestamp;
+        let proposal = &ctx.accounts.proposal;
         
         require!(
             proposal.status == ProposalStatus::Passed,
             PackManagerError::ProposalNotPassed
         );
+        require!(
+            current_time >= proposal.executable_at,
+            PackManagerError::TimelockNotElapsed
+        );
+        require!(
+            current_time < proposal.execution_deadline(),
+            PackManagerError::ExecutionWindowClosed
+        );
 
-        // Execute based on proposal type
-        match proposal.proposal_type {
-            ProposalType::TreasuryWithdrawal => {
-                // Execute treasury withdrawal
-                msg!("Executing treasury withdrawal proposal");
+        let action = ProposalAction::decode(&proposal.proposal_type, &proposal.execution_data)?;
+        let pack_key = ctx.accounts.pack.key();
+        let governance_seeds = &[
+            b"governance",
+            pack_key.as_ref(),
+            &[*ctx.bumps.get("governance").unwrap()],
+        ];
+        let governance_signer = &[&governance_seeds[..]];
+
+        match action {
+            ProposalAction::TreasuryWithdrawal(withdrawal) => {
+                let treasury = ctx.accounts.treasury.as_mut().ok_or(PackManagerError::MissingExecutionAccount)?;
+                let treasury_token_account = ctx.accounts.treasury_token_account.as_ref()
+                    .ok_or(PackManagerError::MissingExecutionAccount)?;
+                let recipient_token_account = ctx.accounts.recipient_token_account.as_ref()
+                    .ok_or(PackManagerError::MissingExecutionAccount)?;
+                let token_program = ctx.accounts.token_program.as_ref()
+                    .ok_or(PackManagerError::MissingExecutionAccount)?;
+
+                let token_index = treasury.tokens.iter()
+                    .position(|t| t.mint == withdrawal.mint)
+                    .ok_or(PackManagerError::TokenNotInTreasury)?;
+                require_keys_eq!(
+                    treasury_token_account.key(),
+                    treasury.tokens[token_index].token_account,
+                    PackManagerError::InvalidTokenAccounts
+                );
+                require!(
+                    recipient_token_account.mint == withdrawal.mint
+                        && recipient_token_account.owner == withdrawal.recipient,
+                    PackManagerError::InvalidTokenAccounts
+                );
+                require!(
+                    treasury.tokens[token_index].balance >= withdrawal.amount,
+                    PackManagerError::InsufficientBalance
+                );
+
+                let seeds = &[
+                    b"treasury",
+                    pack_key.as_ref(),
+                    &[treasury.bump],
+                ];
+                let signer = &[&seeds[..]];
+
+                let cpi_accounts = Transfer {
+                    from: treasury_token_account.to_account_info(),
+                    to: recipient_token_account.to_account_info(),
+                    authority: treasury.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+                token::transfer(cpi_ctx, withdrawal.amount)?;
+
+                // Shares are untouched, so the withdrawal is borne by every holder pro rata
+                let treasury_token = &mut treasury.tokens[token_index];
+                treasury_token.balance -= withdrawal.amount;
+                treasury_token.value_usd = treasury_token.value_of(treasury_token.balance)?;
+                treasury.total_value_usd = treasury.tokens_value()?;
+                treasury.updated_at = current_time;
+
+                emit!(TreasuryWithdrawal {
+                    header: EventHeader::now()?,
+                    treasury: treasury.key(),
+                    recipient: withdrawal.recipient,
+                    mint: withdrawal.mint,
+                    amount: withdrawal.amount,
+                    timestamp: current_time,
+                });
             },
-            ProposalType::StrategyChange => {
-                // Change pack strategy
-                msg!("Executing strategy change proposal");
+            ProposalAction::StrategyChange(change) => {
+                let cpi_accounts = alpha_pack_core::cpi::accounts::SetPackStrategy {
+                    pack: ctx.accounts.pack.to_account_info(),
+                    global_state: ctx.accounts.core_state.to_account_info(),
+                    governance: ctx.accounts.governance.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.core_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, governance_signer);
+                alpha_pack_core::cpi::set_pack_strategy(cpi_ctx, change.strategy_focus, change.risk_level)?;
             },
-            ProposalType::MemberRemoval => {
-                // Remove member from pack
-                msg!("Executing member removal proposal");
+            ProposalAction::MemberRemoval(removal) => {
+                let member = ctx.accounts.target_user.as_ref().ok_or(PackManagerError::MissingExecutionAccount)?;
+                require_keys_eq!(member.authority, removal.member, PackManagerError::InvalidExecutionAccount);
+
+                let cpi_accounts = alpha_pack_core::cpi::accounts::GovernanceRemoveMember {
+                    pack: ctx.accounts.pack.to_account_info(),
+                    member: member.to_account_info(),
+                    global_state: ctx.accounts.core_state.to_account_info(),
+                    governance: ctx.accounts.governance.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.core_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, governance_signer);
+                alpha_pack_core::cpi::governance_remove_member(cpi_ctx)?;
             },
-            ProposalType::LeadershipChange => {
-                // Change pack leadership
-                msg!("Executing leadership change proposal");
+            ProposalAction::LeadershipChange(change) => {
+                let new_leader = ctx.accounts.target_user.as_ref().ok_or(PackManagerError::MissingExecutionAccount)?;
+                require_keys_eq!(new_leader.authority, change.new_leader, PackManagerError::InvalidExecutionAccount);
+
+                let cpi_accounts = alpha_pack_core::cpi::accounts::GovernanceTransferLeadership {
+                    pack: ctx.accounts.pack.to_account_info(),
+                    new_leader: new_leader.to_account_info(),
+                    global_state: ctx.accounts.core_state.to_account_info(),
+                    governance: ctx.accounts.governance.to_account_info(),
+                };
+                let cpi_program = ctx.accounts.core_program.to_account_info();
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, governance_signer);
+                alpha_pack_core::cpi::governance_transfer_leadership(cpi_ctx)?;
+            },
+            ProposalAction::GovernanceChange(change) => {
+                let config = ctx.accounts.governance_config.as_mut().ok_or(PackManagerError::MissingExecutionAccount)?;
+                config.quorum_bps = change.quorum_bps;
+                config.threshold_bps = change.threshold_bps;
+                config.voting_mode = change.voting_mode;
+                config.voting_period = change.voting_period;
+                config.updated_at = current_time;
+
+                emit!(GovernanceConfigUpdated {
+                    header: EventHeader::now()?,
+                    pack: config.pack,
+                    quorum_bps: config.quorum_bps,
+                    threshold_bps: config.threshold_bps,
+                    voting_mode: config.voting_mode,
+                    voting_period: config.voting_period,
+                    timestamp: current_time,
+                });
             },
         }
 
+        let proposal = &mut ctx.accounts.proposal;
         proposal.status = ProposalStatus::Executed;
-        proposal.executed_at = Some(Clock::get()?.unix_timestamp);
+        proposal.executed_at = Some(current_time);
 
         emit!(ProposalExecuted {
+            header: EventHeader::now()?,
             proposal: proposal.key(),
             executor: ctx.accounts.executor.key(),
-            timestamp: Clock::get()?.unix_timestamp,
+            timestamp: current_time,
         });
 
         msg!("Proposal executed: {}", proposal.title);
         Ok(())
     }
+
+    /// Withdraw an active proposal before anyone has voted (proposer or pack leader)
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            proposal.status == ProposalStatus::Active,
+            PackManagerError::ProposalNotActive
+        );
+        require!(proposal.total_voters == 0, PackManagerError::ProposalHasVotes);
+
+        proposal.status = ProposalStatus::Cancelled;
+
+        emit!(ProposalCancelled {
+            header: EventHeader::now()?,
+            proposal: proposal.key(),
+            cancelled_by: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Proposal cancelled: {}", proposal.title);
+        Ok(())
+    }
+
+    /// Resolve a proposal still active when its voting window closed (permissionless): Expired
+    /// without quorum, otherwise Passed or Rejected by the threshold
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            proposal.status == ProposalStatus::Active,
+            PackManagerError::ProposalNotActive
+        );
+        require!(
+            current_time >= proposal.voting_ends_at,
+            PackManagerError::VotingPeriodNotEnded
+        );
+
+        if !proposal.quorum_reached() {
+            proposal.status = ProposalStatus::Expired;
+        } else if proposal.threshold_met(proposal.votes_for, proposal.votes_against) {
+            proposal.status = ProposalStatus::Passed;
+            proposal.executable_at = current_time + Proposal::EXECUTION_DELAY;
+        } else {
+            proposal.status = ProposalStatus::Rejected;
+        }
+
+        emit!(ProposalFinalized {
+            header: EventHeader::now()?,
+            proposal: proposal.key(),
+            status: proposal.status.clone(),
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            votes_abstain: proposal.votes_abstain,
+            timestamp: current_time,
+        });
+
+        msg!("Proposal finalized: {}", proposal.title);
+        Ok(())
+    }
+
+    /// Close a settled proposal and return its rent to the proposer. Passed proposals stay open
+    /// until executed or until their execution window lapses.
+    pub fn close_proposal(ctx: Context<CloseProposal>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(ctx.accounts.proposal.is_closable(now), PackManagerError::ProposalNotSettled);
+
+        emit!(ProposalClosed {
+            header: EventHeader::now()?,
+            proposal: ctx.accounts.proposal.key(),
+            proposer: ctx.accounts.proposer.key(),
+            timestamp: now,
+        });
+
+        msg!("Proposal closed: {}", ctx.accounts.proposal.title);
+        Ok(())
+    }
+
+    /// Close a vote receipt and return its rent to the voter, once its proposal is closed or can
+    /// be. Closed proposals' addresses are never reused, so the receipt is no longer needed to
+    /// block a second vote.
+    pub fn close_vote_receipt(ctx: Context<CloseVoteReceipt>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let info = ctx.accounts.proposal.to_account_info();
+        if !info.data_is_empty() {
+            let proposal: Account<Proposal> = Account::try_from(&info)?;
+            require!(proposal.is_closable(now), PackManagerError::ProposalNotSettled);
+        }
+
+        emit!(VoteReceiptClosed {
+            header: EventHeader::now()?,
+            proposal: info.key(),
+            voter: ctx.accounts.voter.key(),
+            timestamp: now,
+        });
+
+        msg!("Vote receipt closed for proposal: {}", info.key());
+        Ok(())
+    }
+
+    /// Upgrade a v1 PackTreasury account to the current layout (permissionless)
+    pub fn migrate_treasury(ctx: Context<MigrateTreasury>) -> Result<()> {
+        let info = ctx.accounts.treasury.to_account_info();
+        let legacy: PackTreasuryV1 = read_legacy::<PackTreasury, _>(&info, PackTreasuryV1::INIT_SPACE)?;
+        let now = Clock::get()?.unix_timestamp;
+        let treasury = PackTreasury::from_v1(legacy, now);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + PackTreasury::INIT_SPACE,
+            &treasury,
+        )?;
+
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: PackTreasury::VERSION,
+            timestamp: now,
+        });
+
+        msg!("Treasury for pack {} migrated to v{}", treasury.pack, PackTreasury::VERSION);
+        Ok(())
+    }
+
+    /// Upgrade a v1 Proposal account to the current layout (permissionless). The voter snapshot
+    /// is taken from the pack's current members, one vote each.
+    pub fn migrate_proposal(ctx: Context<MigrateProposal>) -> Result<()> {
+        let info = ctx.accounts.proposal.to_account_info();
+        let legacy: ProposalV1 = read_legacy::<Proposal, _>(&info, ProposalV1::INIT_SPACE)?;
+        require_keys_eq!(legacy.pack, ctx.accounts.pack.key(), PackManagerError::PackMismatch);
+        let proposal = Proposal::from_v1(legacy, &ctx.accounts.pack.members);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + Proposal::INIT_SPACE,
+            &proposal,
+        )?;
+
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: Proposal::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Proposal {} migrated to v{}", info.key(), Proposal::VERSION);
+        Ok(())
+    }
+}
+
+// Helper function to read a member's voting weight from the account passed for them: their
+// MemberShare PDA for the deposit-based modes (no account means no deposit), their User for
+// ReputationWeighted
+fn voter_weight(mode: &VotingMode, member: Pubkey, treasury: Pubkey, info: &AccountInfo) -> Result<u64> {
+    match mode {
+        VotingMode::Equal => Ok(1),
+        VotingMode::DepositWeighted | VotingMode::Quadratic => {
+            let (member_share, _) = Pubkey::find_program_address(
+                &[b"member_share", treasury.as_ref(), member.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(info.key(), member_share, PackManagerError::InvalidVoterAccounts);
+            if info.data_is_empty() {
+                return Ok(0);
+            }
+            let shares = MemberShare::try_deserialize(&mut &info.try_borrow_data()?[..])?.shares;
+            Ok(if *mode == VotingMode::Quadratic { integer_sqrt(shares) } else { shares })
+        }
+        VotingMode::ReputationWeighted => {
+            let user: Account<User> = Account::try_from(info)?;
+            require_keys_eq!(user.authority, member, PackManagerError::InvalidVoterAccounts);
+            // Every member keeps at least one vote; win rate adds up to 100 on top of social score
+            Ok(1 + user.social_score.saturating_add(user.win_rate_bps as u64 / 100))
+        }
+    }
+}
+
+// Helper function for the floor of the square root, by Newton's method
+fn integer_sqrt(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = (x + n / x) / 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
 }
 
 // Account structures
 #[derive(Accounts)]
-#[instruction(treasury_bump: u8)]
+pub struct InitializeManagerState<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ManagerState::INIT_SPACE,
+        seeds = [b"manager_state"],
+        bump
+    )]
+    pub manager_state: Account<'info, ManagerState>,
+    
+    // Only the protocol admin may create the singleton, so it cannot be front-run at deploy
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        has_one = authority @ PackManagerError::Unauthorized
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
 pub struct InitializeTreasury<'info> {
     #[account(
         init,
@@ -386,8 +929,17 @@ pub struct InitializeTreasury<'info> {
     )]
     pub treasury: Account<'info, PackTreasury>,
     
+    #[account(constraint = pack.leader == pack_leader.key() @ PackManagerError::Unauthorized)]
     pub pack: Account<'info, Pack>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub pack_leader: Signer<'info>,
     
@@ -396,9 +948,17 @@ pub struct InitializeTreasury<'info> {
 
 #[derive(Accounts)]
 pub struct AddTreasuryToken<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"treasury", pack.key().as_ref()],
+        bump = treasury.bump,
+        has_one = pack
+    )]
     pub treasury: Account<'info, PackTreasury>,
     
+    #[account(constraint = pack.leader == pack_leader.key() @ PackManagerError::Unauthorized)]
+    pub pack: Account<'info, Pack>,
+    
     pub mint: Account<'info, Mint>,
     
     #[account(
@@ -409,6 +969,14 @@ pub struct AddTreasuryToken<'info> {
     )]
     pub token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub pack_leader: Signer<'info>,
     
@@ -419,9 +987,25 @@ pub struct AddTreasuryToken<'info> {
 
 #[derive(Accounts)]
 pub struct DepositToTreasury<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"treasury", pack.key().as_ref()],
+        bump = treasury.bump,
+        has_one = pack
+    )]
     pub treasury: Account<'info, PackTreasury>,
     
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        init_if_needed,
+        payer = depositor,
+        space = 8 + MemberShare::INIT_SPACE,
+        seeds = [b"member_share", treasury.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub member_share: Account<'info, MemberShare>,
+    
     pub mint: Account<'info, Mint>,
     
     #[account(
@@ -438,43 +1022,48 @@ pub struct DepositToTreasury<'info> {
     )]
     pub treasury_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub depositor: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct WithdrawFromTreasury<'info> {
     #[account(
         mut,
-        seeds = [b"treasury", pack.key().as_ref()],
-        bump = treasury.bump,
-        has_one = pack
+        seeds = [b"treasury", treasury.pack.as_ref()],
+        bump = treasury.bump
     )]
     pub treasury: Account<'info, PackTreasury>,
     
-    #[account(has_one = leader)]
-    pub pack: Account<'info, Pack>,
-    
-    pub mint: Account<'info, Mint>,
-    
     #[account(
         mut,
-        associated_token::mint = mint,
-        associated_token::authority = treasury,
+        seeds = [b"member_share", treasury.key().as_ref(), owner.key().as_ref()],
+        bump = member_share.bump,
+        has_one = treasury,
+        has_one = owner
     )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub member_share: Account<'info, MemberShare>,
     
     #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = pack_leader,
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
     )]
-    pub leader_token_account: Account<'info, TokenAccount>,
+    pub core_state: Account<'info, GlobalState>,
     
-    #[account(mut)]
-    pub pack_leader: Signer<'info>,
+    pub owner: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
 }
@@ -486,31 +1075,86 @@ pub struct RebalanceTreasury<'info> {
     
     pub pack: Account<'info, Pack>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateTokenPrices<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"treasury", pack.key().as_ref()],
+        bump = treasury.bump,
+        has_one = pack
+    )]
     pub treasury: Account<'info, PackTreasury>,
     
-    pub price_oracle: Signer<'info>, // Oracle authority
-}
-
-#[derive(Accounts)]
-pub struct CreateProposal<'info> {
-    #[account(
-        init,
+    #[account(mut)]
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    // Prices drive share issuance and the core treasury value, so only the registered oracle may post them
+    #[account(
+        constraint = core_state.price_oracle == Some(price_oracle.key()) @ PackManagerError::InvalidPriceOracle
+    )]
+    pub price_oracle: Signer<'info>,
+    
+    pub core_program: Program<'info, AlphaPackCore>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_index: u64)]
+pub struct CreateProposal<'info> {
+    #[account(
+        init,
         payer = proposer,
         space = 8 + Proposal::INIT_SPACE,
-        seeds = [b"proposal", pack.key().as_ref(), proposer.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"proposal", pack.key().as_ref(), &proposal_index.to_le_bytes()],
         bump
     )]
     pub proposal: Account<'info, Proposal>,
     
+    #[account(constraint = pack.members.contains(&proposer.key()) @ PackManagerError::NotPackMember)]
     pub pack: Account<'info, Pack>,
     
+    #[account(
+        mut,
+        seeds = [b"manager_state"],
+        bump = manager_state.bump,
+        constraint = manager_state.total_proposals == proposal_index @ PackManagerError::InvalidCounter
+    )]
+    pub manager_state: Account<'info, ManagerState>,
+    
+    /// CHECK: the pack's GovernanceConfig PDA; defaults apply while it has not been created
+    #[account(
+        seeds = [b"governance_config", pack.key().as_ref()],
+        bump
+    )]
+    pub governance_config: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub proposer: Signer<'info>,
     
@@ -522,24 +1166,254 @@ pub struct VoteOnProposal<'info> {
     #[account(mut)]
     pub proposal: Account<'info, Proposal>,
     
-    pub pack: Account<'info, Pack>,
-    
+    #[account(
+        seeds = [b"user", voter.authority.as_ref()],
+        bump,
+        seeds::program = alpha_pack_core::ID
+    )]
     pub voter: Account<'info, User>,
     
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VoteReceipt::INIT_SPACE,
+        seeds = [b"vote", proposal.key().as_ref(), voter.authority.as_ref()],
+        bump
+    )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
+    
+    /// CHECK: the voter's MemberShare PDA, which need not exist; required under the deposit-based
+    /// voting modes and checked by address in the handler
+    pub member_share: Option<UncheckedAccount<'info>>,
+    
+    pub session_key: Option<Account<'info, SessionKey>>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    /// The voter's authority or one of its session delegates; pays for the vote receipt
+    #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct ExecuteProposal<'info> {
+pub struct InitializeGovernanceConfig<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + GovernanceConfig::INIT_SPACE,
+        seeds = [b"governance_config", pack.key().as_ref()],
+        bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+    
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut, has_one = pack)]
     pub proposal: Account<'info, Proposal>,
     
+    #[account(mut)]
+    pub pack: Account<'info, Pack>,
+    
+    /// CHECK: the pack's governance PDA, signs the alpha_pack_core CPIs; holds no data
+    #[account(
+        seeds = [b"governance", pack.key().as_ref()],
+        bump
+    )]
+    pub governance: UncheckedAccount<'info>,
+    
+    // Treasury withdrawals only
+    #[account(
+        mut,
+        seeds = [b"treasury", pack.key().as_ref()],
+        bump = treasury.bump,
+        has_one = pack
+    )]
+    pub treasury: Option<Box<Account<'info, PackTreasury>>>,
+    
+    #[account(mut)]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub recipient_token_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    
+    // The member to remove, or the new leader
+    #[account(mut)]
+    pub target_user: Option<Box<Account<'info, User>>>,
+    
+    // Governance changes only
+    #[account(
+        mut,
+        seeds = [b"governance_config", pack.key().as_ref()],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Option<Account<'info, GovernanceConfig>>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    pub core_program: Program<'info, AlphaPackCore>,
+    
     pub executor: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(mut, has_one = pack)]
+    pub proposal: Account<'info, Proposal>,
+    
+    pub pack: Account<'info, Pack>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    /// The proposer or the pack leader
+    #[account(
+        constraint = authority.key() == proposal.proposer || authority.key() == pack.leader @ PackManagerError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    #[account(mut)]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProposal<'info> {
+    #[account(
+        mut,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVoteReceipt<'info> {
+    #[account(
+        mut,
+        seeds = [b"vote", vote_receipt.proposal.as_ref(), voter.key().as_ref()],
+        bump = vote_receipt.bump,
+        close = voter
+    )]
+    pub vote_receipt: Account<'info, VoteReceipt>,
+    
+    /// CHECK: the receipt's proposal, which may already be closed; read in the handler
+    #[account(address = vote_receipt.proposal)]
+    pub proposal: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ PackManagerError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    /// The member's wallet authority
+    #[account(mut)]
+    pub voter: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateTreasury<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(mut, owner = crate::ID)]
+    pub treasury: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProposal<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(mut, owner = crate::ID)]
+    pub proposal: UncheckedAccount<'info>,
+    
+    /// The proposal's pack; a v1 proposal's voter snapshot is taken from its current members
+    pub pack: Account<'info, Pack>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 // Data structures
+#[account]
+pub struct ManagerState {
+    pub authority: Pubkey,
+    pub total_proposals: u64,
+    pub bump: u8,
+    pub created_at: i64,
+}
+
+impl ManagerState {
+    pub const INIT_SPACE: usize = 32 + 8 + 1 + 8;
+}
+
 #[account]
 pub struct PackTreasury {
+    pub version: u8,
     pub pack: Pubkey,
     pub authority: Pubkey,
     pub total_value_usd: u64,
@@ -548,10 +1422,61 @@ pub struct PackTreasury {
     pub bump: u8,
     pub created_at: i64,
     pub updated_at: i64,
+    pub total_shares: u64,
 }
 
 impl PackTreasury {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + (4 + 10 * TreasuryToken::SPACE) + 1 + 1 + 8 + 8;
+    pub const VERSION: u8 = 2;
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + 8 + (4 + 10 * TreasuryToken::SPACE) + 1 + 1 + 8 + 8 + 8;
+
+    // Holdings from before shares existed have no owner to issue shares to, so the supply
+    // starts empty and deposits stay closed until governance withdraws those holdings
+    pub fn from_v1(v1: PackTreasuryV1, now: i64) -> Self {
+        Self {
+            version: Self::VERSION,
+            pack: v1.pack,
+            authority: v1.authority,
+            total_value_usd: v1.total_value_usd,
+            tokens: v1.tokens,
+            token_count: v1.token_count,
+            bump: v1.bump,
+            created_at: v1.created_at,
+            updated_at: now,
+            total_shares: 0,
+        }
+    }
+
+    /// Every token the treasury holds must have a fresh price before shares are priced
+    pub fn require_fresh_prices(&self, now: i64) -> Result<()> {
+        require!(
+            self.tokens.iter().all(|t| t.balance == 0 || t.is_priced(now)),
+            PackManagerError::StalePrice
+        );
+        Ok(())
+    }
+
+    /// Sum of the tokens' last computed values
+    pub fn tokens_value(&self) -> Result<u64> {
+        self.tokens
+            .iter()
+            .try_fold(0u64, |total, t| total.checked_add(t.value_usd))
+            .ok_or(error!(PackManagerError::MathOverflow))
+    }
+
+    /// Shares issued for a deposit worth `value_usd`. The first deposit sets one share per
+    /// unit of value; after that shares are issued at the treasury's current value per share.
+    pub fn shares_for(&self, value_usd: u64) -> Result<u64> {
+        if self.total_shares == 0 {
+            require!(
+                self.tokens.iter().all(|t| t.balance == 0),
+                PackManagerError::UnclaimedHoldings
+            );
+            return Ok(value_usd);
+        }
+        require!(self.total_value_usd > 0, PackManagerError::StalePrice);
+        u64::try_from(value_usd as u128 * self.total_shares as u128 / self.total_value_usd as u128)
+            .map_err(|_| error!(PackManagerError::MathOverflow))
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -567,10 +1492,346 @@ pub struct TreasuryToken {
 
 impl TreasuryToken {
     pub const SPACE: usize = 32 + 32 + 8 + 8 + 1 + 8 + 8;
+    /// Prices older than this are not used to price shares
+    pub const MAX_PRICE_AGE: i64 = 60 * 60;
+
+    pub fn is_priced(&self, now: i64) -> bool {
+        self.last_price > 0 && now - self.price_updated_at <= Self::MAX_PRICE_AGE
+    }
+
+    /// USD value of `amount` at the last price; prices are quoted per 10^6 base units
+    pub fn value_of(&self, amount: u64) -> Result<u64> {
+        u64::try_from(amount as u128 * self.last_price as u128 / 1_000_000)
+            .map_err(|_| error!(PackManagerError::MathOverflow))
+    }
+}
+
+/// A member's claim on a pack treasury, `[b"member_share", treasury, owner]`
+#[account]
+pub struct MemberShare {
+    pub treasury: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+    /// Total USD value deposited, at the prices used to issue the shares
+    pub deposited_value_usd: u64,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl MemberShare {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 1 + 8 + 8;
 }
 
 #[account]
 pub struct Proposal {
+    pub version: u8,
+    pub pack: Pubkey,
+    pub proposer: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub proposal_type: ProposalType,
+    pub execution_data: Vec<u8>,
+    pub status: ProposalStatus,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub votes_abstain: u64,
+    pub total_voters: u32,
+    /// Pack members with voting weight when the proposal was created; only they may vote
+    pub eligible_voters: Vec<EligibleVoter>,
+    pub quorum_bps: u16,
+    pub threshold_bps: u16,
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub executed_at: Option<i64>,
+    /// When a passed proposal may be executed; zero until it passes
+    pub executable_at: i64,
+    pub voting_mode: VotingMode,
+    /// Sum of the eligible voters' weights
+    pub total_weight: u64,
+}
+
+impl Proposal {
+    pub const VERSION: u8 = 2;
+    /// alpha_pack_core caps packs at 20 members
+    pub const MAX_VOTERS: usize = 20;
+    pub const INIT_SPACE: usize = 1 + 32 + 32 + (4 + 100) + (4 + 1000) + 1 + (4 + 1000) + 1 + 8 + 8 + 8 + 4
+        + (4 + Self::MAX_VOTERS * EligibleVoter::SPACE) + 2 + 2 + 8 + 8 + (1 + 8) + 8 + 1 + 8;
+    /// Timelock between a proposal passing and its execution
+    pub const EXECUTION_DELAY: i64 = 24 * 60 * 60;
+    /// How long a passed proposal stays executable once its timelock elapses
+    pub const EXECUTION_WINDOW: i64 = 7 * 24 * 60 * 60;
+
+    /// Active v1 proposals are expired: their votes were never receipted, so they cannot be
+    /// carried over without letting those members vote again
+    pub fn from_v1(v1: ProposalV1, members: &[Pubkey]) -> Self {
+        let status = match v1.status {
+            ProposalStatus::Active => ProposalStatus::Expired,
+            status => status,
+        };
+        Self {
+            version: Self::VERSION,
+            pack: v1.pack,
+            proposer: v1.proposer,
+            title: v1.title,
+            description: v1.description,
+            proposal_type: v1.proposal_type,
+            execution_data: v1.execution_data,
+            status,
+            votes_for: v1.votes_for as u64,
+            votes_against: v1.votes_against as u64,
+            votes_abstain: 0,
+            total_voters: v1.total_voters,
+            eligible_voters: members.iter()
+                .map(|&voter| EligibleVoter { voter, weight: 1 })
+                .collect(),
+            quorum_bps: GovernanceConfig::DEFAULT_QUORUM_BPS,
+            threshold_bps: GovernanceConfig::DEFAULT_THRESHOLD_BPS,
+            created_at: v1.created_at,
+            voting_ends_at: v1.voting_ends_at,
+            executed_at: v1.executed_at,
+            executable_at: 0,
+            voting_mode: VotingMode::Equal,
+            total_weight: members.len() as u64,
+        }
+    }
+
+    /// End of the execution window of a passed proposal
+    pub fn execution_deadline(&self) -> i64 {
+        self.executable_at.saturating_add(Self::EXECUTION_WINDOW)
+    }
+
+    /// Settled, or passed but no longer executable
+    pub fn is_closable(&self, now: i64) -> bool {
+        self.status.is_settled()
+            || (self.status == ProposalStatus::Passed && now >= self.execution_deadline())
+    }
+
+    /// Weight not yet cast by the snapshot
+    pub fn remaining_weight(&self) -> u64 {
+        self.total_weight.saturating_sub(self.votes_for + self.votes_against + self.votes_abstain)
+    }
+
+    pub fn quorum_reached(&self) -> bool {
+        let cast = self.votes_for as u128 + self.votes_against as u128 + self.votes_abstain as u128;
+        cast * 10_000 >= self.quorum_bps as u128 * self.total_weight as u128
+    }
+
+    /// Whether `votes_for` clears the threshold of the decisive (non-abstaining) votes
+    pub fn threshold_met(&self, votes_for: u64, votes_against: u64) -> bool {
+        let decisive = votes_for as u128 + votes_against as u128;
+        votes_for as u128 * 10_000 > self.threshold_bps as u128 * decisive
+    }
+
+    /// Passes even if every remaining voter votes against
+    pub fn is_decided_for(&self) -> bool {
+        self.quorum_reached()
+            && self.threshold_met(self.votes_for, self.votes_against.saturating_add(self.remaining_weight()))
+    }
+
+    /// Fails even if every remaining voter votes for
+    pub fn is_decided_against(&self) -> bool {
+        !self.threshold_met(self.votes_for.saturating_add(self.remaining_weight()), self.votes_against)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EligibleVoter {
+    /// The member's wallet authority
+    pub voter: Pubkey,
+    pub weight: u64,
+}
+
+impl EligibleVoter {
+    pub const SPACE: usize = 32 + 8;
+}
+
+/// A member's vote on a proposal, `[b"vote", proposal, voter]`; its existence blocks a second vote
+#[account]
+pub struct VoteReceipt {
+    pub proposal: Pubkey,
+    /// The member's wallet authority
+    pub voter: Pubkey,
+    pub choice: VoteChoice,
+    pub weight: u64,
+    pub bump: u8,
+    pub timestamp: i64,
+}
+
+impl VoteReceipt {
+    pub const INIT_SPACE: usize = 32 + 32 + 1 + 8 + 1 + 8;
+}
+
+/// Per-pack voting rules, `[b"governance_config", pack]`, copied into each proposal when it is created
+#[account]
+pub struct GovernanceConfig {
+    pub version: u8,
+    pub pack: Pubkey,
+    /// Share of the snapshot's weight that must vote, abstentions included
+    pub quorum_bps: u16,
+    /// Share of the for + against weight that must be for
+    pub threshold_bps: u16,
+    pub bump: u8,
+    pub updated_at: i64,
+    pub voting_mode: VotingMode,
+    /// Seconds a proposal stays open for voting
+    pub voting_period: i64,
+}
+
+impl GovernanceConfig {
+    pub const VERSION: u8 = 1;
+    pub const INIT_SPACE: usize = 1 + 32 + 2 + 2 + 1 + 8 + 1 + 8;
+    pub const DEFAULT_QUORUM_BPS: u16 = 5_000;
+    pub const DEFAULT_THRESHOLD_BPS: u16 = 5_000;
+    pub const DEFAULT_VOTING_PERIOD: i64 = 7 * 24 * 60 * 60;
+    /// Floor on quorum, so a handful of members cannot pass proposals on their own
+    pub const MIN_QUORUM_BPS: u16 = 2_000;
+    pub const MIN_VOTING_PERIOD: i64 = 60 * 60;
+    pub const MAX_VOTING_PERIOD: i64 = 30 * 24 * 60 * 60;
+
+    pub fn defaults(pack: Pubkey) -> Self {
+        Self {
+            version: Self::VERSION,
+            pack,
+            quorum_bps: Self::DEFAULT_QUORUM_BPS,
+            threshold_bps: Self::DEFAULT_THRESHOLD_BPS,
+            bump: 0,
+            updated_at: 0,
+            voting_mode: VotingMode::Equal,
+            voting_period: Self::DEFAULT_VOTING_PERIOD,
+        }
+    }
+
+    pub fn validate(quorum_bps: u16, threshold_bps: u16, voting_period: i64) -> Result<()> {
+        require!(
+            (Self::MIN_QUORUM_BPS..=10_000).contains(&quorum_bps)
+                && (5_000..10_000).contains(&threshold_bps)
+                && (Self::MIN_VOTING_PERIOD..=Self::MAX_VOTING_PERIOD).contains(&voting_period),
+            PackManagerError::InvalidGovernanceConfig
+        );
+        Ok(())
+    }
+}
+
+/// `execution_data` of a TreasuryWithdrawal proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct TreasuryWithdrawalAction {
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// Owner of the token account receiving the withdrawal
+    pub recipient: Pubkey,
+}
+
+/// `execution_data` of a StrategyChange proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct StrategyChangeAction {
+    pub strategy_focus: StrategyFocus,
+    pub risk_level: RiskLevel,
+}
+
+/// `execution_data` of a MemberRemoval proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct MemberRemovalAction {
+    /// Authority of the member to remove
+    pub member: Pubkey,
+}
+
+/// `execution_data` of a LeadershipChange proposal
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct LeadershipChangeAction {
+    /// Authority of the member to hand leadership to
+    pub new_leader: Pubkey,
+}
+
+/// `execution_data` of a GovernanceChange proposal: the voting rules for later proposals
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct GovernanceChangeAction {
+    pub quorum_bps: u16,
+    pub threshold_bps: u16,
+    pub voting_mode: VotingMode,
+    pub voting_period: i64,
+}
+
+/// A proposal's decoded `execution_data`; the payload is the Borsh encoding of the action
+/// struct for the proposal's type, without an enum tag
+#[derive(Clone, PartialEq, Eq)]
+pub enum ProposalAction {
+    TreasuryWithdrawal(TreasuryWithdrawalAction),
+    StrategyChange(StrategyChangeAction),
+    MemberRemoval(MemberRemovalAction),
+    LeadershipChange(LeadershipChangeAction),
+    GovernanceChange(GovernanceChangeAction),
+}
+
+impl ProposalAction {
+    pub fn decode(proposal_type: &ProposalType, execution_data: &[u8]) -> Result<Self> {
+        let action = match proposal_type {
+            ProposalType::TreasuryWithdrawal => {
+                TreasuryWithdrawalAction::try_from_slice(execution_data).map(Self::TreasuryWithdrawal)
+            },
+            ProposalType::StrategyChange => {
+                StrategyChangeAction::try_from_slice(execution_data).map(Self::StrategyChange)
+            },
+            ProposalType::MemberRemoval => {
+                MemberRemovalAction::try_from_slice(execution_data).map(Self::MemberRemoval)
+            },
+            ProposalType::LeadershipChange => {
+                LeadershipChangeAction::try_from_slice(execution_data).map(Self::LeadershipChange)
+            },
+            ProposalType::GovernanceChange => {
+                GovernanceChangeAction::try_from_slice(execution_data).map(Self::GovernanceChange)
+            },
+        };
+        action.map_err(|_| error!(PackManagerError::InvalidExecutionData))
+    }
+
+    pub fn proposal_type(&self) -> ProposalType {
+        match self {
+            Self::TreasuryWithdrawal(_) => ProposalType::TreasuryWithdrawal,
+            Self::StrategyChange(_) => ProposalType::StrategyChange,
+            Self::MemberRemoval(_) => ProposalType::MemberRemoval,
+            Self::LeadershipChange(_) => ProposalType::LeadershipChange,
+            Self::GovernanceChange(_) => ProposalType::GovernanceChange,
+        }
+    }
+
+    pub fn execution_data(&self) -> Vec<u8> {
+        match self {
+            Self::TreasuryWithdrawal(action) => action.try_to_vec(),
+            Self::StrategyChange(action) => action.try_to_vec(),
+            Self::MemberRemoval(action) => action.try_to_vec(),
+            Self::LeadershipChange(action) => action.try_to_vec(),
+            Self::GovernanceChange(action) => action.try_to_vec(),
+        }
+        .unwrap()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TokenPrice {
+    pub mint: Pubkey,
+    pub price_usd: u64,
+}
+
+// Legacy account layouts, kept so the migrate_* instructions can decode deployed accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PackTreasuryV1 {
+    pub pack: Pubkey,
+    pub authority: Pubkey,
+    pub total_value_usd: u64,
+    pub tokens: Vec<TreasuryToken>,
+    pub token_count: u8,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl PackTreasuryV1 {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + (4 + 10 * TreasuryToken::SPACE) + 1 + 1 + 8 + 8;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProposalV1 {
     pub pack: Pubkey,
     pub proposer: Pubkey,
     pub title: String,
@@ -587,7 +1848,7 @@ pub struct Proposal {
     pub executed_at: Option<i64>,
 }
 
-impl Proposal {
+impl ProposalV1 {
     pub const INIT_SPACE: usize = 32 + 32 + (4 + 100) + (4 + 1000) + 1 + (4 + 1000) + 1 + 4 + 4 + 4 + (4 + 50 * Vote::SPACE) + 8 + 8 + (1 + 8);
 }
 
@@ -602,12 +1863,6 @@ impl Vote {
     pub const SPACE: usize = 32 + 1 + 8;
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct TokenPrice {
-    pub mint: Pubkey,
-    pub price_usd: u64,
-}
-
 // Enums
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
 pub enum ProposalType {
@@ -615,6 +1870,7 @@ pub enum ProposalType {
     StrategyChange,
     MemberRemoval,
     LeadershipChange,
+    GovernanceChange,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -623,12 +1879,43 @@ pub enum ProposalStatus {
     Passed,
     Rejected,
     Executed,
+    /// Voting closed without quorum
     Expired,
+    /// Withdrawn before any votes were cast
+    Cancelled,
+}
+
+impl ProposalStatus {
+    /// Nothing further can happen to the proposal
+    pub fn is_settled(&self) -> bool {
+        matches!(self, Self::Rejected | Self::Executed | Self::Expired | Self::Cancelled)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum VoteChoice {
+    For,
+    Against,
+    /// Counts towards quorum but not towards the threshold
+    Abstain,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VotingMode {
+    /// One vote per member
+    Equal,
+    /// Treasury shares held
+    DepositWeighted,
+    /// Social score plus win rate
+    ReputationWeighted,
+    /// Square root of treasury shares held
+    Quadratic,
 }
 
 // Events
 #[event]
 pub struct TreasuryTokenAdded {
+    pub header: EventHeader,
     pub treasury: Pubkey,
     pub mint: Pubkey,
     pub allocation_percentage: u8,
@@ -637,6 +1924,7 @@ pub struct TreasuryTokenAdded {
 
 #[event]
 pub struct TreasuryDeposit {
+    pub header: EventHeader,
     pub treasury: Pubkey,
     pub depositor: Pubkey,
     pub mint: Pubkey,
@@ -646,15 +1934,40 @@ pub struct TreasuryDeposit {
 
 #[event]
 pub struct TreasuryWithdrawal {
+    pub header: EventHeader,
     pub treasury: Pubkey,
-    pub leader: Pubkey,
+    pub recipient: Pubkey,
     pub mint: Pubkey,
     pub amount: u64,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct SharesIssued {
+    pub header: EventHeader,
+    pub treasury: Pubkey,
+    pub member: Pubkey,
+    pub shares: u64,
+    pub value_usd: u64,
+    pub total_shares: u64,
+    pub treasury_value: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SharesRedeemed {
+    pub header: EventHeader,
+    pub treasury: Pubkey,
+    pub member: Pubkey,
+    pub shares: u64,
+    pub total_shares: u64,
+    pub treasury_value: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct TreasuryRebalanced {
+    pub header: EventHeader,
     pub treasury: Pubkey,
     pub total_value: u64,
     pub timestamp: i64,
@@ -662,6 +1975,7 @@ pub struct TreasuryRebalanced {
 
 #[event]
 pub struct TokenPricesUpdated {
+    pub header: EventHeader,
     pub treasury: Pubkey,
     pub total_value: u64,
     pub timestamp: i64,
@@ -669,6 +1983,7 @@ pub struct TokenPricesUpdated {
 
 #[event]
 pub struct ProposalCreated {
+    pub header: EventHeader,
     pub proposal: Pubkey,
     pub pack: Pubkey,
     pub proposer: Pubkey,
@@ -677,8 +1992,11 @@ pub struct ProposalCreated {
     pub timestamp: i64,
 }
 
+/// No longer emitted. v1 voting logged this without the header, so historical logs are in
+/// the legacy layout and do not decode into this struct.
 #[event]
 pub struct VoteCast {
+    pub header: EventHeader,
     pub proposal: Pubkey,
     pub voter: Pubkey,
     pub vote: bool,
@@ -687,13 +2005,73 @@ pub struct VoteCast {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct VoteRecorded {
+    pub header: EventHeader,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub choice: VoteChoice,
+    pub weight: u64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub votes_abstain: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GovernanceConfigUpdated {
+    pub header: EventHeader,
+    pub pack: Pubkey,
+    pub quorum_bps: u16,
+    pub threshold_bps: u16,
+    pub voting_mode: VotingMode,
+    pub voting_period: i64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct ProposalExecuted {
+    pub header: EventHeader,
     pub proposal: Pubkey,
     pub executor: Pubkey,
     pub timestamp: i64,
 }
 
+#[event]
+pub struct ProposalCancelled {
+    pub header: EventHeader,
+    pub proposal: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalFinalized {
+    pub header: EventHeader,
+    pub proposal: Pubkey,
+    pub status: ProposalStatus,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub votes_abstain: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub header: EventHeader,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteReceiptClosed {
+    pub header: EventHeader,
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub timestamp: i64,
+}
+
 // Errors
 #[error_code]
 pub enum PackManagerError {
@@ -713,10 +2091,54 @@ pub enum PackManagerError {
     ProposalNotActive,
     #[msg("Voting period has ended")]
     VotingPeriodEnded,
-    #[msg("User has already voted")]
-    AlreadyVoted,
     #[msg("Proposal has not passed")]
     ProposalNotPassed,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Alpha Pack protocol is paused")]
+    ProtocolPaused,
+    #[msg("Counter does not match on-chain state")]
+    InvalidCounter,
+    #[msg("Only pack members can deposit to the treasury")]
+    NotPackMember,
+    #[msg("Treasury token price is missing or stale")]
+    StalePrice,
+    #[msg("Deposit is too small to issue a share")]
+    DepositTooSmall,
+    #[msg("Insufficient shares")]
+    InsufficientShares,
+    #[msg("Token accounts do not match the treasury tokens")]
+    InvalidTokenAccounts,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Treasury holds tokens that no shares were issued for")]
+    UnclaimedHoldings,
+    #[msg("Execution data does not decode as the proposal type's action")]
+    InvalidExecutionData,
+    #[msg("Proposal timelock has not elapsed")]
+    TimelockNotElapsed,
+    #[msg("An account required to execute this proposal is missing")]
+    MissingExecutionAccount,
+    #[msg("Account does not match the proposal's action")]
+    InvalidExecutionAccount,
+    #[msg("Voter was not a pack member when the proposal was created")]
+    NotEligibleToVote,
+    #[msg("Quorum must be 2000-10000 bps, threshold 5000-9999 bps and the voting period 1 hour to 30 days")]
+    InvalidGovernanceConfig,
+    #[msg("Account belongs to a different pack")]
+    PackMismatch,
+    #[msg("Voter accounts do not match the pack members and voting mode")]
+    InvalidVoterAccounts,
+    #[msg("No pack member has voting weight")]
+    NoVotingWeight,
+    #[msg("Proposal already has votes")]
+    ProposalHasVotes,
+    #[msg("Voting period has not ended")]
+    VotingPeriodNotEnded,
+    #[msg("Proposal is still open or awaiting execution")]
+    ProposalNotSettled,
+    #[msg("Signer is not the registered price oracle")]
+    InvalidPriceOracle,
+    #[msg("Proposal's execution window has closed")]
+    ExecutionWindowClosed,
 }
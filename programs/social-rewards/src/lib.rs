@@ -1,8 +1,13 @@
+// Anchor handlers take one argument per instruction field and return its large Error type
+#![allow(clippy::result_large_err, clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo, Transfer};
+use anchor_spl::token::{self, Token, TokenAccount, Mint, MintTo};
 use anchor_spl::associated_token::AssociatedToken;
-use mpl_token_metadata::instruction as mpl_instruction;
-use alpha_pack_core::{Pack, User};
+use alpha_pack_core::{
+    GlobalState, Pack, User, AdminConfig, AccountMigrated, EventHeader, read_legacy, write_migrated,
+};
+use alpha_pack_core::program::AlphaPackCore;
 
 declare_id!("SocialRewards111111111111111111111111111111");
 
@@ -18,6 +23,7 @@ pub mod social_rewards {
         engagement_threshold: u64,
     ) -> Result<()> {
         let rewards_system = &mut ctx.accounts.rewards_system;
+        rewards_system.version = SocialRewardsSystem::VERSION;
         rewards_system.authority = ctx.accounts.authority.key();
         rewards_system.reward_mint = ctx.accounts.reward_mint.key();
         rewards_system.daily_reward_pool = daily_reward_pool;
@@ -29,8 +35,10 @@ pub mod social_rewards {
         rewards_system.is_active = true;
         rewards_system.created_at = Clock::get()?.unix_timestamp;
         rewards_system.updated_at = Clock::get()?.unix_timestamp;
+        rewards_system.admin = AdminConfig::default();
 
         emit!(RewardsSystemInitialized {
+            header: EventHeader::now()?,
             system: rewards_system.key(),
             authority: rewards_system.authority,
             daily_reward_pool,
@@ -45,6 +53,7 @@ pub mod social_rewards {
     /// Create social content post
     pub fn create_content_post(
         ctx: Context<CreateContentPost>,
+        post_index: u64,
         platform: SocialPlatform,
         content_type: ContentType,
         content_hash: String,
@@ -80,15 +89,16 @@ pub mod social_rewards {
         rewards_system.updated_at = current_time;
 
         emit!(ContentPostCreated {
+            header: EventHeader::now()?,
             post: content_post.key(),
             creator: content_post.creator,
             pack: content_post.pack,
-            platform: content_post.platform,
-            content_type: content_post.content_type,
+            platform: content_post.platform.clone(),
+            content_type: content_post.content_type.clone(),
             timestamp: current_time,
         });
 
-        msg!("Content post created");
+        msg!("Content post {} created", post_index);
         Ok(())
     }
 
@@ -118,7 +128,7 @@ pub mod social_rewards {
         let virality_score = calculate_virality_score(
             engagement_score,
             current_time - content_post.created_at,
-            content_post.platform,
+            content_post.platform.clone(),
         );
         content_post.virality_score = virality_score;
 
@@ -135,6 +145,7 @@ pub mod social_rewards {
             rewards_system.updated_at = current_time;
 
             emit!(ContentWentViral {
+                header: EventHeader::now()?,
                 post: content_post.key(),
                 creator: content_post.creator,
                 engagement_score,
@@ -144,6 +155,7 @@ pub mod social_rewards {
         }
 
         emit!(EngagementUpdated {
+            header: EventHeader::now()?,
             post: content_post.key(),
             likes,
             shares,
@@ -194,10 +206,8 @@ pub mod social_rewards {
         let total_reward = base_reward + viral_bonus;
 
         // Mint rewards to creator
-        let seeds = &[
-            b"rewards_system",
-            &[ctx.bumps.rewards_system],
-        ];
+        let bump = *ctx.bumps.get("rewards_system").unwrap();
+        let seeds = &[b"rewards_system".as_ref(), &[bump]];
         let signer = &[&seeds[..]];
 
         let cpi_accounts = MintTo {
@@ -219,12 +229,23 @@ pub mod social_rewards {
         rewards_system.total_rewards_distributed += total_reward;
         rewards_system.updated_at = current_time;
 
-        // Update user social score
-        let user = &mut ctx.accounts.user;
-        user.social_score += (total_reward / 1000); // Convert to social score points
-        user.updated_at = current_time;
+        // Credit the creator's social score in core, signed by the rewards_system PDA
+        let epoch = ctx.accounts.core_state.current_epoch(current_time);
+        let cpi_accounts = alpha_pack_core::cpi::accounts::UpdateSocialScore {
+            user: ctx.accounts.user.to_account_info(),
+            pack: ctx.accounts.pack.as_ref().map(|pack| pack.to_account_info()),
+            user_epoch_stats: ctx.accounts.user_epoch_stats.to_account_info(),
+            pack_epoch_stats: ctx.accounts.pack_epoch_stats.as_ref().map(|stats| stats.to_account_info()),
+            global_state: ctx.accounts.core_state.to_account_info(),
+            scorer: ctx.accounts.rewards_system.to_account_info(),
+            payer: ctx.accounts.creator.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(ctx.accounts.core_program.to_account_info(), cpi_accounts, signer);
+        alpha_pack_core::cpi::update_social_score(cpi_ctx, epoch, (total_reward / 1000) as i64)?; // Convert to social score points
 
         emit!(RewardsClaimed {
+            header: EventHeader::now()?,
             post: content_post.key(),
             creator: content_post.creator,
             base_reward,
@@ -271,6 +292,7 @@ pub mod social_rewards {
         token::mint_to(cpi_ctx, 1)?; // Mint 1 NFT
 
         emit!(ViralNFTMinted {
+            header: EventHeader::now()?,
             post: content_post.key(),
             creator: content_post.creator,
             nft_mint: ctx.accounts.nft_mint.key(),
@@ -293,11 +315,12 @@ pub mod social_rewards {
         );
 
         let leaderboard = &mut ctx.accounts.influence_leaderboard;
-        leaderboard.season = leaderboard.season + 1;
+        leaderboard.season += 1;
         leaderboard.top_influencers = top_influencers;
         leaderboard.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(InfluenceLeaderboardUpdated {
+            header: EventHeader::now()?,
             leaderboard: leaderboard.key(),
             season: leaderboard.season,
             total_influencers: leaderboard.top_influencers.len() as u32,
@@ -315,6 +338,7 @@ pub mod social_rewards {
     ) -> Result<()> {
         let leaderboard = &ctx.accounts.influence_leaderboard;
         let rewards_system = &mut ctx.accounts.rewards_system;
+        rewards_system.admin.check_approval(&rewards_system.authority, ctx.remaining_accounts)?;
 
         require!(
             reward_amounts.len() == leaderboard.top_influencers.len(),
@@ -328,6 +352,7 @@ pub mod social_rewards {
         rewards_system.updated_at = Clock::get()?.unix_timestamp;
 
         emit!(SeasonalRewardsDistributed {
+            header: EventHeader::now()?,
             season: leaderboard.season,
             total_amount: total_distributed,
             recipients: leaderboard.top_influencers.len() as u32,
@@ -337,6 +362,72 @@ pub mod social_rewards {
         msg!("Seasonal rewards distributed: {}", total_distributed);
         Ok(())
     }
+
+    /// Propose a new rewards system authority; `None` cancels a pending proposal
+    pub fn propose_admin_authority(ctx: Context<ProposeAdminAuthority>, new_authority: Option<Pubkey>) -> Result<()> {
+        let rewards_system = &mut ctx.accounts.rewards_system;
+        let (account, authority) = (rewards_system.key(), rewards_system.authority);
+        let now = Clock::get()?.unix_timestamp;
+        rewards_system.admin.propose(account, authority, ctx.remaining_accounts, new_authority, now)?;
+        rewards_system.updated_at = now;
+
+        msg!("Rewards system authority transfer proposed");
+        Ok(())
+    }
+
+    /// Accept a proposed rewards system authority transfer (signed by the new authority)
+    pub fn accept_admin_authority(ctx: Context<AcceptAdminAuthority>) -> Result<()> {
+        let rewards_system = &mut ctx.accounts.rewards_system;
+        let (account, authority) = (rewards_system.key(), rewards_system.authority);
+        let now = Clock::get()?.unix_timestamp;
+        rewards_system.authority = rewards_system.admin.transfer(account, authority, ctx.accounts.new_authority.key(), now)?;
+        rewards_system.updated_at = now;
+
+        msg!("Rewards system authority transferred to {}", rewards_system.authority);
+        Ok(())
+    }
+
+    /// Set the M-of-N approvers required for rewards admin actions; an empty set disables them
+    pub fn configure_admin_approvals(
+        ctx: Context<ConfigureAdminApprovals>,
+        approvers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let rewards_system = &mut ctx.accounts.rewards_system;
+        let (account, authority) = (rewards_system.key(), rewards_system.authority);
+        let now = Clock::get()?.unix_timestamp;
+        rewards_system.admin.configure(account, authority, ctx.remaining_accounts, approvers, threshold, now)?;
+        rewards_system.updated_at = now;
+
+        msg!("Rewards admin approvals set to {}-of-{}", threshold, rewards_system.admin.approvers.len());
+        Ok(())
+    }
+
+    /// Upgrade the v1 rewards system account to the current layout (permissionless)
+    pub fn migrate_rewards_system(ctx: Context<MigrateRewardsSystem>) -> Result<()> {
+        let info = ctx.accounts.rewards_system.to_account_info();
+        let legacy: SocialRewardsSystemV1 =
+            read_legacy::<SocialRewardsSystem, _>(&info, SocialRewardsSystemV1::INIT_SPACE)?;
+        let rewards_system = SocialRewardsSystem::from_v1(legacy);
+        write_migrated(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + SocialRewardsSystem::INIT_SPACE,
+            &rewards_system,
+        )?;
+
+        emit!(AccountMigrated {
+            header: EventHeader::now()?,
+            account: info.key(),
+            from_version: 1,
+            to_version: SocialRewardsSystem::VERSION,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Rewards system migrated to v{}", SocialRewardsSystem::VERSION);
+        Ok(())
+    }
 }
 
 // Helper functions
@@ -413,12 +504,13 @@ pub struct InitializeRewardsSystem<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(post_index: u64)]
 pub struct CreateContentPost<'info> {
     #[account(
         init,
         payer = creator,
         space = 8 + ContentPost::INIT_SPACE,
-        seeds = [b"content_post", creator.key().as_ref(), &Clock::get().unwrap().unix_timestamp.to_le_bytes()],
+        seeds = [b"content_post".as_ref(), &post_index.to_le_bytes()],
         bump
     )]
     pub content_post: Account<'info, ContentPost>,
@@ -426,9 +518,22 @@ pub struct CreateContentPost<'info> {
     #[account(mut)]
     pub user: Account<'info, User>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"rewards_system"],
+        bump,
+        constraint = rewards_system.total_content_pieces == post_index @ SocialRewardsError::InvalidCounter
+    )]
     pub rewards_system: Account<'info, SocialRewardsSystem>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ SocialRewardsError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub creator: Signer<'info>,
     
@@ -443,6 +548,14 @@ pub struct UpdateEngagement<'info> {
     #[account(mut)]
     pub rewards_system: Account<'info, SocialRewardsSystem>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ SocialRewardsError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     pub engagement_oracle: Signer<'info>, // Authorized engagement updater
 }
 
@@ -468,14 +581,40 @@ pub struct ClaimContentRewards<'info> {
     )]
     pub creator_token_account: Account<'info, TokenAccount>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"user", creator.key().as_ref()],
+        bump,
+        seeds::program = alpha_pack_core::ID
+    )]
     pub user: Account<'info, User>,
     
+    #[account(mut)]
+    pub pack: Option<Account<'info, Pack>>,
+    
+    /// CHECK: the creator's current-epoch stats; seeds are checked by core
+    #[account(mut)]
+    pub user_epoch_stats: UncheckedAccount<'info>,
+    
+    /// CHECK: the pack's current-epoch stats; seeds are checked by core
+    #[account(mut)]
+    pub pack_epoch_stats: Option<UncheckedAccount<'info>>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ SocialRewardsError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub creator: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    pub core_program: Program<'info, AlphaPackCore>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
@@ -499,6 +638,14 @@ pub struct MintViralNFT<'info> {
     )]
     pub creator_nft_account: Account<'info, TokenAccount>,
     
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ SocialRewardsError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub creator: Signer<'info>,
     
@@ -518,6 +665,21 @@ pub struct UpdateInfluenceLeaderboard<'info> {
     )]
     pub influence_leaderboard: Account<'info, InfluenceLeaderboard>,
     
+    #[account(
+        seeds = [b"rewards_system"],
+        bump,
+        has_one = authority
+    )]
+    pub rewards_system: Account<'info, SocialRewardsSystem>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ SocialRewardsError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -528,15 +690,84 @@ pub struct UpdateInfluenceLeaderboard<'info> {
 pub struct DistributeSeasonalRewards<'info> {
     pub influence_leaderboard: Account<'info, InfluenceLeaderboard>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"rewards_system"],
+        bump,
+        has_one = authority
+    )]
+    pub rewards_system: Account<'info, SocialRewardsSystem>,
+    
+    #[account(
+        seeds = [b"global_state"],
+        bump = core_state.bump,
+        seeds::program = alpha_pack_core::ID,
+        constraint = !core_state.paused @ SocialRewardsError::ProtocolPaused
+    )]
+    pub core_state: Account<'info, GlobalState>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_system"],
+        bump,
+        has_one = authority
+    )]
+    pub rewards_system: Account<'info, SocialRewardsSystem>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdminAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_system"],
+        bump
+    )]
+    pub rewards_system: Account<'info, SocialRewardsSystem>,
+    
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfigureAdminApprovals<'info> {
+    #[account(
+        mut,
+        seeds = [b"rewards_system"],
+        bump,
+        has_one = authority
+    )]
     pub rewards_system: Account<'info, SocialRewardsSystem>,
     
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct MigrateRewardsSystem<'info> {
+    /// CHECK: still holds the v1 layout; checked by size and discriminator in the handler
+    #[account(
+        mut,
+        seeds = [b"rewards_system"],
+        bump,
+        owner = crate::ID
+    )]
+    pub rewards_system: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 // Data structures
 #[account]
 pub struct SocialRewardsSystem {
+    pub version: u8,
     pub authority: Pubkey,
     pub reward_mint: Pubkey,
     pub daily_reward_pool: u64,
@@ -548,10 +779,30 @@ pub struct SocialRewardsSystem {
     pub is_active: bool,
     pub created_at: i64,
     pub updated_at: i64,
+    pub admin: AdminConfig,
 }
 
 impl SocialRewardsSystem {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + 2 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+    pub const VERSION: u8 = 2;
+    pub const INIT_SPACE: usize = 1 + SocialRewardsSystemV1::INIT_SPACE + AdminConfig::SPACE;
+
+    pub fn from_v1(v1: SocialRewardsSystemV1) -> Self {
+        Self {
+            version: Self::VERSION,
+            authority: v1.authority,
+            reward_mint: v1.reward_mint,
+            daily_reward_pool: v1.daily_reward_pool,
+            viral_bonus_multiplier: v1.viral_bonus_multiplier,
+            engagement_threshold: v1.engagement_threshold,
+            total_rewards_distributed: v1.total_rewards_distributed,
+            total_content_pieces: v1.total_content_pieces,
+            total_viral_content: v1.total_viral_content,
+            is_active: v1.is_active,
+            created_at: v1.created_at,
+            updated_at: v1.updated_at,
+            admin: AdminConfig::default(),
+        }
+    }
 }
 
 #[account]
@@ -604,8 +855,28 @@ impl InfluencerEntry {
     pub const SPACE: usize = 32 + (1 + 32) + 8 + 4 + 8 + 4;
 }
 
+// Legacy account layouts, kept so migrate_rewards_system can decode deployed accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SocialRewardsSystemV1 {
+    pub authority: Pubkey,
+    pub reward_mint: Pubkey,
+    pub daily_reward_pool: u64,
+    pub viral_bonus_multiplier: u16,
+    pub engagement_threshold: u64,
+    pub total_rewards_distributed: u64,
+    pub total_content_pieces: u64,
+    pub total_viral_content: u64,
+    pub is_active: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl SocialRewardsSystemV1 {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 2 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+}
+
 // Enums
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum SocialPlatform {
     Twitter,
     TikTok,
@@ -615,7 +886,7 @@ pub enum SocialPlatform {
     YouTube,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
 pub enum ContentType {
     Text,
     Image,
@@ -630,6 +901,7 @@ pub enum ContentType {
 // Events
 #[event]
 pub struct RewardsSystemInitialized {
+    pub header: EventHeader,
     pub system: Pubkey,
     pub authority: Pubkey,
     pub daily_reward_pool: u64,
@@ -639,6 +911,7 @@ pub struct RewardsSystemInitialized {
 
 #[event]
 pub struct ContentPostCreated {
+    pub header: EventHeader,
     pub post: Pubkey,
     pub creator: Pubkey,
     pub pack: Option<Pubkey>,
@@ -649,6 +922,7 @@ pub struct ContentPostCreated {
 
 #[event]
 pub struct EngagementUpdated {
+    pub header: EventHeader,
     pub post: Pubkey,
     pub likes: u64,
     pub shares: u64,
@@ -661,6 +935,7 @@ pub struct EngagementUpdated {
 
 #[event]
 pub struct ContentWentViral {
+    pub header: EventHeader,
     pub post: Pubkey,
     pub creator: Pubkey,
     pub engagement_score: u64,
@@ -670,6 +945,7 @@ pub struct ContentWentViral {
 
 #[event]
 pub struct RewardsClaimed {
+    pub header: EventHeader,
     pub post: Pubkey,
     pub creator: Pubkey,
     pub base_reward: u64,
@@ -680,6 +956,7 @@ pub struct RewardsClaimed {
 
 #[event]
 pub struct ViralNFTMinted {
+    pub header: EventHeader,
     pub post: Pubkey,
     pub creator: Pubkey,
     pub nft_mint: Pubkey,
@@ -689,6 +966,7 @@ pub struct ViralNFTMinted {
 
 #[event]
 pub struct InfluenceLeaderboardUpdated {
+    pub header: EventHeader,
     pub leaderboard: Pubkey,
     pub season: u32,
     pub total_influencers: u32,
@@ -697,6 +975,7 @@ pub struct InfluenceLeaderboardUpdated {
 
 #[event]
 pub struct SeasonalRewardsDistributed {
+    pub header: EventHeader,
     pub season: u32,
     pub total_amount: u64,
     pub recipients: u32,
@@ -730,4 +1009,8 @@ pub enum SocialRewardsError {
     MismatchedRewardAmounts,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Alpha Pack protocol is paused")]
+    ProtocolPaused,
+    #[msg("Counter does not match on-chain state")]
+    InvalidCounter,
 }